@@ -0,0 +1,144 @@
+use crate::varslicedvec::VarSlicedVec;
+
+/// A directed graph in compressed sparse row form with staged edges.
+///
+/// Neighbor lists are the segments of a `VarSlicedVec<u32>`.
+/// New edges are buffered by `add_edge` and merged into the rows
+/// by `finalize` in a single linear pass, instead of shifting the
+/// storage for every edge. Edges of a node keep the order in which
+/// they were added.
+/// ```
+/// use sliced::*;
+/// let mut graph = AdjacencyList::new();
+/// graph.add_edge(0, 2);
+/// graph.add_edge(2, 1);
+/// graph.add_edge(0, 1);
+/// assert_eq!(graph.node_count(), 3);
+/// assert_eq!(graph.pending_len(), 3);
+/// graph.finalize();
+/// assert_eq!(graph.neighbors(0), [2, 1]);
+/// assert_eq!(graph.neighbors(1), []);
+/// graph.add_edge(1, 0);
+/// graph.finalize();
+/// assert_eq!(graph.neighbors(1), [0]);
+/// assert_eq!(graph.edge_count(), 4);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AdjacencyList {
+    rows: VarSlicedVec<u32>,
+    pending: Vec<(u32, u32)>,
+    node_count: usize,
+}
+
+impl AdjacencyList {
+    /// Construct an empty graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Construct a graph with `n` nodes and no edges.
+    pub fn with_nodes(n: usize) -> Self {
+        let mut graph = Self::new();
+        graph.add_nodes(n);
+        graph.finalize();
+        graph
+    }
+    /// Returns the number of nodes, including those only in staged edges.
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+    /// Returns the number of edges, including staged edges.
+    pub fn edge_count(&self) -> usize {
+        self.rows.storage_len() + self.pending.len()
+    }
+    /// Returns the number of staged edges.
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+    /// Test if there are no staged edges.
+    pub fn is_finalized(&self) -> bool {
+        self.pending.is_empty() && self.rows.len() == self.node_count
+    }
+    /// Add `n` nodes without edges.
+    pub fn add_nodes(&mut self, n: usize) {
+        self.node_count += n;
+    }
+    /// Stage a directed edge from `u` to `v`.
+    ///
+    /// The graph grows to include both nodes.
+    pub fn add_edge(&mut self, u: u32, v: u32) {
+        self.node_count = self.node_count.max(u.max(v) as usize + 1);
+        self.pending.push((u, v));
+    }
+    /// Merge staged edges into the rows.
+    ///
+    /// Runs in time linear in the number of nodes and edges.
+    pub fn finalize(&mut self) {
+        if self.is_finalized() {
+            return;
+        }
+        let n = self.node_count;
+        let mut offsets = vec![0; n + 1];
+        for (u, len) in self.rows.lengths_iter().enumerate() {
+            offsets[u + 1] = len;
+        }
+        for (u, _) in &self.pending {
+            offsets[*u as usize + 1] += 1;
+        }
+        for u in 0..n {
+            offsets[u + 1] += offsets[u];
+        }
+        let mut storage = vec![0; offsets[n]];
+        let mut fill = offsets.clone();
+        for (u, row) in self.rows.iter().enumerate() {
+            storage[fill[u]..fill[u] + row.len()].copy_from_slice(row);
+            fill[u] += row.len();
+        }
+        for (u, v) in self.pending.drain(..) {
+            storage[fill[u as usize]] = v;
+            fill[u as usize] += 1;
+        }
+        self.rows = VarSlicedVec::from_parts(storage, offsets);
+    }
+    /// Get the finalized neighbors of `u`.
+    ///
+    /// Staged edges are not visible until `finalize` is called.
+    /// # Panics
+    /// If `u` is out of range.
+    pub fn neighbors(&self, u: u32) -> &[u32] {
+        let u = u as usize;
+        assert!(u < self.node_count);
+        self.rows.get(u).unwrap_or(&[])
+    }
+    /// Iterate over the finalized edges.
+    pub fn edges(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.rows
+            .iter()
+            .enumerate()
+            .flat_map(|(u, row)| row.iter().map(move |v| (u as u32, *v)))
+    }
+    /// Get the finalized rows.
+    pub fn as_var_sliced_vec(&self) -> &VarSlicedVec<u32> {
+        &self.rows
+    }
+    /// Finalize and convert into the rows.
+    pub fn into_var_sliced_vec(mut self) -> VarSlicedVec<u32> {
+        self.finalize();
+        self.rows
+    }
+}
+
+/// Use the segments as the neighbor lists of nodes `0..len`.
+///
+/// # Panics
+/// If a neighbor is not a node.
+impl From<VarSlicedVec<u32>> for AdjacencyList {
+    fn from(rows: VarSlicedVec<u32>) -> Self {
+        let node_count = rows.len();
+        assert!(rows.iter().flatten().all(|v| (*v as usize) < node_count));
+        Self {
+            rows,
+            pending: Vec::new(),
+            node_count,
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use std::{ops::Range, sync::Arc};
+
+use arrow_array::{
+    cast::AsArray, types::ArrowPrimitiveType, Array, ArrayRef, FixedSizeListArray,
+    GenericListArray, LargeListArray, ListArray, OffsetSizeTrait, PrimitiveArray,
+};
+use arrow_buffer::{ArrowNativeType, OffsetBuffer, ScalarBuffer};
+use arrow_schema::Field;
+
+use crate::{offset::Offset, slicedvec::SlicedVec, varslicedvec::VarSlicedVec};
+
+impl<T, O> VarSlicedVec<T, O>
+where
+    T: Copy + Clone + ArrowNativeType,
+    O: Offset,
+{
+    /// Convert into an Arrow `ListArray`.
+    ///
+    /// The storage becomes the values buffer without copying.
+    /// Offsets are converted to `i32`.
+    ///
+    /// # Example
+    /// ```
+    /// use arrow_array::{types::Int32Type, Array};
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1, 2], [3], [4, 5, 6]];
+    /// let list = vv.into_list_array::<Int32Type>();
+    /// assert_eq!(list.len(), 3);
+    /// assert_eq!(list.value_offsets(), &[0, 2, 3, 6]);
+    /// ```
+    /// # Panics
+    /// If the storage length does not fit in an `i32`.
+    pub fn into_list_array<P>(self) -> ListArray
+    where
+        P: ArrowPrimitiveType<Native = T>,
+    {
+        self.into_generic_list_array::<P, i32>()
+    }
+    /// Convert into an Arrow `LargeListArray`.
+    ///
+    /// The storage becomes the values buffer without copying.
+    /// Offsets are converted to `i64`.
+    pub fn into_large_list_array<P>(self) -> LargeListArray
+    where
+        P: ArrowPrimitiveType<Native = T>,
+    {
+        self.into_generic_list_array::<P, i64>()
+    }
+    /// Initialize a `VarSlicedVec` from an Arrow list array.
+    ///
+    /// The values buffer is reused without copying if it is
+    /// not shared and not offset. Otherwise the covered values
+    /// are copied. Null list entries are read as whatever
+    /// range their offsets describe, which is usually empty.
+    ///
+    /// # Example
+    /// ```
+    /// use arrow_array::{types::Int32Type, ListArray};
+    /// use sliced::*;
+    /// let list = ListArray::from_iter_primitive::<Int32Type, _, _>([
+    ///     Some(vec![Some(1), Some(2)]),
+    ///     Some(vec![]),
+    ///     Some(vec![Some(3)]),
+    /// ]);
+    /// let vv = VarSlicedVec::<i32>::from_list_array::<Int32Type, _>(list);
+    /// assert_eq!(vv.lengths(), vec![2, 0, 1]);
+    /// assert_eq!(vv[2], [3]);
+    /// ```
+    /// # Panics
+    /// If the values are not of primitive type `P`.
+    pub fn from_list_array<P, S>(array: GenericListArray<S>) -> Self
+    where
+        P: ArrowPrimitiveType<Native = T>,
+        S: OffsetSizeTrait,
+    {
+        let (_, offsets, values, _) = array.into_parts();
+        let first = offsets[0].as_usize();
+        let last = offsets[offsets.len() - 1].as_usize();
+        let vv = Self {
+            storage: values_into_vec::<P>(values, first..last),
+            extents: offsets
+                .iter()
+                .map(|offset| O::from_usize(offset.as_usize() - first))
+                .collect(),
+        };
+        debug_assert!(vv.check_invariants());
+        vv
+    }
+    fn into_generic_list_array<P, S>(self) -> GenericListArray<S>
+    where
+        P: ArrowPrimitiveType<Native = T>,
+        S: OffsetSizeTrait,
+    {
+        let offsets = self
+            .extents
+            .iter()
+            .map(|offset| S::from_usize(offset.to_usize()).expect("offset overflow"))
+            .collect::<Vec<S>>();
+        let values = PrimitiveArray::<P>::new(ScalarBuffer::from(self.storage), None);
+        GenericListArray::new(
+            Arc::new(Field::new_list_field(P::DATA_TYPE, false)),
+            OffsetBuffer::new(ScalarBuffer::from(offsets)),
+            Arc::new(values),
+            None,
+        )
+    }
+}
+
+impl<T> SlicedVec<T>
+where
+    T: Copy + Clone + ArrowNativeType,
+{
+    /// Convert into an Arrow `FixedSizeListArray`.
+    ///
+    /// The storage becomes the values buffer without copying.
+    ///
+    /// # Example
+    /// ```
+    /// use arrow_array::{types::Float32Type, Array};
+    /// use sliced::*;
+    /// let sv = slicedvec![[1.0f32, 2.0], [3.0, 4.0]];
+    /// let list = sv.into_fixed_size_list_array::<Float32Type>();
+    /// assert_eq!(list.len(), 2);
+    /// assert_eq!(list.value_length(), 2);
+    /// ```
+    /// # Panics
+    /// If the segment length does not fit in an `i32`.
+    pub fn into_fixed_size_list_array<P>(self) -> FixedSizeListArray
+    where
+        P: ArrowPrimitiveType<Native = T>,
+    {
+        let size = i32::try_from(self.segment_len()).expect("segment length overflow");
+        let values = PrimitiveArray::<P>::new(ScalarBuffer::from(self.storage), None);
+        FixedSizeListArray::new(
+            Arc::new(Field::new_list_field(P::DATA_TYPE, false)),
+            size,
+            Arc::new(values),
+            None,
+        )
+    }
+    /// Initialize a `SlicedVec` from an Arrow `FixedSizeListArray`.
+    ///
+    /// The values buffer is reused without copying if it is
+    /// not shared and not offset.
+    ///
+    /// # Example
+    /// ```
+    /// use arrow_array::types::UInt8Type;
+    /// use sliced::*;
+    /// let list = slicedvec![[1u8, 2, 3], [4, 5, 6]].into_fixed_size_list_array::<UInt8Type>();
+    /// let sv = SlicedVec::from_fixed_size_list_array::<UInt8Type>(list);
+    /// assert_eq!(sv[1], [4, 5, 6]);
+    /// ```
+    /// # Panics
+    /// If the values are not of primitive type `P` or
+    /// the list size is zero.
+    pub fn from_fixed_size_list_array<P>(array: FixedSizeListArray) -> Self
+    where
+        P: ArrowPrimitiveType<Native = T>,
+    {
+        let len = array.len();
+        let (_, size, values, _) = array.into_parts();
+        let segment_len = size.as_usize();
+        SlicedVec::from_vec(
+            segment_len,
+            values_into_vec::<P>(values, 0..len * segment_len),
+        )
+    }
+}
+
+// Reuse the values allocation when possible
+fn values_into_vec<P>(values: ArrayRef, range: Range<usize>) -> Vec<P::Native>
+where
+    P: ArrowPrimitiveType,
+{
+    let array = values.as_primitive::<P>().clone();
+    drop(values);
+    let (_, buffer, _) = array.into_parts();
+    match buffer.into_inner().into_vec::<P::Native>() {
+        Ok(mut vec) => {
+            vec.truncate(range.end);
+            vec.drain(..range.start);
+            vec
+        }
+        Err(buffer) => buffer.typed_data::<P::Native>()[range].to_vec(),
+    }
+}
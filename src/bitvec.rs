@@ -0,0 +1,264 @@
+use crate::{slicedslab::OccupiedKeys, slicedvec::SlicedVec};
+
+/// A vector of fixed-length bitsets.
+///
+/// Each segment of `segment_bits` bits is packed into whole
+/// `u64` words stored contiguously in a `SlicedVec`, using one
+/// eighth of the memory of a `SlicedVec<u8>` of flags. Bits past
+/// the segment length in the last word are always zero.
+/// ```
+/// use sliced::*;
+/// let mut masks = SlicedBitVec::new(100);
+/// let a = masks.push_zeros();
+/// let b = masks.push_zeros();
+/// masks.get_mut(a).unwrap().set(3, true);
+/// masks.get_mut(a).unwrap().set(99, true);
+/// masks.get_mut(b).unwrap().set(99, true);
+/// assert!(masks.get(a).unwrap().get(99));
+/// assert_eq!(masks.get(a).unwrap().count_ones(), 2);
+/// let (mut left, right) = masks.get_pair_mut(a, b);
+/// left.and(&right.as_bits());
+/// assert_eq!(masks.get(a).unwrap().iter_ones().collect::<Vec<_>>(), [99]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SlicedBitVec {
+    words: SlicedVec<u64>,
+    segment_bits: usize,
+}
+
+impl SlicedBitVec {
+    /// Construct a `SlicedBitVec` of segments with `segment_bits` bits.
+    ///
+    /// # Panics
+    /// If `segment_bits` is zero.
+    pub fn new(segment_bits: usize) -> Self {
+        Self::with_capacity(segment_bits, 0)
+    }
+    /// Initialize a `SlicedBitVec` with room for `size` segments.
+    ///
+    /// # Panics
+    /// If `segment_bits` is zero.
+    pub fn with_capacity(segment_bits: usize, size: usize) -> Self {
+        assert_ne!(segment_bits, 0);
+        Self {
+            words: SlicedVec::with_capacity(segment_bits.div_ceil(64), size),
+            segment_bits,
+        }
+    }
+    /// Returns the number of segments.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+    /// Test if there are no segments.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+    /// Returns the number of bits in each segment.
+    pub fn segment_bits(&self) -> usize {
+        self.segment_bits
+    }
+    /// Append a segment with all bits clear and return its index.
+    pub fn push_zeros(&mut self) -> usize {
+        let len = self.words.storage.len() + self.words.segment_len();
+        self.words.storage.resize(len, 0);
+        self.len() - 1
+    }
+    /// Append a segment from booleans and return its index.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut bv = SlicedBitVec::new(3);
+    /// let index = bv.push_bools(&[true, false, true]);
+    /// assert_eq!(bv.get(index).unwrap().iter_ones().collect::<Vec<_>>(), [0, 2]);
+    /// ```
+    /// # Panics
+    /// If the length of `bits` is not `segment_bits`.
+    pub fn push_bools(&mut self, bits: &[bool]) -> usize {
+        assert_eq!(bits.len(), self.segment_bits);
+        let index = self.push_zeros();
+        let mut segment = self.get_mut(index).unwrap();
+        bits.iter()
+            .enumerate()
+            .for_each(|(bit, value)| segment.set(bit, *value));
+        index
+    }
+    /// Get a view of a segment.
+    pub fn get(&self, index: usize) -> Option<Bits<'_>> {
+        Some(Bits {
+            words: self.words.get(index)?,
+            len: self.segment_bits,
+        })
+    }
+    /// Get a mutable view of a segment.
+    pub fn get_mut(&mut self, index: usize) -> Option<BitsMut<'_>> {
+        Some(BitsMut {
+            words: self.words.get_mut(index)?,
+            len: self.segment_bits,
+        })
+    }
+    /// Get mutable views of two distinct segments.
+    ///
+    /// # Panics
+    /// If `a` equals `b` or either is out of range.
+    pub fn get_pair_mut(&mut self, a: usize, b: usize) -> (BitsMut<'_>, BitsMut<'_>) {
+        assert_ne!(a, b);
+        assert!(a < self.len() && b < self.len());
+        let width = self.words.segment_len();
+        let (low, high) = (a.min(b), a.max(b));
+        let (head, tail) = self.words.storage.split_at_mut(high * width);
+        let low_words = &mut head[low * width..(low + 1) * width];
+        let high_words = &mut tail[..width];
+        let (a_words, b_words) = if a < b {
+            (low_words, high_words)
+        } else {
+            (high_words, low_words)
+        };
+        let len = self.segment_bits;
+        (
+            BitsMut {
+                words: a_words,
+                len,
+            },
+            BitsMut {
+                words: b_words,
+                len,
+            },
+        )
+    }
+    /// Iterate over views of the segments.
+    pub fn iter(&self) -> impl Iterator<Item = Bits<'_>> {
+        let len = self.segment_bits;
+        self.words.iter().map(move |words| Bits { words, len })
+    }
+    /// Remove all segments.
+    pub fn clear(&mut self) {
+        self.words.clear()
+    }
+    /// Get the packed words of all segments.
+    pub fn as_words(&self) -> &SlicedVec<u64> {
+        &self.words
+    }
+}
+
+/// A view of one bitset segment of a `SlicedBitVec`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bits<'a> {
+    words: &'a [u64],
+    len: usize,
+}
+
+impl<'a> Bits<'a> {
+    /// Returns the number of bits.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Test if the segment has no bits.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Get a bit.
+    ///
+    /// # Panics
+    /// If `bit` is out of range.
+    pub fn get(&self, bit: usize) -> bool {
+        assert!(bit < self.len);
+        self.words[bit / 64] >> (bit % 64) & 1 == 1
+    }
+    /// Returns the number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+    /// Test if any bit is set.
+    pub fn any(&self) -> bool {
+        self.words.iter().any(|word| *word != 0)
+    }
+    /// Iterate over the positions of set bits.
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + 'a {
+        OccupiedKeys::new(self.words)
+    }
+    /// Get the packed words.
+    pub fn as_words(&self) -> &'a [u64] {
+        self.words
+    }
+}
+
+/// A mutable view of one bitset segment of a `SlicedBitVec`
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct BitsMut<'a> {
+    words: &'a mut [u64],
+    len: usize,
+}
+
+impl<'a> BitsMut<'a> {
+    /// Reborrow as an immutable view.
+    pub fn as_bits(&self) -> Bits<'_> {
+        Bits {
+            words: self.words,
+            len: self.len,
+        }
+    }
+    /// Get a bit.
+    ///
+    /// # Panics
+    /// If `bit` is out of range.
+    pub fn get(&self, bit: usize) -> bool {
+        self.as_bits().get(bit)
+    }
+    /// Returns the number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.as_bits().count_ones()
+    }
+    /// Set or clear a bit.
+    ///
+    /// # Panics
+    /// If `bit` is out of range.
+    pub fn set(&mut self, bit: usize, value: bool) {
+        assert!(bit < self.len);
+        let mask = 1 << (bit % 64);
+        if value {
+            self.words[bit / 64] |= mask;
+        } else {
+            self.words[bit / 64] &= !mask;
+        }
+    }
+    /// Clear all bits.
+    pub fn clear(&mut self) {
+        self.words.fill(0)
+    }
+    /// Keep only bits also set in `other`.
+    ///
+    /// # Panics
+    /// If the lengths differ.
+    pub fn and(&mut self, other: &Bits) {
+        self.combine(other, |a, b| a & b)
+    }
+    /// Set bits that are set in `other`.
+    ///
+    /// # Panics
+    /// If the lengths differ.
+    pub fn or(&mut self, other: &Bits) {
+        self.combine(other, |a, b| a | b)
+    }
+    /// Flip bits that are set in `other`.
+    ///
+    /// # Panics
+    /// If the lengths differ.
+    pub fn xor(&mut self, other: &Bits) {
+        self.combine(other, |a, b| a ^ b)
+    }
+    // Apply a word-wise operation with another segment
+    fn combine<F>(&mut self, other: &Bits, f: F)
+    where
+        F: Fn(u64, u64) -> u64,
+    {
+        assert_eq!(self.len, other.len);
+        self.words
+            .iter_mut()
+            .zip(other.words)
+            .for_each(|(a, b)| *a = f(*a, *b));
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{offset::Offset, varslicedvec::VarSlicedVec};
+
+/// Builder for bulk loading a `VarSlicedVec`.
+///
+/// Both the storage and the extents are allocated up front,
+/// so a load that stays within the hinted capacities never
+/// reallocates either buffer.
+/// ```
+/// use sliced::*;
+/// let mut builder = VarSlicedVecBuilder::with_capacities(3, 6);
+/// builder.push(&[1, 2]).push(&[3]).push(&[4, 5, 6]);
+/// let vv = builder.build();
+/// assert_eq!(vv.lengths(), vec![2, 1, 3]);
+/// assert!(vv.storage_capacity() >= 6);
+/// ```
+#[derive(Debug, Clone)]
+pub struct VarSlicedVecBuilder<T, O = usize>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    data: VarSlicedVec<T, O>,
+}
+
+impl<T> VarSlicedVecBuilder<T>
+where
+    T: Copy + Clone,
+{
+    /// Initialize a builder for `n_segments` segments
+    /// holding `n_elements` elements in total.
+    pub fn with_capacities(n_segments: usize, n_elements: usize) -> Self {
+        Self::with_offset_capacities(n_segments, n_elements)
+    }
+}
+
+impl<T, O> VarSlicedVecBuilder<T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    /// Initialize a builder with capacities and offset type `O`.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut builder = VarSlicedVecBuilder::<u8, u32>::with_offset_capacities(2, 4);
+    /// builder.push_from_iter(0..4);
+    /// assert_eq!(builder.build().offsets(), &[0u32, 4]);
+    /// ```
+    pub fn with_offset_capacities(n_segments: usize, n_elements: usize) -> Self {
+        let mut extents = Vec::with_capacity(n_segments + 1);
+        extents.push(O::from_usize(0));
+        Self {
+            data: VarSlicedVec {
+                storage: Vec::with_capacity(n_elements),
+                extents,
+            },
+        }
+    }
+    /// Add a segment to the end.
+    pub fn push(&mut self, segment: &[T]) -> &mut Self {
+        self.data.push(segment);
+        self
+    }
+    /// Add a segment by draining an iterator.
+    pub fn push_from_iter<I>(&mut self, iter: I) -> &mut Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.data.push_from_iter(iter);
+        self
+    }
+    /// Returns the number of segments added so far.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+    /// Test if no segments have been added.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+    /// Finish and return the `VarSlicedVec`.
+    pub fn build(self) -> VarSlicedVec<T, O> {
+        self.data
+    }
+}
@@ -0,0 +1,264 @@
+use std::fmt;
+
+use crate::slicedslab::SlicedSlab;
+
+// Marks the end of the access list
+const NIL: usize = usize::MAX;
+
+type EvictFn<T> = Box<dyn FnMut(usize, &[T])>;
+
+/// A capacity-bounded `SlicedSlab` with least-recently-used eviction.
+///
+/// Each occupied key is linked into an access list that is
+/// updated by `insert`, `get` and `get_mut`. Inserting into a
+/// full cache evicts the least recently used segment first,
+/// passing it to the eviction callback if one is set.
+/// ```
+/// use sliced::*;
+/// let mut cache = SlicedCache::new(2, 2);
+/// let a = cache.insert(&[1, 2]);
+/// let b = cache.insert(&[3, 4]);
+/// cache.get(a);
+/// let c = cache.insert(&[5, 6]);
+/// assert_eq!(c, b);
+/// assert_eq!(cache.peek(a), Some([1, 2].as_slice()));
+/// assert_eq!(cache.peek(b), Some([5, 6].as_slice()));
+/// assert_eq!(cache.lru_key(), Some(a));
+/// ```
+pub struct SlicedCache<T>
+where
+    T: Copy + Clone,
+{
+    slab: SlicedSlab<T>,
+    capacity: usize,
+    // Neighbors toward the most and least recently used ends
+    prev: Vec<usize>,
+    next: Vec<usize>,
+    // Most recently used
+    head: usize,
+    // Least recently used
+    tail: usize,
+    on_evict: Option<EvictFn<T>>,
+}
+
+impl<T> SlicedCache<T>
+where
+    T: Copy + Clone,
+{
+    /// Construct a new `SlicedCache` holding at most `capacity` segments.
+    ///
+    /// # Panics
+    /// If `segment_len` or `capacity` is zero.
+    pub fn new(segment_len: usize, capacity: usize) -> Self {
+        assert_ne!(capacity, 0);
+        Self {
+            slab: SlicedSlab::with_capacity(segment_len, capacity),
+            capacity,
+            prev: Vec::with_capacity(capacity),
+            next: Vec::with_capacity(capacity),
+            head: NIL,
+            tail: NIL,
+            on_evict: None,
+        }
+    }
+    /// Set a callback run on each segment evicted to make room
+    /// or by `evict_lru`.
+    ///
+    /// Segments removed with `release` are not passed to it.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// use std::{cell::RefCell, rc::Rc};
+    /// let evicted = Rc::new(RefCell::new(Vec::new()));
+    /// let log = evicted.clone();
+    /// let mut cache = SlicedCache::new(1, 1);
+    /// cache.set_on_evict(move |key, segment| log.borrow_mut().push((key, segment[0])));
+    /// cache.insert(&[7]);
+    /// cache.insert(&[8]);
+    /// assert_eq!(*evicted.borrow(), [(0, 7)]);
+    /// ```
+    pub fn set_on_evict<F>(&mut self, f: F)
+    where
+        F: FnMut(usize, &[T]) + 'static,
+    {
+        self.on_evict = Some(Box::new(f));
+    }
+    /// Remove the eviction callback.
+    pub fn clear_on_evict(&mut self) {
+        self.on_evict = None;
+    }
+    /// Returns the number of cached segments.
+    pub fn len(&self) -> usize {
+        self.slab.occupied_len()
+    }
+    /// Test if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the maximum number of cached segments.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.slab.segment_len()
+    }
+    /// Insert a segment as the most recently used.
+    ///
+    /// If the cache is full the least recently used segment
+    /// is evicted first and its key may be reused.
+    /// # Panics
+    /// If the length of the slice does
+    /// not match the segments size of the slab.
+    pub fn insert(&mut self, segment: &[T]) -> usize {
+        assert_eq!(segment.len(), self.segment_len());
+        if self.len() == self.capacity {
+            self.evict_lru(1);
+        }
+        let key = self.slab.insert(segment);
+        if key == self.prev.len() {
+            self.prev.push(NIL);
+            self.next.push(NIL);
+        }
+        self.push_front(key);
+        key
+    }
+    /// Get a segment and mark it as most recently used.
+    ///
+    /// Returns `None` if `key` is not cached.
+    pub fn get(&mut self, key: usize) -> Option<&[T]> {
+        self.touch(key)?;
+        self.slab.get(key)
+    }
+    /// Get a mutable segment and mark it as most recently used.
+    ///
+    /// Returns `None` if `key` is not cached.
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut [T]> {
+        self.touch(key)?;
+        self.slab.get_mut(key)
+    }
+    /// Get a segment without changing the access order.
+    pub fn peek(&self, key: usize) -> Option<&[T]> {
+        self.slab.get(key)
+    }
+    /// Test if `key` is cached.
+    pub fn contains_key(&self, key: usize) -> bool {
+        self.slab.contains_key(key)
+    }
+    /// Returns the least recently used key.
+    pub fn lru_key(&self) -> Option<usize> {
+        (self.tail != NIL).then_some(self.tail)
+    }
+    /// Remove a segment without running the eviction callback.
+    ///
+    /// Returns `false` if `key` is not cached.
+    pub fn release(&mut self, key: usize) -> bool {
+        if !self.contains_key(key) {
+            return false;
+        }
+        self.unlink(key);
+        self.slab.release(key);
+        true
+    }
+    /// Evict up to `n` of the least recently used segments.
+    ///
+    /// Returns the number evicted.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut cache = SlicedCache::new(1, 4);
+    /// let keys: Vec<_> = (0..4).map(|i| cache.insert(&[i])).collect();
+    /// cache.get(keys[0]);
+    /// assert_eq!(cache.evict_lru(2), 2);
+    /// assert!(cache.contains_key(keys[0]));
+    /// assert!(cache.contains_key(keys[3]));
+    /// assert_eq!(cache.evict_lru(5), 2);
+    /// assert!(cache.is_empty());
+    /// ```
+    pub fn evict_lru(&mut self, n: usize) -> usize {
+        let mut evicted = 0;
+        while evicted < n && self.tail != NIL {
+            let key = self.tail;
+            self.unlink(key);
+            if let Some(f) = self.on_evict.as_mut() {
+                f(key, &self.slab[key]);
+            }
+            self.slab.release(key);
+            evicted += 1;
+        }
+        evicted
+    }
+    /// Iterate over keys from most to least recently used.
+    pub fn iter_keys(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut key = self.head;
+        std::iter::from_fn(move || {
+            let current = key;
+            (current != NIL).then(|| {
+                key = self.next[current];
+                current
+            })
+        })
+    }
+    /// Remove all segments without running the eviction callback.
+    pub fn clear(&mut self) {
+        self.slab.clear();
+        self.prev.clear();
+        self.next.clear();
+        self.head = NIL;
+        self.tail = NIL;
+    }
+    /// Get a reference to the underlying slab.
+    pub fn as_slab(&self) -> &SlicedSlab<T> {
+        &self.slab
+    }
+    // Move an occupied key to the front of the access list
+    fn touch(&mut self, key: usize) -> Option<()> {
+        if !self.contains_key(key) {
+            return None;
+        }
+        if self.head != key {
+            self.unlink(key);
+            self.push_front(key);
+        }
+        Some(())
+    }
+    // Link a key in as most recently used
+    fn push_front(&mut self, key: usize) {
+        self.prev[key] = NIL;
+        self.next[key] = self.head;
+        if self.head != NIL {
+            self.prev[self.head] = key;
+        } else {
+            self.tail = key;
+        }
+        self.head = key;
+    }
+    // Remove a key from the access list
+    fn unlink(&mut self, key: usize) {
+        let (prev, next) = (self.prev[key], self.next[key]);
+        if prev != NIL {
+            self.next[prev] = next;
+        } else {
+            self.head = next;
+        }
+        if next != NIL {
+            self.prev[next] = prev;
+        } else {
+            self.tail = prev;
+        }
+    }
+}
+
+impl<T> fmt::Debug for SlicedCache<T>
+where
+    T: Copy + Clone + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SlicedCache")
+            .field("slab", &self.slab)
+            .field("capacity", &self.capacity)
+            .field("head", &self.head)
+            .field("tail", &self.tail)
+            .finish_non_exhaustive()
+    }
+}
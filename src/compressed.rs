@@ -0,0 +1,165 @@
+use crate::varslicedvec::VarSlicedVec;
+
+/// A variable length segmented vector of compressed `u32` values.
+///
+/// Each segment is delta-encoded and the deltas are written as
+/// LEB128 varints, so sorted runs such as document posting lists
+/// typically use one or two bytes per value. Unsorted segments are
+/// supported but compress poorly. Segments are decoded on the fly.
+/// ```
+/// use sliced::*;
+/// let mut cv = CompressedVarSlicedVec::new();
+/// cv.push(&[3, 7, 8, 200, 1000]);
+/// cv.push(&[]);
+/// cv.push(&[42]);
+/// assert_eq!(cv.len(), 3);
+/// assert_eq!(cv.storage_len(), 8);
+/// assert_eq!(cv.get(0), Some(vec![3, 7, 8, 200, 1000]));
+/// assert_eq!(cv.iter().map(|s| s.count()).collect::<Vec<_>>(), vec![5, 0, 1]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct CompressedVarSlicedVec {
+    bytes: VarSlicedVec<u8>,
+}
+
+impl CompressedVarSlicedVec {
+    /// Initialize a `CompressedVarSlicedVec`.
+    pub fn new() -> Self {
+        Self {
+            bytes: VarSlicedVec::new(),
+        }
+    }
+    /// Initialize a `CompressedVarSlicedVec` and set the byte capacity.
+    pub fn with_capacity(size: usize) -> Self {
+        Self {
+            bytes: VarSlicedVec::with_capacity(size),
+        }
+    }
+    /// Compress and add a segment to the end.
+    ///
+    /// Complexity is linear in the segment length.
+    pub fn push(&mut self, segment: &[u32]) {
+        let mut prev = 0u32;
+        self.bytes.push_from_iter(segment.iter().flat_map(|&value| {
+            let delta = value.wrapping_sub(prev);
+            prev = value;
+            Varint::new(delta)
+        }));
+    }
+    /// Decode a segment.
+    ///
+    /// Returns `None` if `index` is out of range.
+    pub fn get(&self, index: usize) -> Option<Vec<u32>> {
+        Some(self.decode(index)?.collect())
+    }
+    /// Decode a segment into `buf`.
+    ///
+    /// `buf` is cleared first. Returns the number of values
+    /// decoded or `None` if `index` is out of range.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut cv = CompressedVarSlicedVec::new();
+    /// cv.push(&[1, 2, 3]);
+    /// let mut buf = Vec::new();
+    /// assert_eq!(cv.decode_into(0, &mut buf), Some(3));
+    /// assert_eq!(buf, [1, 2, 3]);
+    /// assert_eq!(cv.decode_into(1, &mut buf), None);
+    /// ```
+    pub fn decode_into(&self, index: usize, buf: &mut Vec<u32>) -> Option<usize> {
+        let decoder = self.decode(index)?;
+        buf.clear();
+        buf.extend(decoder);
+        Some(buf.len())
+    }
+    /// Return a decoding iterator over a segment.
+    ///
+    /// Returns `None` if `index` is out of range.
+    pub fn decode(&self, index: usize) -> Option<DeltaDecoder<'_>> {
+        Some(DeltaDecoder::new(self.bytes.get(index)?))
+    }
+    /// Return an iterator of decoding iterators, one per segment.
+    pub fn iter(&self) -> impl Iterator<Item = DeltaDecoder<'_>> {
+        self.bytes.iter().map(DeltaDecoder::new)
+    }
+    /// Returns the number of segments.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+    /// Test if there are no segments.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+    /// Returns the number of bytes of compressed storage.
+    pub fn storage_len(&self) -> usize {
+        *self.bytes.offsets().last().unwrap()
+    }
+    /// Clear the contents.
+    pub fn clear(&mut self) {
+        self.bytes.clear()
+    }
+    /// Shrink the storage to fit data.
+    pub fn shrink_to_fit(&mut self) {
+        self.bytes.shrink_to_fit()
+    }
+}
+
+/// Iterator decoding one compressed segment
+#[derive(Debug, Clone)]
+pub struct DeltaDecoder<'a> {
+    bytes: &'a [u8],
+    prev: u32,
+}
+
+impl<'a> DeltaDecoder<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, prev: 0 }
+    }
+}
+
+impl<'a> Iterator for DeltaDecoder<'a> {
+    type Item = u32;
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut delta = 0u32;
+        let mut shift = 0;
+        loop {
+            let (&byte, rest) = self.bytes.split_first()?;
+            self.bytes = rest;
+            delta |= u32::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+        }
+        self.prev = self.prev.wrapping_add(delta);
+        Some(self.prev)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Each value takes between one and five bytes
+        (self.bytes.len().div_ceil(5), Some(self.bytes.len()))
+    }
+}
+
+// LEB128 encoding of a single value
+struct Varint(Option<u32>);
+
+impl Varint {
+    fn new(value: u32) -> Self {
+        Self(Some(value))
+    }
+}
+
+impl Iterator for Varint {
+    type Item = u8;
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.0?;
+        if value < 0x80 {
+            self.0 = None;
+            Some(value as u8)
+        } else {
+            self.0 = Some(value >> 7);
+            Some((value as u8 & 0x7f) | 0x80)
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ptr,
+    sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
+};
+
+use crate::slicedvec::SlicedVec;
+
+/// An append-only segmented vector that threads push to concurrently.
+///
+/// A push claims a segment index with one atomic increment and
+/// copies into a block reserved for that index, so pushes never
+/// take a lock. The first block holds the capacity requested
+/// up front and each later block doubles in size. Blocks are
+/// allocated on first use and never move, so earlier segments
+/// stay in place as the vector grows. When all threads are done,
+/// `into_sliced_vec` freezes the contents into a `SlicedVec`,
+/// without copying if they fit in the first block.
+/// ```
+/// use sliced::*;
+/// let results = ConcurrentSlicedVec::with_capacity(2, 400);
+/// std::thread::scope(|s| {
+///     for t in 0..4 {
+///         let results = &results;
+///         s.spawn(move || {
+///             for n in 0..100 {
+///                 results.push(&[t, n]);
+///             }
+///         });
+///     }
+/// });
+/// let mut frozen: Vec<_> = results.into_sliced_vec().iter().map(|s| s.to_vec()).collect();
+/// frozen.sort();
+/// assert_eq!(frozen.len(), 400);
+/// assert_eq!(frozen[101], [1, 1]);
+/// ```
+pub struct ConcurrentSlicedVec<T>
+where
+    T: Copy + Clone,
+{
+    blocks: Box<[AtomicPtr<MaybeUninit<T>>]>,
+    len: AtomicUsize,
+    segment_len: usize,
+    // Number of segments in the first block
+    first_block: usize,
+    marker: PhantomData<T>,
+}
+
+// Safety: each pushed value is written by one thread and
+// only read after the vector is moved or borrowed mutably
+unsafe impl<T> Sync for ConcurrentSlicedVec<T> where T: Copy + Clone + Send {}
+
+impl<T> ConcurrentSlicedVec<T>
+where
+    T: Copy + Clone,
+{
+    /// Construct an empty vector.
+    ///
+    /// Nothing is allocated until the first push.
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn new(segment_len: usize) -> Self {
+        Self::with_first_block(segment_len, 16)
+    }
+    /// Construct a vector with room for `size` segments.
+    ///
+    /// The first block is allocated now, so up to `size`
+    /// pushes never allocate.
+    /// # Panics
+    /// If `segment_len` or `size` is zero.
+    pub fn with_capacity(segment_len: usize, size: usize) -> Self {
+        let vec = Self::with_first_block(segment_len, size);
+        vec.block_ptr(0);
+        vec
+    }
+    // Empty vector whose first block holds `first_block` segments
+    fn with_first_block(segment_len: usize, first_block: usize) -> Self {
+        assert_ne!(segment_len, 0);
+        assert_ne!(first_block, 0);
+        Self {
+            blocks: (0..usize::BITS)
+                .map(|_| AtomicPtr::new(ptr::null_mut()))
+                .collect(),
+            len: AtomicUsize::new(0),
+            segment_len,
+            first_block,
+            marker: PhantomData,
+        }
+    }
+    /// Returns the number of segments pushed.
+    ///
+    /// Includes pushes still in progress on other threads.
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+    /// Test if there are no segments.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.segment_len
+    }
+    /// Append a segment and return its index.
+    ///
+    /// Indices are unique but segments from different
+    /// threads are interleaved in no particular order.
+    /// # Panics
+    /// If the length of the slice does
+    /// not match the segments size of the vector.
+    pub fn push(&self, segment: &[T]) -> usize {
+        assert_eq!(segment.len(), self.segment_len);
+        let index = self.len.fetch_add(1, Ordering::Relaxed);
+        let (block, offset) = self.locate(index);
+        let base = self.block_ptr(block) as *mut T;
+        // Safety: the index was claimed by this push alone and
+        // the segment lies within the block
+        unsafe {
+            ptr::copy_nonoverlapping(
+                segment.as_ptr(),
+                base.add(offset * self.segment_len),
+                self.segment_len,
+            );
+        }
+        index
+    }
+    /// Convert into a `SlicedVec` in push order.
+    pub fn into_sliced_vec(mut self) -> SlicedVec<T> {
+        let len = *self.len.get_mut();
+        let block = self.blocks[0].swap(ptr::null_mut(), Ordering::Relaxed);
+        if len <= self.first_block {
+            if block.is_null() {
+                return SlicedVec::new(self.segment_len);
+            }
+            // Safety: the first block was allocated with room for
+            // `first_block` segments and the first `len` are written
+            let storage = unsafe {
+                Vec::from_raw_parts(
+                    block as *mut T,
+                    len * self.segment_len,
+                    self.first_block * self.segment_len,
+                )
+            };
+            return SlicedVec::from_vec(self.segment_len, storage);
+        }
+        self.blocks[0].store(block, Ordering::Relaxed);
+        let mut storage = Vec::with_capacity(len * self.segment_len);
+        let mut remaining = len;
+        for (b, block) in self.blocks.iter_mut().enumerate() {
+            if remaining == 0 {
+                break;
+            }
+            let count = remaining.min(self.first_block << b);
+            // Safety: every claimed index was written before
+            // the vector was moved here
+            storage.extend_from_slice(unsafe {
+                std::slice::from_raw_parts(*block.get_mut() as *const T, count * self.segment_len)
+            });
+            remaining -= count;
+        }
+        SlicedVec::from_vec(self.segment_len, storage)
+    }
+    // Block and segment offset within it of a segment index
+    fn locate(&self, index: usize) -> (usize, usize) {
+        let block = (index / self.first_block + 1).ilog2() as usize;
+        (block, index - self.first_block * ((1 << block) - 1))
+    }
+    // Storage of a block, allocating it on first use
+    fn block_ptr(&self, block: usize) -> *mut MaybeUninit<T> {
+        let current = self.blocks[block].load(Ordering::Acquire);
+        if !current.is_null() {
+            return current;
+        }
+        let len = (self.first_block << block) * self.segment_len;
+        let fresh = Box::into_raw(Box::<[T]>::new_uninit_slice(len)) as *mut MaybeUninit<T>;
+        match self.blocks[block].compare_exchange(
+            ptr::null_mut(),
+            fresh,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => fresh,
+            Err(winner) => {
+                // Safety: `fresh` was never shared
+                drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(fresh, len)) });
+                winner
+            }
+        }
+    }
+}
+
+impl<T> Drop for ConcurrentSlicedVec<T>
+where
+    T: Copy + Clone,
+{
+    fn drop(&mut self) {
+        for (b, block) in self.blocks.iter_mut().enumerate() {
+            let block = *block.get_mut();
+            if !block.is_null() {
+                let len = (self.first_block << b) * self.segment_len;
+                // Safety: allocated in `block_ptr` with this length
+                drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(block, len)) });
+            }
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for ConcurrentSlicedVec<T>
+where
+    T: Copy + Clone,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConcurrentSlicedVec")
+            .field("len", &self.len())
+            .field("segment_len", &self.segment_len)
+            .finish_non_exhaustive()
+    }
+}
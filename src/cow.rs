@@ -0,0 +1,213 @@
+use std::{
+    ops::{Index, IndexMut},
+    sync::Arc,
+};
+
+use crate::slicedvec::SlicedVec;
+
+/// A copy-on-write segmented vector.
+///
+/// The storage is shared through an `Arc`, so `clone` is a
+/// reference count increment and clones can be sent to other
+/// threads as read snapshots. The first mutation of a shared
+/// vector copies the buffer; later mutations are in place
+/// until the vector is cloned again.
+/// ```
+/// use sliced::*;
+/// let mut live = CowSlicedVec::from(slicedvec![[1, 2], [3, 4]]);
+/// let snapshot = live.clone();
+/// assert!(live.is_shared());
+/// live[0][0] = 10;
+/// live.push(&[5, 6]);
+/// assert!(!live.is_shared());
+/// assert_eq!(snapshot.len(), 2);
+/// assert_eq!(snapshot[0], [1, 2]);
+/// assert_eq!(live[0], [10, 2]);
+/// let handle = std::thread::spawn(move || snapshot.iter().flatten().sum::<i32>());
+/// assert_eq!(handle.join().unwrap(), 10);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CowSlicedVec<T>
+where
+    T: Copy + Clone,
+{
+    storage: Arc<Vec<T>>,
+    segment_len: usize,
+}
+
+impl<T> CowSlicedVec<T>
+where
+    T: Copy + Clone,
+{
+    /// Construct an empty `CowSlicedVec`.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn new(segment_len: usize) -> Self {
+        Self::with_capacity(segment_len, 0)
+    }
+    /// Construct a `CowSlicedVec` with room for `size` segments.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn with_capacity(segment_len: usize, size: usize) -> Self {
+        assert_ne!(segment_len, 0);
+        Self {
+            storage: Arc::new(Vec::with_capacity(segment_len * size)),
+            segment_len,
+        }
+    }
+    /// Returns the number of segments.
+    pub fn len(&self) -> usize {
+        self.storage.len() / self.segment_len
+    }
+    /// Test if there are no segments.
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.segment_len
+    }
+    /// Test if the storage is shared with a clone.
+    ///
+    /// If shared, the next mutation copies the storage.
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.storage) > 1
+    }
+    /// Test if two vectors share the same storage.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.storage, &other.storage)
+    }
+    /// Get a reference to a segment.
+    ///
+    /// Returns `None` if `index` is out of range.
+    pub fn get(&self, index: usize) -> Option<&[T]> {
+        self.storage
+            .get(index * self.segment_len..(index + 1) * self.segment_len)
+    }
+    /// Get a mutable reference to a segment.
+    ///
+    /// Copies the storage if it is shared.
+    /// Returns `None` if `index` is out of range.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut [T]> {
+        if index >= self.len() {
+            return None;
+        }
+        let range = index * self.segment_len..(index + 1) * self.segment_len;
+        Some(&mut self.make_mut()[range])
+    }
+    /// Iterate over the segments.
+    pub fn iter(&self) -> impl Iterator<Item = &[T]> {
+        self.storage.chunks_exact(self.segment_len)
+    }
+    /// Iterate mutably over the segments.
+    ///
+    /// Copies the storage if it is shared.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        let segment_len = self.segment_len;
+        self.make_mut().chunks_exact_mut(segment_len)
+    }
+    /// Append one or more segments.
+    ///
+    /// Copies the storage if it is shared.
+    /// # Panics
+    /// If the length of the slice is not
+    /// a multiple of the segment length.
+    pub fn push(&mut self, segment: &[T]) {
+        assert_eq!(segment.len() % self.segment_len, 0);
+        self.make_mut().extend_from_slice(segment)
+    }
+    /// Remove and return the last segment.
+    ///
+    /// Copies the storage if it is shared.
+    /// Returns `None` if empty.
+    pub fn pop(&mut self) -> Option<Vec<T>> {
+        if self.is_empty() {
+            return None;
+        }
+        let begin = self.storage.len() - self.segment_len;
+        Some(self.make_mut().split_off(begin))
+    }
+    /// Remove and return a segment, replacing it with the last.
+    ///
+    /// Copies the storage if it is shared.
+    /// # Panics
+    /// If `index` is out of range.
+    pub fn swap_remove(&mut self, index: usize) -> Vec<T> {
+        assert!(index < self.len());
+        let last = self.len() - 1;
+        let segment_len = self.segment_len;
+        let storage = self.make_mut();
+        if index != last {
+            let (head, tail) = storage.split_at_mut(last * segment_len);
+            head[index * segment_len..(index + 1) * segment_len].swap_with_slice(tail);
+        }
+        storage.split_off(last * segment_len)
+    }
+    /// Remove all segments.
+    ///
+    /// A shared storage is released rather than copied.
+    pub fn clear(&mut self) {
+        match Arc::get_mut(&mut self.storage) {
+            Some(storage) => storage.clear(),
+            None => self.storage = Arc::new(Vec::new()),
+        }
+    }
+    /// Copy the contents into a `SlicedVec`.
+    pub fn to_sliced_vec(&self) -> SlicedVec<T> {
+        SlicedVec::from_vec(self.segment_len, self.storage.to_vec())
+    }
+    /// Convert into a `SlicedVec`.
+    ///
+    /// The storage is copied only if it is shared.
+    pub fn into_sliced_vec(self) -> SlicedVec<T> {
+        let storage = Arc::try_unwrap(self.storage).unwrap_or_else(|shared| shared.to_vec());
+        SlicedVec::from_vec(self.segment_len, storage)
+    }
+    // Unique access to the storage, copying it if shared
+    fn make_mut(&mut self) -> &mut Vec<T> {
+        Arc::make_mut(&mut self.storage)
+    }
+}
+
+/// Take ownership of the storage without copying.
+impl<T> From<SlicedVec<T>> for CowSlicedVec<T>
+where
+    T: Copy + Clone,
+{
+    fn from(data: SlicedVec<T>) -> Self {
+        Self {
+            segment_len: data.segment_len(),
+            storage: Arc::new(data.storage),
+        }
+    }
+}
+
+/// Get a segment by position.
+///
+/// # Panics
+/// If `index` is out of range.
+impl<T> Index<usize> for CowSlicedVec<T>
+where
+    T: Copy + Clone,
+{
+    type Output = [T];
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("index out of range")
+    }
+}
+
+/// Get a mutable segment by position.
+///
+/// Copies the storage if it is shared.
+/// # Panics
+/// If `index` is out of range.
+impl<T> IndexMut<usize> for CowSlicedVec<T>
+where
+    T: Copy + Clone,
+{
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("index out of range")
+    }
+}
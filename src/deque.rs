@@ -0,0 +1,213 @@
+use std::ops::Index;
+
+use crate::{varslicedslice::VarSlicedSliceIter, varslicedvec::VarSlicedVec};
+
+/// A variable length segmented queue.
+///
+/// Segments are pushed at the back and popped from either end.
+/// Popping from the front advances a start index instead of
+/// moving data. Once the consumed segments outnumber the live
+/// ones the storage is compacted, so `pop_front` is amortized
+/// constant time per element.
+/// ```
+/// use sliced::*;
+/// let mut vd = VarSlicedDeque::new();
+/// vd.push_back(&[1, 2]);
+/// vd.push_back(&[3]);
+/// vd.push_back(&[4, 5, 6]);
+/// assert_eq!(vd.pop_front(), Some(vec![1, 2]));
+/// assert_eq!(vd.front(), Some([3].as_slice()));
+/// assert_eq!(vd.len(), 2);
+/// assert_eq!(vd[1], [4, 5, 6]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct VarSlicedDeque<T>
+where
+    T: Copy + Clone,
+{
+    data: VarSlicedVec<T>,
+    head: usize,
+}
+
+impl<T> VarSlicedDeque<T>
+where
+    T: Copy + Clone,
+{
+    /// Initialize a `VarSlicedDeque`.
+    pub fn new() -> Self {
+        Self {
+            data: VarSlicedVec::new(),
+            head: 0,
+        }
+    }
+    /// Initialize a `VarSlicedDeque` and set the capacity.
+    pub fn with_capacity(size: usize) -> Self {
+        Self {
+            data: VarSlicedVec::with_capacity(size),
+            head: 0,
+        }
+    }
+    /// Add a segment to the back.
+    pub fn push_back(&mut self, segment: &[T]) {
+        self.data.push(segment)
+    }
+    /// Remove and return the front segment.
+    ///
+    /// Returns `None` if empty.
+    pub fn pop_front(&mut self) -> Option<Vec<T>> {
+        let segment = self.front()?.to_vec();
+        self.advance();
+        Some(segment)
+    }
+    /// Remove the front segment and write it into `buf`.
+    ///
+    /// `buf` is cleared first. Returns the segment length or
+    /// `None` if empty. No allocation occurs once `buf` has
+    /// grown to the largest segment.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vd: VarSlicedDeque<i32> = varslicedvec![[1, 2], [3]].into();
+    /// let mut buf = Vec::new();
+    /// assert_eq!(vd.pop_front_into(&mut buf), Some(2));
+    /// assert_eq!(buf, [1, 2]);
+    /// assert_eq!(vd.pop_front_into(&mut buf), Some(1));
+    /// assert_eq!(vd.pop_front_into(&mut buf), None);
+    /// ```
+    pub fn pop_front_into(&mut self, buf: &mut Vec<T>) -> Option<usize> {
+        let segment = self.front()?;
+        buf.clear();
+        buf.extend_from_slice(segment);
+        self.advance();
+        Some(buf.len())
+    }
+    /// Remove and return the back segment.
+    ///
+    /// Returns `None` if empty.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vd: VarSlicedDeque<i32> = varslicedvec![[1, 2], [3]].into();
+    /// assert_eq!(vd.pop_back(), Some(vec![3]));
+    /// assert_eq!(vd.pop_front(), Some(vec![1, 2]));
+    /// assert_eq!(vd.pop_back(), None);
+    /// ```
+    pub fn pop_back(&mut self) -> Option<Vec<T>> {
+        if self.is_empty() {
+            return None;
+        }
+        let segment = self.data.pop();
+        if self.is_empty() {
+            self.clear();
+        }
+        segment
+    }
+    /// Get a reference to the front segment.
+    ///
+    /// Returns `None` if empty.
+    pub fn front(&self) -> Option<&[T]> {
+        self.data.get(self.head)
+    }
+    /// Get a reference to the back segment.
+    ///
+    /// Returns `None` if empty.
+    pub fn back(&self) -> Option<&[T]> {
+        if self.is_empty() {
+            None
+        } else {
+            self.data.last()
+        }
+    }
+    /// Get a reference to a segment counting from the front.
+    ///
+    /// Returns `None` if `index` is out of range.
+    pub fn get(&self, index: usize) -> Option<&[T]> {
+        self.data.get(self.head.checked_add(index)?)
+    }
+    /// Returns the number of segments.
+    pub fn len(&self) -> usize {
+        self.data.len() - self.head
+    }
+    /// Test if there are no segments.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Clear the contents.
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.head = 0;
+    }
+    /// Return iterator over slices from front to back.
+    pub fn iter(&self) -> VarSlicedSliceIter<'_, T> {
+        self.data.as_slice().split_at(self.head).1.iter()
+    }
+    /// Convert into a `VarSlicedVec` holding the live segments.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vd = VarSlicedDeque::new();
+    /// vd.push_back(&[1]);
+    /// vd.push_back(&[2, 3]);
+    /// vd.pop_front();
+    /// assert_eq!(vd.into_var_sliced_vec(), varslicedvec![[2, 3]]);
+    /// ```
+    pub fn into_var_sliced_vec(mut self) -> VarSlicedVec<T> {
+        self.compact();
+        self.data
+    }
+    // Drop the front segment, compacting when mostly consumed
+    fn advance(&mut self) {
+        self.head += 1;
+        if self.is_empty() {
+            self.clear();
+        } else if self.head >= self.len() {
+            self.compact();
+        }
+    }
+    // Move live segments to the start of storage
+    fn compact(&mut self) {
+        if self.head == 0 {
+            return;
+        }
+        let base = self.data.extents[self.head];
+        self.data.storage.drain(..base);
+        self.data.extents.drain(..self.head);
+        self.data
+            .extents
+            .iter_mut()
+            .for_each(|extent| *extent -= base);
+        self.head = 0;
+        debug_assert!(self.data.check_invariants());
+    }
+}
+
+impl<T> Default for VarSlicedDeque<T>
+where
+    T: Copy + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<VarSlicedVec<T>> for VarSlicedDeque<T>
+where
+    T: Copy + Clone,
+{
+    fn from(data: VarSlicedVec<T>) -> Self {
+        Self { data, head: 0 }
+    }
+}
+
+impl<T> Index<usize> for VarSlicedDeque<T>
+where
+    T: Copy + Clone,
+{
+    type Output = [T];
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.data[self.head + index]
+    }
+}
@@ -0,0 +1,455 @@
+use std::{
+    error::Error,
+    fmt,
+    io::{self, Read, Write},
+    mem, slice,
+};
+
+use crate::{
+    offset::Offset, slicedslab::SlicedSlab, slicedvec::SlicedVec, varslicedvec::VarSlicedVec,
+};
+
+/// Plain data that may be read from and written to raw bytes.
+///
+/// # Safety
+/// Implementors must be non-zero-sized, contain no padding and
+/// no pointers, and every bit pattern must be a valid value.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($t:ty),*) => {
+        $(unsafe impl Pod for $t {})*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+/// Error reading the binary format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatError {
+    /// The magic bytes do not match the container type.
+    Magic,
+    /// The format version is not supported.
+    Version(u32),
+    /// The data was written with a different byte order.
+    Endianness,
+    /// The element size does not match `size_of::<T>()`.
+    ElementSize(u32),
+    /// The buffer is shorter than the header describes.
+    Truncated,
+    /// The buffer is not aligned for the element type.
+    Misaligned,
+    /// The segment offsets or lengths are inconsistent.
+    Invalid,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Magic => write!(f, "unrecognized magic bytes"),
+            Self::Version(version) => write!(f, "unsupported format version {}", version),
+            Self::Endianness => write!(f, "data has foreign byte order"),
+            Self::ElementSize(size) => write!(f, "unexpected element size {}", size),
+            Self::Truncated => write!(f, "buffer is truncated"),
+            Self::Misaligned => write!(f, "buffer is misaligned for element type"),
+            Self::Invalid => write!(f, "invalid segment layout"),
+        }
+    }
+}
+
+impl Error for FormatError {}
+
+/// Report a format error as `InvalidData`.
+impl From<FormatError> for io::Error {
+    fn from(err: FormatError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+pub(crate) const VERSION: u32 = 1;
+pub(crate) const BYTE_ORDER_MARK: u32 = 0x0102_0304;
+pub(crate) const HEADER_LEN: usize = 40;
+pub(crate) const SLICED_MAGIC: [u8; 8] = *b"SLICEDF\0";
+pub(crate) const VARSLICED_MAGIC: [u8; 8] = *b"SLICEDV\0";
+pub(crate) const SLAB_MAGIC: [u8; 8] = *b"SLICEDS\0";
+pub(crate) const STREAM_MAGIC: [u8; 8] = *b"SLICEDW\0";
+
+/// Header shared by all containers.
+///
+/// All fields are in native byte order. The byte order mark lets
+/// readers reject data written on a machine of the other endianness.
+/// ```text
+/// offset  size  field
+///      0     8  magic
+///      8     4  version
+///     12     4  byte order mark 0x01020304
+///     16     4  element size in bytes
+///     20     4  reserved, zero
+///     24     8  first parameter (segment length or segment count)
+///     32     8  second parameter (segment count or storage length)
+/// ```
+/// The header is followed by the data of each container:
+/// ```text
+/// SlicedVec     "SLICEDF"  segment length, segment count
+///               storage
+/// VarSlicedVec  "SLICEDV"  segment count, storage length
+///               segment count + 1 offsets as u64, storage
+/// SlicedSlab    "SLICEDS"  segment length, slot count
+///               open slot count as u64, open keys as u64
+///               in free list order, occupied segments in key order
+/// stream        "SLICEDW"  segment length, zero
+///               segments until the end of the stream
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Header {
+    pub(crate) magic: [u8; 8],
+    pub(crate) elem_size: u32,
+    pub(crate) params: [u64; 2],
+}
+
+impl Header {
+    pub(crate) fn new<T>(magic: [u8; 8], params: [u64; 2]) -> Self {
+        Self {
+            magic,
+            elem_size: mem::size_of::<T>() as u32,
+            params,
+        }
+    }
+    pub(crate) fn to_bytes(self) -> [u8; HEADER_LEN] {
+        let mut bytes = [0u8; HEADER_LEN];
+        bytes[0..8].copy_from_slice(&self.magic);
+        bytes[8..12].copy_from_slice(&VERSION.to_ne_bytes());
+        bytes[12..16].copy_from_slice(&BYTE_ORDER_MARK.to_ne_bytes());
+        bytes[16..20].copy_from_slice(&self.elem_size.to_ne_bytes());
+        bytes[24..32].copy_from_slice(&self.params[0].to_ne_bytes());
+        bytes[32..40].copy_from_slice(&self.params[1].to_ne_bytes());
+        bytes
+    }
+    // Check magic, version, byte order and element size
+    pub(crate) fn read<T>(bytes: &[u8], magic: [u8; 8]) -> Result<Self, FormatError> {
+        let bytes = bytes.get(..HEADER_LEN).ok_or(FormatError::Truncated)?;
+        if bytes[0..8] != magic {
+            return Err(FormatError::Magic);
+        }
+        let u32_at = |i: usize| u32::from_ne_bytes(bytes[i..i + 4].try_into().unwrap());
+        let u64_at = |i: usize| u64::from_ne_bytes(bytes[i..i + 8].try_into().unwrap());
+        if u32_at(12) != BYTE_ORDER_MARK {
+            return Err(FormatError::Endianness);
+        }
+        if u32_at(8) != VERSION {
+            return Err(FormatError::Version(u32_at(8)));
+        }
+        let header = Self {
+            magic,
+            elem_size: u32_at(16),
+            params: [u64_at(24), u64_at(32)],
+        };
+        if header.elem_size as usize != mem::size_of::<T>() {
+            return Err(FormatError::ElementSize(header.elem_size));
+        }
+        Ok(header)
+    }
+}
+
+// Reinterpret plain data as bytes
+pub(crate) fn as_bytes<T: Pod>(values: &[T]) -> &[u8] {
+    // Safety: Pod types have no padding
+    unsafe { slice::from_raw_parts(values.as_ptr() as *const u8, mem::size_of_val(values)) }
+}
+
+// Reinterpret plain data as mutable bytes
+pub(crate) fn as_bytes_mut<T: Pod>(values: &mut [T]) -> &mut [u8] {
+    // Safety: Pod types have no padding and any bit pattern is valid
+    unsafe { slice::from_raw_parts_mut(values.as_mut_ptr() as *mut u8, mem::size_of_val(values)) }
+}
+
+// Read and check a header
+pub(crate) fn read_header<T: Pod, R: Read>(reader: &mut R, magic: [u8; 8]) -> io::Result<Header> {
+    let mut bytes = [0u8; HEADER_LEN];
+    reader.read_exact(&mut bytes)?;
+    Ok(Header::read::<T>(&bytes, magic)?)
+}
+
+// Read `len` values, growing the buffer only as data arrives
+fn read_values<T: Pod, R: Read>(reader: &mut R, len: u64) -> io::Result<Vec<T>> {
+    const CHUNK: usize = 1 << 16;
+    let len = usize::try_from(len).map_err(|_| FormatError::Truncated)?;
+    // Safety: any bit pattern, including zero, is a valid Pod
+    let zero: T = unsafe { mem::zeroed() };
+    let mut values = Vec::new();
+    while values.len() < len {
+        let begin = values.len();
+        values.resize(len.min(begin + CHUNK), zero);
+        reader.read_exact(as_bytes_mut(&mut values[begin..]))?;
+    }
+    Ok(values)
+}
+
+// Reinterpret bytes as plain data
+pub(crate) fn cast_slice<T: Pod>(bytes: &[u8]) -> Result<&[T], FormatError> {
+    assert_ne!(mem::size_of::<T>(), 0);
+    if !(bytes.as_ptr() as usize).is_multiple_of(mem::align_of::<T>()) {
+        return Err(FormatError::Misaligned);
+    }
+    debug_assert_eq!(bytes.len() % mem::size_of::<T>(), 0);
+    // Safety: alignment is checked and any bit pattern is a valid Pod
+    Ok(unsafe {
+        slice::from_raw_parts(
+            bytes.as_ptr() as *const T,
+            bytes.len() / mem::size_of::<T>(),
+        )
+    })
+}
+
+// Reinterpret bytes as mutable plain data
+pub(crate) fn cast_slice_mut<T: Pod>(bytes: &mut [u8]) -> Result<&mut [T], FormatError> {
+    assert_ne!(mem::size_of::<T>(), 0);
+    if !(bytes.as_ptr() as usize).is_multiple_of(mem::align_of::<T>()) {
+        return Err(FormatError::Misaligned);
+    }
+    debug_assert_eq!(bytes.len() % mem::size_of::<T>(), 0);
+    // Safety: alignment is checked and any bit pattern is a valid Pod
+    Ok(unsafe {
+        slice::from_raw_parts_mut(
+            bytes.as_mut_ptr() as *mut T,
+            bytes.len() / mem::size_of::<T>(),
+        )
+    })
+}
+
+// Byte range of `count` elements of `T` starting at `begin`
+pub(crate) fn byte_range<T>(
+    begin: usize,
+    count: u64,
+    len: usize,
+) -> Result<std::ops::Range<usize>, FormatError> {
+    let end = usize::try_from(count)
+        .ok()
+        .and_then(|count| count.checked_mul(mem::size_of::<T>()))
+        .and_then(|size| size.checked_add(begin))
+        .ok_or(FormatError::Truncated)?;
+    if end > len {
+        return Err(FormatError::Truncated);
+    }
+    Ok(begin..end)
+}
+
+impl<T> SlicedVec<T>
+where
+    T: Copy + Clone + Pod,
+{
+    /// Encode as bytes in the binary format.
+    ///
+    /// The layout is a header followed by the storage.
+    /// See `SlicedView::from_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let header = Header::new::<T>(SLICED_MAGIC, [self.segment_len() as u64, self.len() as u64]);
+        let mut bytes = Vec::with_capacity(HEADER_LEN + mem::size_of_val(self.storage.as_slice()));
+        bytes.extend_from_slice(&header.to_bytes());
+        bytes.extend_from_slice(as_bytes(&self.storage));
+        bytes
+    }
+    /// Write in the binary format.
+    ///
+    /// Writes the same bytes as `to_bytes`.
+    pub fn save_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let header = Header::new::<T>(SLICED_MAGIC, [self.segment_len() as u64, self.len() as u64]);
+        writer.write_all(&header.to_bytes())?;
+        writer.write_all(as_bytes(&self.storage))
+    }
+    /// Read a vector in the binary format.
+    ///
+    /// Unlike `SlicedView::from_bytes`, the source need not be
+    /// aligned and the data is copied. Malformed data is
+    /// reported as `InvalidData` wrapping a `FormatError`.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// use std::{fs::File, io::BufReader};
+    /// let path = std::env::temp_dir().join("sliced_save_doctest.bin");
+    /// let sv = slicedvec![[1.5f32, 2.5], [3.5, 4.5]];
+    /// sv.save_to(File::create(&path).unwrap()).unwrap();
+    /// let loaded = SlicedVec::<f32>::load_from(BufReader::new(File::open(&path).unwrap())).unwrap();
+    /// assert_eq!(loaded.segment_len(), 2);
+    /// assert_eq!(loaded[1], [3.5, 4.5]);
+    /// assert!(SlicedVec::<f64>::load_from(File::open(&path).unwrap()).is_err());
+    /// std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn load_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let header = read_header::<T, _>(&mut reader, SLICED_MAGIC)?;
+        let [segment_len, count] = header.params;
+        if segment_len == 0 {
+            return Err(FormatError::Invalid.into());
+        }
+        let storage_len = segment_len
+            .checked_mul(count)
+            .ok_or(FormatError::Truncated)?;
+        let storage = read_values(&mut reader, storage_len)?;
+        Ok(Self::from_vec(segment_len as usize, storage))
+    }
+}
+
+impl<T, O> VarSlicedVec<T, O>
+where
+    T: Copy + Clone + Pod,
+    O: Offset,
+{
+    /// Encode as bytes in the binary format.
+    ///
+    /// The layout is a header followed by the offsets as `u64`
+    /// and then the storage. See `VarSlicedView::from_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let header = Header::new::<T>(
+            VARSLICED_MAGIC,
+            [self.len() as u64, self.storage.len() as u64],
+        );
+        let mut bytes = Vec::with_capacity(
+            HEADER_LEN + 8 * self.extents.len() + mem::size_of_val(self.storage.as_slice()),
+        );
+        bytes.extend_from_slice(&header.to_bytes());
+        self.extents
+            .iter()
+            .for_each(|extent| bytes.extend_from_slice(&(extent.to_usize() as u64).to_ne_bytes()));
+        bytes.extend_from_slice(as_bytes(&self.storage));
+        bytes
+    }
+    /// Write in the binary format.
+    ///
+    /// Writes the same bytes as `to_bytes`. Offsets are written
+    /// one at a time, so wrap unbuffered writers in a `BufWriter`.
+    pub fn save_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let header = Header::new::<T>(
+            VARSLICED_MAGIC,
+            [self.len() as u64, self.storage.len() as u64],
+        );
+        writer.write_all(&header.to_bytes())?;
+        for extent in &self.extents {
+            writer.write_all(&(extent.to_usize() as u64).to_ne_bytes())?;
+        }
+        writer.write_all(as_bytes(&self.storage))
+    }
+    /// Read a vector in the binary format.
+    ///
+    /// Offsets are checked as in `VarSlicedView::from_bytes` and
+    /// must also fit in the offset type `O`.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut bytes = Vec::new();
+    /// varslicedvec![[1u8, 2], [], [3]].save_to(&mut bytes).unwrap();
+    /// let vv = VarSlicedVec::<u8, u16>::load_from(bytes.as_slice()).unwrap();
+    /// assert_eq!(vv.lengths(), [2, 0, 1]);
+    /// bytes[40] = 1;
+    /// assert!(VarSlicedVec::<u8>::load_from(bytes.as_slice()).is_err());
+    /// ```
+    pub fn load_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let header = read_header::<T, _>(&mut reader, VARSLICED_MAGIC)?;
+        let [count, storage_len] = header.params;
+        let n_offsets = count.checked_add(1).ok_or(FormatError::Truncated)?;
+        let extents = read_values::<u64, _>(&mut reader, n_offsets)?;
+        let bits = 8 * mem::size_of::<O>() as u32;
+        if extents[0] != 0
+            || *extents.last().unwrap() != storage_len
+            || extents.windows(2).any(|x| x[0] > x[1])
+            || storage_len.checked_shr(bits).is_some_and(|high| high != 0)
+        {
+            return Err(FormatError::Invalid.into());
+        }
+        let storage = read_values(&mut reader, storage_len)?;
+        let extents = extents
+            .into_iter()
+            .map(|extent| O::from_usize(extent as usize))
+            .collect();
+        Ok(Self::from_parts(storage, extents))
+    }
+}
+
+impl<T> SlicedSlab<T>
+where
+    T: Copy + Clone + Pod,
+{
+    /// Write in the binary format.
+    ///
+    /// Keys, occupancy and the order in which open slots are
+    /// reused are saved, but not the contents of open slots.
+    /// Segments are written one at a time, so wrap unbuffered
+    /// writers in a `BufWriter`.
+    pub fn save_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let header = Header::new::<T>(SLAB_MAGIC, [self.segment_len() as u64, self.len() as u64]);
+        writer.write_all(&header.to_bytes())?;
+        writer.write_all(&(self.vacant_len() as u64).to_ne_bytes())?;
+        for key in self.iter_vacant_keys() {
+            writer.write_all(&(key as u64).to_ne_bytes())?;
+        }
+        for key in self.iter_keys() {
+            writer.write_all(as_bytes(&self.slots[key]))?;
+        }
+        Ok(())
+    }
+    /// Read a slab in the binary format.
+    ///
+    /// The loaded slab hands out the same keys as the saved
+    /// one. Open slots are zeroed.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut ss = SlicedSlab::from_vec(2, (0..8u32).collect());
+    /// ss.release(2);
+    /// ss.release(0);
+    /// let mut bytes = Vec::new();
+    /// ss.save_to(&mut bytes).unwrap();
+    /// let mut loaded = SlicedSlab::<u32>::load_from(bytes.as_slice()).unwrap();
+    /// assert_eq!(loaded.get_keys(), [1, 3]);
+    /// assert_eq!(loaded[3], [6, 7]);
+    /// assert_eq!(loaded.insert(&[9, 9]), 0);
+    /// assert_eq!(loaded.insert(&[9, 9]), 2);
+    /// ```
+    pub fn load_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let header = read_header::<T, _>(&mut reader, SLAB_MAGIC)?;
+        let [segment_len, len] = header.params;
+        let segment_len = usize::try_from(segment_len).map_err(|_| FormatError::Invalid)?;
+        let len = usize::try_from(len).map_err(|_| FormatError::Invalid)?;
+        if segment_len == 0 {
+            return Err(FormatError::Invalid.into());
+        }
+        let storage_len = len.checked_mul(segment_len).ok_or(FormatError::Invalid)?;
+        let free_len = read_values::<u64, _>(&mut reader, 1)?[0];
+        let free_len = usize::try_from(free_len).map_err(|_| FormatError::Invalid)?;
+        let occupied_len = len.checked_sub(free_len).ok_or(FormatError::Invalid)?;
+        let free = read_values::<u64, _>(&mut reader, free_len as u64)?;
+        // Cannot overflow since occupied_len is at most len
+        let data = read_values::<T, _>(&mut reader, (occupied_len * segment_len) as u64)?;
+        // Slot count is now backed by data that was read
+        let mut open = vec![0u64; len.div_ceil(64)];
+        let free = free
+            .into_iter()
+            .map(|key| {
+                let key = usize::try_from(key)
+                    .ok()
+                    .filter(|key| *key < len && open[key / 64] >> (key % 64) & 1 == 0)
+                    .ok_or(FormatError::Invalid)?;
+                open[key / 64] |= 1 << (key % 64);
+                Ok(key)
+            })
+            .collect::<Result<Vec<_>, FormatError>>()?;
+        // Open slots are not backed by data, so
+        // the full storage may not be allocatable
+        let mut storage = Vec::new();
+        storage
+            .try_reserve_exact(storage_len)
+            .map_err(|_| FormatError::Invalid)?;
+        // Safety: any bit pattern, including zero, is a valid Pod
+        let zero: T = unsafe { mem::zeroed() };
+        let mut segments = data.chunks_exact(segment_len);
+        for key in 0..len {
+            match open[key / 64] >> (key % 64) & 1 {
+                0 => storage.extend_from_slice(segments.next().unwrap()),
+                _ => storage.resize(storage.len() + segment_len, zero),
+            }
+        }
+        Ok(Self::from_open_keys(segment_len, storage, &free))
+    }
+}
@@ -0,0 +1,113 @@
+use std::{ops::Index, sync::Arc};
+
+use crate::slicedslab::{OccupiedKeys, SlicedSlab};
+
+/// An immutable, shareable snapshot of a `SlicedSlab`.
+///
+/// Storage and occupancy are held behind `Arc`, so cloning is
+/// cheap and the snapshot can be sent to reader threads while
+/// the writer keeps modifying the original slab.
+/// ```
+/// use sliced::*;
+/// let mut ss = SlicedSlab::from_vec(2, (0..6).collect());
+/// ss.release(1);
+/// let frozen = ss.freeze();
+/// let reader = {
+///     let frozen = frozen.clone();
+///     std::thread::spawn(move || frozen.iter().map(|(_, s)| s[0]).sum::<i32>())
+/// };
+/// ss[0][0] = 100;
+/// assert_eq!(reader.join().unwrap(), 4);
+/// assert_eq!(frozen[0], [0, 1]);
+/// assert_eq!(frozen.get(1), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrozenSlab<T>
+where
+    T: Copy + Clone,
+{
+    storage: Arc<[T]>,
+    words: Arc<[u64]>,
+    segment_len: usize,
+    occupied_len: usize,
+}
+
+impl<T> FrozenSlab<T>
+where
+    T: Copy + Clone,
+{
+    /// Returns the number of slots, occupied or open.
+    pub fn len(&self) -> usize {
+        self.storage.len() / self.segment_len
+    }
+    /// Test if there are no slots.
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+    /// Returns the number of occupied slots.
+    pub fn occupied_len(&self) -> usize {
+        self.occupied_len
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.segment_len
+    }
+    /// Test if `key` refers to an occupied slot.
+    pub fn contains_key(&self, key: usize) -> bool {
+        self.words
+            .get(key / 64)
+            .is_some_and(|word| word >> (key % 64) & 1 == 1)
+    }
+    /// Get a reference to a segment.
+    ///
+    /// Returns `None` if `key` is out of range
+    /// or the slot is marked as unoccupied.
+    pub fn get(&self, key: usize) -> Option<&[T]> {
+        self.contains_key(key).then(|| self.segment(key))
+    }
+    /// Iterate over active keys.
+    pub fn iter_keys(&self) -> impl Iterator<Item = usize> + '_ {
+        OccupiedKeys::new(&self.words)
+    }
+    /// Iterate over key, slice pairs of occupied slots.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &[T])> {
+        self.iter_keys().map(|key| (key, self.segment(key)))
+    }
+    // Slice of a slot without checking occupancy
+    fn segment(&self, key: usize) -> &[T] {
+        let begin = key * self.segment_len;
+        &self.storage[begin..begin + self.segment_len]
+    }
+}
+
+impl<T> SlicedSlab<T>
+where
+    T: Copy + Clone,
+{
+    /// Copy the slab into an immutable `FrozenSlab`.
+    ///
+    /// Only the storage and occupancy bitmap are copied.
+    /// Keys are the same as in the slab.
+    pub fn freeze(&self) -> FrozenSlab<T> {
+        FrozenSlab {
+            storage: self.slots.storage.as_slice().into(),
+            words: self.occupied.words.as_slice().into(),
+            segment_len: self.segment_len(),
+            occupied_len: self.occupied_len(),
+        }
+    }
+}
+
+/// Get segment from frozen slab.
+///
+/// # Panics
+/// If `key` is out of range or the slot is unoccupied.
+impl<T> Index<usize> for FrozenSlab<T>
+where
+    T: Copy + Clone,
+{
+    type Output = [T];
+    fn index(&self, key: usize) -> &Self::Output {
+        self.get(key).expect("key is not occupied")
+    }
+}
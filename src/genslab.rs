@@ -0,0 +1,205 @@
+use std::ops::{Index, IndexMut};
+
+use crate::slicedslab::SlicedSlab;
+
+/// A key into a `SlicedGenSlab`.
+///
+/// The generation is advanced every time its slot is
+/// released, so a key outliving its segment is detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GenKey {
+    index: usize,
+    generation: u32,
+}
+
+impl GenKey {
+    /// Returns the slot index.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+    /// Returns the generation.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+/// A segmented slab with generational keys.
+///
+/// Slots are reused as in `SlicedSlab`, but each key also
+/// records the generation of its slot. Looking up a key whose
+/// slot has since been released, and perhaps reused, returns
+/// `None` rather than another segment.
+/// ```
+/// use sliced::*;
+/// let mut gs = SlicedGenSlab::new(2);
+/// let a = gs.insert(&[1, 2]);
+/// assert!(gs.release(a));
+/// let b = gs.insert(&[3, 4]);
+/// assert_eq!(a.index(), b.index());
+/// assert_eq!(gs.get(a), None);
+/// assert_eq!(gs.get(b), Some([3, 4].as_slice()));
+/// assert!(!gs.release(a));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SlicedGenSlab<T>
+where
+    T: Copy + Clone,
+{
+    slab: SlicedSlab<T>,
+    generations: Vec<u32>,
+}
+
+impl<T> SlicedGenSlab<T>
+where
+    T: Copy + Clone,
+{
+    /// Construct a new `SlicedGenSlab`.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn new(segment_len: usize) -> Self {
+        Self {
+            slab: SlicedSlab::new(segment_len),
+            generations: Vec::new(),
+        }
+    }
+    /// Initialize a `SlicedGenSlab` and set the capacity and segment size.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn with_capacity(segment_len: usize, size: usize) -> Self {
+        Self {
+            slab: SlicedSlab::with_capacity(segment_len, size),
+            generations: Vec::with_capacity(size),
+        }
+    }
+    /// Returns the number of occupied slots.
+    pub fn len(&self) -> usize {
+        self.slab.occupied_len()
+    }
+    /// Test if there are no occupied slots.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.slab.segment_len()
+    }
+    /// Insert a segment and return its key.
+    ///
+    /// # Panics
+    /// If the length of the slice does
+    /// not match the segments size of the slab.
+    pub fn insert(&mut self, segment: &[T]) -> GenKey {
+        let index = self.slab.insert(segment);
+        if index == self.generations.len() {
+            self.generations.push(0);
+        }
+        GenKey {
+            index,
+            generation: self.generations[index],
+        }
+    }
+    /// Release the slot referred to by `key`.
+    ///
+    /// Returns `false` if `key` is stale or out of range.
+    pub fn release(&mut self, key: GenKey) -> bool {
+        if !self.contains_key(key) {
+            return false;
+        }
+        let generation = &mut self.generations[key.index];
+        *generation = generation.wrapping_add(1);
+        self.slab.release(key.index);
+        true
+    }
+    /// Test if `key` refers to a live segment.
+    pub fn contains_key(&self, key: GenKey) -> bool {
+        self.generations.get(key.index) == Some(&key.generation)
+            && self.slab.contains_key(key.index)
+    }
+    /// Get a reference to a segment.
+    ///
+    /// Returns `None` if `key` is stale or out of range.
+    pub fn get(&self, key: GenKey) -> Option<&[T]> {
+        if !self.contains_key(key) {
+            return None;
+        }
+        self.slab.get(key.index)
+    }
+    /// Get a mutable reference to a segment.
+    ///
+    /// Returns `None` if `key` is stale or out of range.
+    pub fn get_mut(&mut self, key: GenKey) -> Option<&mut [T]> {
+        if !self.contains_key(key) {
+            return None;
+        }
+        self.slab.get_mut(key.index)
+    }
+    /// Release all slots.
+    ///
+    /// Every outstanding key becomes stale.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut gs = SlicedGenSlab::new(1);
+    /// let a = gs.insert(&[1]);
+    /// gs.clear();
+    /// let b = gs.insert(&[2]);
+    /// assert_eq!(gs.get(a), None);
+    /// assert_eq!(gs[b], [2]);
+    /// ```
+    pub fn clear(&mut self) {
+        for index in self.slab.iter_keys() {
+            let generation = &mut self.generations[index];
+            *generation = generation.wrapping_add(1);
+        }
+        self.slab.clear();
+    }
+    /// Iterate over key, slice pairs of occupied slots.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut gs = SlicedGenSlab::new(1);
+    /// let keys: Vec<_> = (0..3).map(|i| gs.insert(&[i])).collect();
+    /// gs.release(keys[1]);
+    /// let live: Vec<_> = gs.iter().map(|(key, _)| key).collect();
+    /// assert_eq!(live, [keys[0], keys[2]]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (GenKey, &[T])> + '_ {
+        self.slab.iter().map(|(index, segment)| {
+            let key = GenKey {
+                index,
+                generation: self.generations[index],
+            };
+            (key, segment)
+        })
+    }
+}
+
+/// Get segment from slab.
+///
+/// # Panics
+/// If `key` is stale or out of range.
+impl<T> Index<GenKey> for SlicedGenSlab<T>
+where
+    T: Copy + Clone,
+{
+    type Output = [T];
+    fn index(&self, key: GenKey) -> &Self::Output {
+        self.get(key).expect("stale or invalid key")
+    }
+}
+
+/// Get mutable segment from slab.
+///
+/// # Panics
+/// If `key` is stale or out of range.
+impl<T> IndexMut<GenKey> for SlicedGenSlab<T>
+where
+    T: Copy + Clone,
+{
+    fn index_mut(&mut self, key: GenKey) -> &mut Self::Output {
+        self.get_mut(key).expect("stale or invalid key")
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{format::Pod, slicedvec::SlicedVec};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// A 32-bit scalar that can be uploaded to a GPU buffer.
+///
+/// Implemented for `f32`, `u32` and `i32`.
+pub trait GpuScalar: private::Sealed + Pod {
+    /// The bits of the value.
+    fn to_bits(self) -> u32;
+}
+
+macro_rules! impl_gpu_scalar {
+    ($($t:ty => $bits:expr),*) => {
+        $(
+            impl private::Sealed for $t {}
+            impl GpuScalar for $t {
+                #[inline]
+                fn to_bits(self) -> u32 {
+                    $bits(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_gpu_scalar!(f32 => f32::to_bits, u32 => u32::from, i32 => |x: i32| x as u32);
+
+/// Memory layout rules for GPU buffers.
+///
+/// Segments of length 2, 3 and 4 are laid out as vectors
+/// (`vec2`, `vec3` and `vec4`), segments of length 1 as scalars
+/// and longer segments as fixed size arrays, each the element
+/// of an array spanning the buffer. `Std140` applies to uniform
+/// buffers and rounds array strides up to 16 bytes. `Std430`
+/// applies to storage buffers and only pads `vec3` to 16 bytes.
+/// These match the WGSL uniform and storage address spaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GpuLayout {
+    /// Uniform buffer layout.
+    Std140,
+    /// Storage buffer layout.
+    Std430,
+}
+
+impl GpuLayout {
+    /// Returns the byte offset between consecutive segments.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// assert_eq!(GpuLayout::Std430.required_stride(1), 4);
+    /// assert_eq!(GpuLayout::Std430.required_stride(3), 16);
+    /// assert_eq!(GpuLayout::Std430.required_stride(5), 20);
+    /// assert_eq!(GpuLayout::Std140.required_stride(1), 16);
+    /// assert_eq!(GpuLayout::Std140.required_stride(5), 80);
+    /// ```
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn required_stride(self, segment_len: usize) -> usize {
+        let size = match segment_len {
+            0 => panic!("segment length must be non-zero"),
+            1 | 2 | 4 => 4 * segment_len,
+            3 => 16,
+            _ => self.element_stride(segment_len) * segment_len,
+        };
+        match self {
+            Self::Std140 => size.next_multiple_of(16),
+            Self::Std430 => size,
+        }
+    }
+    /// Returns the byte offset between consecutive values within a segment.
+    ///
+    /// Values are packed except in `Std140` arrays, where
+    /// each value is padded to 16 bytes.
+    pub fn element_stride(self, segment_len: usize) -> usize {
+        match self {
+            Self::Std140 if segment_len > 4 => 16,
+            _ => 4,
+        }
+    }
+}
+
+impl<T> SlicedVec<T>
+where
+    T: Copy + Clone + GpuScalar,
+{
+    /// Returns the byte offset between segments in a GPU buffer.
+    pub fn required_stride(&self, layout: GpuLayout) -> usize {
+        layout.required_stride(self.segment_len())
+    }
+    /// Copy the segments into a padded little-endian GPU buffer.
+    ///
+    /// Padding bytes are zero. The buffer length is the number
+    /// of segments times `required_stride`.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let positions = slicedvec![[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]];
+    /// let bytes = positions.to_gpu_bytes(GpuLayout::Std430);
+    /// assert_eq!(bytes.len(), 32);
+    /// assert_eq!(bytes[16..20], 4.0f32.to_le_bytes());
+    /// assert_eq!(bytes[12..16], [0; 4]);
+    /// let weights = slicedvec![[1u32], [2]];
+    /// let bytes = weights.to_gpu_bytes(GpuLayout::Std140);
+    /// assert_eq!(bytes.len(), 32);
+    /// assert_eq!(bytes[16..20], 2u32.to_le_bytes());
+    /// ```
+    pub fn to_gpu_bytes(&self, layout: GpuLayout) -> Vec<u8> {
+        let stride = self.required_stride(layout);
+        let element_stride = layout.element_stride(self.segment_len());
+        let mut bytes = vec![0; stride * self.len()];
+        for (segment, out) in self.iter().zip(bytes.chunks_exact_mut(stride)) {
+            for (value, out) in segment.iter().zip(out.chunks_mut(element_stride)) {
+                out[..4].copy_from_slice(&value.to_bits().to_le_bytes());
+            }
+        }
+        bytes
+    }
+}
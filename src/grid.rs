@@ -0,0 +1,167 @@
+use crate::slicedvec::SlicedVec;
+
+/// A uniform spatial hash grid with a fixed capacity per cell.
+///
+/// The domain starting at `origin` is divided into cubic cells
+/// of side `cell_size`, `dims[d]` cells along axis `d`. Each
+/// cell's payloads occupy one segment of a `SlicedVec`, so the
+/// grid is a single allocation that is reused after `clear`.
+/// ```
+/// use sliced::*;
+/// let mut grid = SlicedGrid::new([0.0, 0.0], 1.0, [4, 4], 8);
+/// let a = grid.insert([0.5, 0.5], 10u32).unwrap();
+/// grid.insert([1.5, 0.2], 11).unwrap();
+/// grid.insert([3.5, 3.5], 12).unwrap();
+/// assert_eq!(grid.query_cell(a), [10]);
+/// let mut near: Vec<_> = grid.query_neighbors(a).copied().collect();
+/// near.sort();
+/// assert_eq!(near, [10, 11]);
+/// assert_eq!(grid.insert([9.0, 0.0], 13), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SlicedGrid<T, const D: usize>
+where
+    T: Copy + Clone,
+{
+    origin: [f32; D],
+    cell_size: f32,
+    dims: [usize; D],
+    cells: SlicedVec<T>,
+    counts: Vec<usize>,
+}
+
+impl<T, const D: usize> SlicedGrid<T, D>
+where
+    T: Copy + Clone + Default,
+{
+    /// Construct an empty grid.
+    ///
+    /// # Panics
+    /// If `capacity` or any dimension is zero, or
+    /// if `cell_size` is not positive.
+    pub fn new(origin: [f32; D], cell_size: f32, dims: [usize; D], capacity: usize) -> Self {
+        assert!(cell_size > 0.0);
+        assert!(dims.iter().all(|n| *n > 0));
+        let ncells = dims.iter().product();
+        Self {
+            origin,
+            cell_size,
+            dims,
+            cells: SlicedVec::from_vec(capacity, vec![T::default(); ncells * capacity]),
+            counts: vec![0; ncells],
+        }
+    }
+}
+
+impl<T, const D: usize> SlicedGrid<T, D>
+where
+    T: Copy + Clone,
+{
+    /// Returns the number of cells.
+    pub fn cell_count(&self) -> usize {
+        self.counts.len()
+    }
+    /// Returns the maximum number of payloads per cell.
+    pub fn cell_capacity(&self) -> usize {
+        self.cells.segment_len()
+    }
+    /// Returns the number of cells along each axis.
+    pub fn dims(&self) -> [usize; D] {
+        self.dims
+    }
+    /// Returns the total number of payloads.
+    pub fn len(&self) -> usize {
+        self.counts.iter().sum()
+    }
+    /// Test if no payloads are stored.
+    pub fn is_empty(&self) -> bool {
+        self.counts.iter().all(|n| *n == 0)
+    }
+    /// Returns the cell containing `point`, if inside the grid.
+    pub fn cell_of(&self, point: [f32; D]) -> Option<usize> {
+        let mut coords = [0; D];
+        for d in 0..D {
+            let x = ((point[d] - self.origin[d]) / self.cell_size).floor();
+            if !(x >= 0.0 && x < self.dims[d] as f32) {
+                return None;
+            }
+            coords[d] = x as usize;
+        }
+        Some(self.cell_index(coords))
+    }
+    /// Returns the cell at integer coordinates.
+    ///
+    /// # Panics
+    /// If `coords` is outside the grid.
+    pub fn cell_index(&self, coords: [usize; D]) -> usize {
+        let mut index = 0;
+        for d in (0..D).rev() {
+            assert!(coords[d] < self.dims[d]);
+            index = index * self.dims[d] + coords[d];
+        }
+        index
+    }
+    /// Returns the integer coordinates of a cell.
+    pub fn cell_coords(&self, mut cell: usize) -> [usize; D] {
+        self.dims.map(|n| {
+            let x = cell % n;
+            cell /= n;
+            x
+        })
+    }
+    /// Store `payload` in the cell containing `point`.
+    ///
+    /// Returns the cell, or `None` if the point is
+    /// outside the grid or the cell is full.
+    pub fn insert(&mut self, point: [f32; D], payload: T) -> Option<usize> {
+        let cell = self.cell_of(point)?;
+        let count = &mut self.counts[cell];
+        if *count == self.cells.segment_len() {
+            return None;
+        }
+        self.cells[cell][*count] = payload;
+        *count += 1;
+        Some(cell)
+    }
+    /// Get the payloads of a cell.
+    ///
+    /// # Panics
+    /// If `cell` is out of range.
+    pub fn query_cell(&self, cell: usize) -> &[T] {
+        &self.cells[cell][..self.counts[cell]]
+    }
+    /// Iterate over a cell and its adjacent cells.
+    ///
+    /// Up to `3^D` cells are visited, clipped at the grid boundary.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let grid = SlicedGrid::<u8, 3>::new([0.0; 3], 1.0, [3, 3, 3], 1);
+    /// assert_eq!(grid.neighbors(0).count(), 8);
+    /// assert_eq!(grid.neighbors(grid.cell_index([1, 1, 1])).count(), 27);
+    /// ```
+    pub fn neighbors(&self, cell: usize) -> impl Iterator<Item = usize> + '_ {
+        let center = self.cell_coords(cell);
+        (0..3usize.pow(D as u32)).filter_map(move |mut offset| {
+            let mut coords = [0; D];
+            for d in 0..D {
+                let x = (center[d] + offset % 3).checked_sub(1)?;
+                if x >= self.dims[d] {
+                    return None;
+                }
+                coords[d] = x;
+                offset /= 3;
+            }
+            Some(self.cell_index(coords))
+        })
+    }
+    /// Iterate over the payloads of a cell and its adjacent cells.
+    pub fn query_neighbors(&self, cell: usize) -> impl Iterator<Item = &T> {
+        self.neighbors(cell)
+            .flat_map(move |cell| self.query_cell(cell).iter())
+    }
+    /// Remove all payloads, keeping the storage.
+    pub fn clear(&mut self) {
+        self.counts.fill(0)
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    cell::RefCell,
+    error::Error,
+    fmt,
+    ops::{Index, IndexMut},
+    rc::Rc,
+};
+
+use crate::slicedslab::SlicedSlab;
+
+/// A counted reference to an occupied slot of a `GuardedSlab`.
+///
+/// The slot cannot be released while any `SlotRef` to it is
+/// alive. Cloning a `SlotRef` increments the count and dropping
+/// one decrements it.
+#[derive(Debug)]
+pub struct SlotRef {
+    key: usize,
+    counts: Rc<RefCell<Vec<u32>>>,
+}
+
+impl SlotRef {
+    /// Returns the key of the referenced slot.
+    pub fn key(&self) -> usize {
+        self.key
+    }
+}
+
+impl Clone for SlotRef {
+    fn clone(&self) -> Self {
+        self.counts.borrow_mut()[self.key] += 1;
+        Self {
+            key: self.key,
+            counts: self.counts.clone(),
+        }
+    }
+}
+
+impl Drop for SlotRef {
+    fn drop(&mut self) {
+        self.counts.borrow_mut()[self.key] -= 1;
+    }
+}
+
+/// Error returned by `GuardedSlab::release` when the slot
+/// is still referenced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotBorrowed;
+
+impl fmt::Display for SlotBorrowed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "slot is still referenced")
+    }
+}
+
+impl Error for SlotBorrowed {}
+
+/// A `SlicedSlab` that refuses to release referenced slots.
+///
+/// This is a correctness layer over the raw key API for
+/// catching use-after-release. Keys obtained through
+/// `slot_ref` are counted per slot; plain `usize` keys
+/// are not tracked.
+/// ```
+/// use sliced::*;
+/// let mut gs = GuardedSlab::new(2);
+/// let key = gs.insert(&[1, 2]);
+/// let guard = gs.slot_ref(key).unwrap();
+/// assert_eq!(gs.release(key), Err(SlotBorrowed));
+/// assert_eq!(gs[&guard], [1, 2]);
+/// drop(guard);
+/// assert_eq!(gs.release(key), Ok(()));
+/// assert!(gs.slot_ref(key).is_none());
+/// ```
+#[derive(Debug)]
+pub struct GuardedSlab<T>
+where
+    T: Copy + Clone,
+{
+    slab: SlicedSlab<T>,
+    counts: Rc<RefCell<Vec<u32>>>,
+}
+
+impl<T> GuardedSlab<T>
+where
+    T: Copy + Clone,
+{
+    /// Construct a new `GuardedSlab`.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn new(segment_len: usize) -> Self {
+        Self {
+            slab: SlicedSlab::new(segment_len),
+            counts: Rc::default(),
+        }
+    }
+    /// Insert a segment into the slab.
+    ///
+    /// See `SlicedSlab::insert`.
+    pub fn insert(&mut self, segment: &[T]) -> usize {
+        let key = self.slab.insert(segment);
+        let mut counts = self.counts.borrow_mut();
+        if key == counts.len() {
+            counts.push(0);
+        }
+        debug_assert_eq!(counts[key], 0);
+        key
+    }
+    /// Take a counted reference to an occupied slot.
+    ///
+    /// Returns `None` if `key` is out of range or open.
+    pub fn slot_ref(&self, key: usize) -> Option<SlotRef> {
+        if !self.slab.contains_key(key) {
+            return None;
+        }
+        self.counts.borrow_mut()[key] += 1;
+        Some(SlotRef {
+            key,
+            counts: self.counts.clone(),
+        })
+    }
+    /// Returns the number of live references to `key`.
+    pub fn ref_count(&self, key: usize) -> u32 {
+        self.counts.borrow().get(key).copied().unwrap_or(0)
+    }
+    /// Release a slot that is not referenced.
+    ///
+    /// # Panics
+    /// If the slot is already marked as available.
+    pub fn release(&mut self, key: usize) -> Result<(), SlotBorrowed> {
+        if self.ref_count(key) > 0 {
+            return Err(SlotBorrowed);
+        }
+        self.slab.release(key);
+        Ok(())
+    }
+    /// Get a reference to a segment.
+    ///
+    /// Returns `None` if `key` is out of range or open.
+    pub fn get(&self, key: usize) -> Option<&[T]> {
+        self.slab.get(key)
+    }
+    /// Get a mutable reference to a segment.
+    ///
+    /// Returns `None` if `key` is out of range or open.
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut [T]> {
+        self.slab.get_mut(key)
+    }
+    /// Get a reference to the underlying slab.
+    pub fn as_slab(&self) -> &SlicedSlab<T> {
+        &self.slab
+    }
+}
+
+/// Get the segment behind a slot reference.
+///
+/// The slot is occupied for as long as the reference lives.
+/// # Panics
+/// If the reference belongs to a different slab.
+impl<T> Index<&SlotRef> for GuardedSlab<T>
+where
+    T: Copy + Clone,
+{
+    type Output = [T];
+    fn index(&self, slot: &SlotRef) -> &Self::Output {
+        assert!(Rc::ptr_eq(&self.counts, &slot.counts));
+        &self.slab[slot.key]
+    }
+}
+
+/// Get the mutable segment behind a slot reference.
+///
+/// # Panics
+/// If the reference belongs to a different slab.
+impl<T> IndexMut<&SlotRef> for GuardedSlab<T>
+where
+    T: Copy + Clone,
+{
+    fn index_mut(&mut self, slot: &SlotRef) -> &mut Self::Output {
+        assert!(Rc::ptr_eq(&self.counts, &slot.counts));
+        &mut self.slab[slot.key]
+    }
+}
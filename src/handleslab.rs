@@ -0,0 +1,188 @@
+use std::ops::{Index, IndexMut};
+
+use crate::slicedslab::SlicedSlab;
+
+/// A stable handle into a `HandleSlab`.
+///
+/// Handles stay valid across compaction. They are reused
+/// after release, like `SlicedSlab` keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Handle(usize);
+
+impl Handle {
+    /// Returns the position in the handle table.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// A segmented slab addressed through stable handles.
+///
+/// Each handle maps to a slot through a redirection table,
+/// so `compact` can move segments freely and update the
+/// table instead of requiring key-holders to call `rekey`.
+/// Each access costs one extra lookup.
+/// ```
+/// use sliced::*;
+/// let mut hs = HandleSlab::new(2);
+/// let a = hs.insert(&[1, 2]);
+/// let b = hs.insert(&[3, 4]);
+/// let c = hs.insert(&[5, 6]);
+/// hs.release(a);
+/// hs.release(b);
+/// hs.compact();
+/// assert_eq!(hs.slot_len(), 1);
+/// assert_eq!(hs[c], [5, 6]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct HandleSlab<T>
+where
+    T: Copy + Clone,
+{
+    slab: SlicedSlab<T>,
+    // Handle to slot
+    slots: SlicedSlab<usize>,
+    // Slot to handle
+    handles: Vec<usize>,
+}
+
+impl<T> HandleSlab<T>
+where
+    T: Copy + Clone,
+{
+    /// Construct a new `HandleSlab`.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn new(segment_len: usize) -> Self {
+        Self {
+            slab: SlicedSlab::new(segment_len),
+            slots: SlicedSlab::new(1),
+            handles: Vec::new(),
+        }
+    }
+    /// Initialize a `HandleSlab` and set the capacity and segment size.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn with_capacity(segment_len: usize, size: usize) -> Self {
+        Self {
+            slab: SlicedSlab::with_capacity(segment_len, size),
+            slots: SlicedSlab::with_capacity(1, size),
+            handles: Vec::with_capacity(size),
+        }
+    }
+    /// Returns the number of live handles.
+    pub fn len(&self) -> usize {
+        self.slab.occupied_len()
+    }
+    /// Test if there are no live handles.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the number of slots, occupied or open.
+    pub fn slot_len(&self) -> usize {
+        self.slab.len()
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.slab.segment_len()
+    }
+    /// Insert a segment and return a handle.
+    ///
+    /// # Panics
+    /// If the length of the slice does
+    /// not match the segments size of the slab.
+    pub fn insert(&mut self, segment: &[T]) -> Handle {
+        let slot = self.slab.insert(segment);
+        let handle = self.slots.insert(&[slot]);
+        if slot == self.handles.len() {
+            self.handles.push(handle);
+        } else {
+            self.handles[slot] = handle;
+        }
+        Handle(handle)
+    }
+    /// Release the segment referred to by `handle`.
+    ///
+    /// # Panics
+    /// If `handle` is not live.
+    pub fn release(&mut self, handle: Handle) {
+        let slot = self.slot(handle).expect("handle is not live");
+        self.slots.release(handle.0);
+        self.slab.release(slot);
+    }
+    /// Test if `handle` refers to a live segment.
+    pub fn contains_handle(&self, handle: Handle) -> bool {
+        self.slots.contains_key(handle.0)
+    }
+    /// Get a reference to a segment.
+    ///
+    /// Returns `None` if `handle` is not live.
+    pub fn get(&self, handle: Handle) -> Option<&[T]> {
+        self.slab.get(self.slot(handle)?)
+    }
+    /// Get a mutable reference to a segment.
+    ///
+    /// Returns `None` if `handle` is not live.
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut [T]> {
+        let slot = self.slot(handle)?;
+        self.slab.get_mut(slot)
+    }
+    /// Move segments down into open slots.
+    ///
+    /// Handles are updated in place so none are invalidated.
+    /// The storage capacity is not affected.
+    pub fn compact(&mut self) {
+        let Self {
+            slab,
+            slots,
+            handles,
+        } = self;
+        slab.compact_with(|old, new| {
+            let handle = handles[old];
+            slots[handle][0] = new;
+            handles[new] = handle;
+        });
+        handles.truncate(slab.len());
+    }
+    /// Iterate over handle, slice pairs of live segments.
+    ///
+    /// Segments are visited in slot order.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (Handle, &[T])> {
+        self.slab
+            .iter()
+            .map(|(slot, segment)| (Handle(self.handles[slot]), segment))
+    }
+    // Look up the slot of a live handle
+    fn slot(&self, handle: Handle) -> Option<usize> {
+        Some(self.slots.get(handle.0)?[0])
+    }
+}
+
+/// Get segment from slab.
+///
+/// # Panics
+/// If `handle` is not live.
+impl<T> Index<Handle> for HandleSlab<T>
+where
+    T: Copy + Clone,
+{
+    type Output = [T];
+    fn index(&self, handle: Handle) -> &Self::Output {
+        self.get(handle).expect("handle is not live")
+    }
+}
+
+/// Get mutable segment from slab.
+///
+/// # Panics
+/// If `handle` is not live.
+impl<T> IndexMut<Handle> for HandleSlab<T>
+where
+    T: Copy + Clone,
+{
+    fn index_mut(&mut self, handle: Handle) -> &mut Self::Output {
+        self.get_mut(handle).expect("handle is not live")
+    }
+}
@@ -0,0 +1,201 @@
+use std::{cmp::Ordering, fmt};
+
+use crate::slicedvec::SlicedVec;
+
+type CompareFn<T> = Box<dyn Fn(&[T], &[T]) -> Ordering>;
+
+/// A max-heap of fixed-length segments.
+///
+/// Segments are ordered by a comparator, so the greatest
+/// segment is at the top. Segments are moved with whole-segment
+/// swaps inside a single `SlicedVec`; no operation allocates
+/// once the storage has grown.
+/// ```
+/// use sliced::*;
+/// let mut heap = SlicedBinaryHeap::new(2);
+/// heap.push(&[1, 9]);
+/// heap.push(&[3, 0]);
+/// heap.push(&[2, 5]);
+/// assert_eq!(heap.peek(), Some([3, 0].as_slice()));
+/// let mut top = [0; 2];
+/// assert!(heap.pop_into(&mut top));
+/// assert_eq!(top, [3, 0]);
+/// assert_eq!(heap.pop(), Some(vec![2, 5]));
+/// assert_eq!(heap.len(), 1);
+/// ```
+pub struct SlicedBinaryHeap<T>
+where
+    T: Copy + Clone,
+{
+    data: SlicedVec<T>,
+    compare: CompareFn<T>,
+}
+
+impl<T> SlicedBinaryHeap<T>
+where
+    T: Copy + Clone + Ord,
+{
+    /// Construct a heap ordering segments lexicographically.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn new(segment_len: usize) -> Self {
+        Self::new_by(segment_len, |a: &[T], b: &[T]| a.cmp(b))
+    }
+}
+
+impl<T> SlicedBinaryHeap<T>
+where
+    T: Copy + Clone,
+{
+    /// Construct a heap ordering segments by `compare`.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut heap = SlicedBinaryHeap::new_by(2, |a: &[i32], b: &[i32]| b[1].cmp(&a[1]));
+    /// heap.push(&[1, 9]);
+    /// heap.push(&[3, 0]);
+    /// assert_eq!(heap.peek(), Some([3, 0].as_slice()));
+    /// ```
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn new_by<F>(segment_len: usize, compare: F) -> Self
+    where
+        F: Fn(&[T], &[T]) -> Ordering + 'static,
+    {
+        Self {
+            data: SlicedVec::new(segment_len),
+            compare: Box::new(compare),
+        }
+    }
+    /// Construct a heap ordering segments by a key extracted with `f`.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut heap = SlicedBinaryHeap::new_by_key(3, |s: &[u8]| s.iter().sum::<u8>());
+    /// heap.push(&[1, 1, 1]);
+    /// heap.push(&[0, 0, 5]);
+    /// heap.push(&[2, 0, 0]);
+    /// assert_eq!(heap.pop(), Some(vec![0, 0, 5]));
+    /// assert_eq!(heap.pop(), Some(vec![1, 1, 1]));
+    /// ```
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn new_by_key<K, F>(segment_len: usize, f: F) -> Self
+    where
+        K: Ord,
+        F: Fn(&[T]) -> K + 'static,
+    {
+        Self::new_by(segment_len, move |a: &[T], b: &[T]| f(a).cmp(&f(b)))
+    }
+    /// Returns the number of segments.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+    /// Test if the heap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.data.segment_len()
+    }
+    /// Reserve room for at least `additional` more segments.
+    pub fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional)
+    }
+    /// Push a segment onto the heap.
+    ///
+    /// # Panics
+    /// If the length of the slice does
+    /// not match the segments size of the heap.
+    pub fn push(&mut self, segment: &[T]) {
+        self.data.push(segment);
+        self.sift_up(self.len() - 1);
+    }
+    /// Returns the greatest segment.
+    pub fn peek(&self) -> Option<&[T]> {
+        self.data.first()
+    }
+    /// Remove the greatest segment, copying it into `dst`.
+    ///
+    /// Returns `false` and leaves `dst` untouched if the heap is empty.
+    /// # Panics
+    /// If the length of `dst` does
+    /// not match the segments size of the heap.
+    pub fn pop_into(&mut self, dst: &mut [T]) -> bool {
+        assert_eq!(dst.len(), self.segment_len());
+        if self.is_empty() {
+            return false;
+        }
+        let last = self.len() - 1;
+        self.data.swap(0, last);
+        dst.copy_from_slice(&self.data[last]);
+        self.data.truncate(last);
+        self.sift_down(0);
+        true
+    }
+    /// Remove and return the greatest segment.
+    pub fn pop(&mut self) -> Option<Vec<T>> {
+        let last = self.len().checked_sub(1)?;
+        self.data.swap(0, last);
+        let top = self.data.pop();
+        self.sift_down(0);
+        top
+    }
+    /// Iterate over segments in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = &[T]> {
+        self.data.iter()
+    }
+    /// Remove all segments.
+    pub fn clear(&mut self) {
+        self.data.clear()
+    }
+    /// Convert into the segments in arbitrary order.
+    pub fn into_sliced_vec(self) -> SlicedVec<T> {
+        self.data
+    }
+    // Move a segment up until its parent is not less
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if (self.compare)(&self.data[index], &self.data[parent]) != Ordering::Greater {
+                break;
+            }
+            self.data.swap(index, parent);
+            index = parent;
+        }
+    }
+    // Move a segment down until neither child is greater
+    fn sift_down(&mut self, mut index: usize) {
+        let len = self.len();
+        loop {
+            let mut largest = index;
+            for child in [2 * index + 1, 2 * index + 2] {
+                if child < len
+                    && (self.compare)(&self.data[child], &self.data[largest]) == Ordering::Greater
+                {
+                    largest = child;
+                }
+            }
+            if largest == index {
+                break;
+            }
+            self.data.swap(index, largest);
+            index = largest;
+        }
+    }
+}
+
+impl<T> fmt::Debug for SlicedBinaryHeap<T>
+where
+    T: Copy + Clone + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SlicedBinaryHeap")
+            .field("data", &self.data)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,447 @@
+use std::ops::Index;
+
+use crate::{slicedslab::SlicedSlab, slicedvec::SlicedVec};
+
+/// A position in a change journal.
+///
+/// Returned by `checkpoint` and passed to `rollback_to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Checkpoint(usize);
+
+// A reversible edit of a `JournaledVec`
+#[derive(Debug, Clone, Copy)]
+enum VecChange {
+    Push,
+    Insert(usize),
+    // Changes below saved the prior segment
+    Pop,
+    Overwrite(usize),
+    Remove(usize),
+    SwapRemove(usize),
+}
+
+/// A `SlicedVec` that records edits so they can be undone.
+///
+/// Each edit logs its kind and index, and edits that destroy
+/// data also save the prior segment contents in a separate
+/// `SlicedVec`. Undoing replays the log backward, so reverting
+/// a few edits never clones the whole vector. Mutation is only
+/// possible through the journaled methods.
+/// ```
+/// use sliced::*;
+/// let mut jv = JournaledVec::new(slicedvec![[1, 2], [3, 4]]);
+/// let start = jv.checkpoint();
+/// jv.push(&[5, 6]);
+/// jv.overwrite(0, &[0, 0]);
+/// jv.swap_remove(1);
+/// assert_eq!(jv.iter().collect::<Vec<_>>(), [[0, 0], [5, 6]]);
+/// assert_eq!(jv.undo(1), 1);
+/// assert_eq!(jv[1], [3, 4]);
+/// jv.rollback_to(start);
+/// assert_eq!(jv.iter().collect::<Vec<_>>(), [[1, 2], [3, 4]]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct JournaledVec<T>
+where
+    T: Copy + Clone,
+{
+    data: SlicedVec<T>,
+    changes: Vec<VecChange>,
+    saved: SlicedVec<T>,
+}
+
+impl<T> JournaledVec<T>
+where
+    T: Copy + Clone,
+{
+    /// Start journaling edits of `data`.
+    pub fn new(data: SlicedVec<T>) -> Self {
+        let saved = SlicedVec::new(data.segment_len());
+        Self {
+            data,
+            changes: Vec::new(),
+            saved,
+        }
+    }
+    /// Returns the number of segments.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+    /// Test if there are no segments.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.data.segment_len()
+    }
+    /// Returns the number of recorded edits.
+    pub fn journal_len(&self) -> usize {
+        self.changes.len()
+    }
+    /// Get a reference to a segment.
+    pub fn get(&self, index: usize) -> Option<&[T]> {
+        self.data.get(index)
+    }
+    /// Iterate over the segments.
+    pub fn iter(&self) -> impl Iterator<Item = &[T]> {
+        self.data.iter()
+    }
+    /// Append a segment.
+    ///
+    /// # Panics
+    /// If the length of the slice does
+    /// not match the segments size of the vector.
+    pub fn push(&mut self, segment: &[T]) {
+        assert_eq!(segment.len(), self.segment_len());
+        self.data.push(segment);
+        self.changes.push(VecChange::Push);
+    }
+    /// Remove and return the last segment.
+    pub fn pop(&mut self) -> Option<Vec<T>> {
+        let segment = self.data.pop()?;
+        self.saved.push(&segment);
+        self.changes.push(VecChange::Pop);
+        Some(segment)
+    }
+    /// Replace the contents of a segment.
+    ///
+    /// # Panics
+    /// If `index` is out of range or the length
+    /// of the slice does not match.
+    pub fn overwrite(&mut self, index: usize, segment: &[T]) {
+        self.modify(index, |dst| dst.copy_from_slice(segment))
+    }
+    /// Edit a segment in place with `f`.
+    ///
+    /// The prior contents are saved before `f` runs.
+    /// # Panics
+    /// If `index` is out of range.
+    pub fn modify<F>(&mut self, index: usize, f: F)
+    where
+        F: FnOnce(&mut [T]),
+    {
+        self.saved.push(&self.data[index]);
+        self.changes.push(VecChange::Overwrite(index));
+        f(&mut self.data[index])
+    }
+    /// Insert a segment at `index`, shifting later segments.
+    ///
+    /// # Panics
+    /// If `index` is out of range or the length
+    /// of the slice does not match.
+    pub fn insert(&mut self, index: usize, segment: &[T]) {
+        self.data.insert(index, segment);
+        self.changes.push(VecChange::Insert(index));
+    }
+    /// Remove and return a segment, shifting later segments.
+    ///
+    /// # Panics
+    /// If `index` is out of range.
+    pub fn remove(&mut self, index: usize) -> Vec<T> {
+        assert!(index < self.len());
+        let range = self.data.storage_range(index);
+        let segment: Vec<T> = self.data.storage.drain(range).collect();
+        self.saved.push(&segment);
+        self.changes.push(VecChange::Remove(index));
+        segment
+    }
+    /// Remove and return a segment, replacing it with the last.
+    ///
+    /// # Panics
+    /// If `index` is out of range.
+    pub fn swap_remove(&mut self, index: usize) -> Vec<T> {
+        let segment = self.data.swap_remove(index);
+        self.saved.push(&segment);
+        self.changes.push(VecChange::SwapRemove(index));
+        segment
+    }
+    /// Mark the current position in the journal.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.changes.len())
+    }
+    /// Revert up to `n` of the most recent edits.
+    ///
+    /// Returns the number of edits reverted.
+    pub fn undo(&mut self, n: usize) -> usize {
+        let n = n.min(self.changes.len());
+        for _ in 0..n {
+            let change = self.changes.pop().unwrap();
+            self.revert(change);
+        }
+        n
+    }
+    /// Revert all edits made after `checkpoint`.
+    ///
+    /// # Panics
+    /// If edits before `checkpoint` were already undone
+    /// or forgotten.
+    pub fn rollback_to(&mut self, checkpoint: Checkpoint) {
+        assert!(checkpoint.0 <= self.changes.len());
+        self.undo(self.changes.len() - checkpoint.0);
+    }
+    /// Keep the current contents and forget all recorded edits.
+    ///
+    /// Earlier checkpoints are invalidated.
+    pub fn clear_journal(&mut self) {
+        self.changes.clear();
+        self.saved.clear();
+    }
+    /// Get the current contents.
+    pub fn as_sliced_vec(&self) -> &SlicedVec<T> {
+        &self.data
+    }
+    /// Stop journaling and return the current contents.
+    pub fn into_sliced_vec(self) -> SlicedVec<T> {
+        self.data
+    }
+    // Apply the inverse of one edit
+    fn revert(&mut self, change: VecChange) {
+        match change {
+            VecChange::Push => {
+                self.data.pop();
+            }
+            VecChange::Insert(index) => {
+                let range = self.data.storage_range(index);
+                self.data.storage.drain(range);
+            }
+            VecChange::Pop => {
+                self.data.push(self.saved.last().unwrap());
+                self.saved.truncate(self.saved.len() - 1);
+            }
+            VecChange::Overwrite(index) => {
+                self.data[index].copy_from_slice(self.saved.last().unwrap());
+                self.saved.truncate(self.saved.len() - 1);
+            }
+            VecChange::Remove(index) => {
+                if index == self.data.len() {
+                    self.data.push(self.saved.last().unwrap());
+                } else {
+                    self.data.insert(index, self.saved.last().unwrap());
+                }
+                self.saved.truncate(self.saved.len() - 1);
+            }
+            VecChange::SwapRemove(index) => {
+                self.data.push(self.saved.last().unwrap());
+                self.data.swap(index, self.data.len() - 1);
+                self.saved.truncate(self.saved.len() - 1);
+            }
+        }
+    }
+}
+
+/// Get a segment by position.
+///
+/// # Panics
+/// If `index` is out of range.
+impl<T> Index<usize> for JournaledVec<T>
+where
+    T: Copy + Clone,
+{
+    type Output = [T];
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.data[index]
+    }
+}
+
+// A reversible edit of a `JournaledSlab`
+#[derive(Debug, Clone, Copy)]
+enum SlabChange {
+    // Insert that reused an open slot or appended one
+    Insert { key: usize, appended: bool },
+    // Changes below saved the prior segment
+    Release(usize),
+    Overwrite(usize),
+}
+
+/// A `SlicedSlab` that records edits so they can be undone.
+///
+/// Works like `JournaledVec`. Undo restores keys as well as
+/// contents, including the order of the free list, so keys
+/// handed out after a rollback match those before it.
+/// ```
+/// use sliced::*;
+/// let mut js = JournaledSlab::new(2);
+/// let a = js.insert(&[1, 2]);
+/// let start = js.checkpoint();
+/// js.release(a);
+/// let b = js.insert(&[3, 4]);
+/// js.insert(&[5, 6]);
+/// assert_eq!(a, b);
+/// js.rollback_to(start);
+/// assert_eq!(js[a], [1, 2]);
+/// assert_eq!(js.as_slab().len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct JournaledSlab<T>
+where
+    T: Copy + Clone,
+{
+    slab: SlicedSlab<T>,
+    changes: Vec<SlabChange>,
+    saved: SlicedVec<T>,
+}
+
+impl<T> JournaledSlab<T>
+where
+    T: Copy + Clone,
+{
+    /// Construct an empty journaled slab.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn new(segment_len: usize) -> Self {
+        Self {
+            slab: SlicedSlab::new(segment_len),
+            changes: Vec::new(),
+            saved: SlicedVec::new(segment_len),
+        }
+    }
+    /// Returns the number of occupied slots.
+    pub fn len(&self) -> usize {
+        self.slab.occupied_len()
+    }
+    /// Test if there are no occupied slots.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.slab.segment_len()
+    }
+    /// Returns the number of recorded edits.
+    pub fn journal_len(&self) -> usize {
+        self.changes.len()
+    }
+    /// Test if `key` is occupied.
+    pub fn contains_key(&self, key: usize) -> bool {
+        self.slab.contains_key(key)
+    }
+    /// Get a reference to an occupied segment.
+    pub fn get(&self, key: usize) -> Option<&[T]> {
+        self.slab.get(key)
+    }
+    /// Insert a segment and return its key.
+    ///
+    /// # Panics
+    /// If the length of the slice does
+    /// not match the segments size of the slab.
+    pub fn insert(&mut self, segment: &[T]) -> usize {
+        let len = self.slab.len();
+        let key = self.slab.insert(segment);
+        self.changes.push(SlabChange::Insert {
+            key,
+            appended: key == len,
+        });
+        key
+    }
+    /// Release an occupied slot.
+    ///
+    /// # Panics
+    /// If the slot is not occupied.
+    pub fn release(&mut self, key: usize) {
+        assert!(self.slab.contains_key(key));
+        self.saved.push(&self.slab[key]);
+        self.changes.push(SlabChange::Release(key));
+        self.slab.release(key);
+    }
+    /// Replace the contents of an occupied segment.
+    ///
+    /// # Panics
+    /// If the slot is not occupied or the length
+    /// of the slice does not match.
+    pub fn overwrite(&mut self, key: usize, segment: &[T]) {
+        self.modify(key, |dst| dst.copy_from_slice(segment))
+    }
+    /// Edit an occupied segment in place with `f`.
+    ///
+    /// The prior contents are saved before `f` runs.
+    /// # Panics
+    /// If the slot is not occupied.
+    pub fn modify<F>(&mut self, key: usize, f: F)
+    where
+        F: FnOnce(&mut [T]),
+    {
+        assert!(self.slab.contains_key(key));
+        self.saved.push(&self.slab[key]);
+        self.changes.push(SlabChange::Overwrite(key));
+        f(&mut self.slab[key])
+    }
+    /// Mark the current position in the journal.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.changes.len())
+    }
+    /// Revert up to `n` of the most recent edits.
+    ///
+    /// Returns the number of edits reverted.
+    pub fn undo(&mut self, n: usize) -> usize {
+        let n = n.min(self.changes.len());
+        for _ in 0..n {
+            let change = self.changes.pop().unwrap();
+            self.revert(change);
+        }
+        n
+    }
+    /// Revert all edits made after `checkpoint`.
+    ///
+    /// # Panics
+    /// If edits before `checkpoint` were already undone
+    /// or forgotten.
+    pub fn rollback_to(&mut self, checkpoint: Checkpoint) {
+        assert!(checkpoint.0 <= self.changes.len());
+        self.undo(self.changes.len() - checkpoint.0);
+    }
+    /// Keep the current contents and forget all recorded edits.
+    ///
+    /// Earlier checkpoints are invalidated.
+    pub fn clear_journal(&mut self) {
+        self.changes.clear();
+        self.saved.clear();
+    }
+    /// Get the current slab.
+    pub fn as_slab(&self) -> &SlicedSlab<T> {
+        &self.slab
+    }
+    /// Stop journaling and return the current slab.
+    pub fn into_slab(self) -> SlicedSlab<T> {
+        self.slab
+    }
+    // Apply the inverse of one edit
+    fn revert(&mut self, change: SlabChange) {
+        match change {
+            SlabChange::Insert { key, appended } => {
+                if appended {
+                    self.slab.pop_slot(key);
+                } else {
+                    self.slab.release(key);
+                }
+            }
+            SlabChange::Release(key) => {
+                self.slab
+                    .insert_at(key, self.saved.last().unwrap())
+                    .unwrap();
+                self.saved.truncate(self.saved.len() - 1);
+            }
+            SlabChange::Overwrite(key) => {
+                self.slab[key].copy_from_slice(self.saved.last().unwrap());
+                self.saved.truncate(self.saved.len() - 1);
+            }
+        }
+    }
+}
+
+/// Get a segment by key.
+///
+/// Like `SlicedSlab`, this returns whatever is stored
+/// in the slot, occupied or not.
+/// # Panics
+/// If `key` is out of range.
+impl<T> Index<usize> for JournaledSlab<T>
+where
+    T: Copy + Clone,
+{
+    type Output = [T];
+    fn index(&self, key: usize) -> &Self::Output {
+        &self.slab[key]
+    }
+}
@@ -0,0 +1,262 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+};
+
+use crate::slicedslab::SlicedSlab;
+
+/// A typed key into a `KeyedSlab`.
+///
+/// The marker `B` brands the key to slabs of the same brand,
+/// so keys from differently branded slabs cannot be mixed up
+/// with each other or with segment indices. Conversions to and
+/// from `usize` are available for interoperating with `SlicedSlab`.
+/// ```
+/// use sliced::*;
+/// let key: Key = Key::new(3);
+/// assert_eq!(usize::from(key), 3);
+/// assert_eq!(Key::<()>::from(3), key);
+/// ```
+pub struct Key<B = ()> {
+    index: usize,
+    brand: PhantomData<fn() -> B>,
+}
+
+impl<B> Key<B> {
+    /// Construct a key from a slot index.
+    pub fn new(index: usize) -> Self {
+        Self {
+            index,
+            brand: PhantomData,
+        }
+    }
+    /// Returns the slot index.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+// Manual impls so that the brand need not implement anything
+
+impl<B> Clone for Key<B> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<B> Copy for Key<B> {}
+
+impl<B> PartialEq for Key<B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<B> Eq for Key<B> {}
+
+impl<B> PartialOrd for Key<B> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<B> Ord for Key<B> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.index.cmp(&other.index)
+    }
+}
+
+impl<B> Hash for Key<B> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state)
+    }
+}
+
+impl<B> fmt::Debug for Key<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Key").field(&self.index).finish()
+    }
+}
+
+impl<B> From<usize> for Key<B> {
+    fn from(index: usize) -> Self {
+        Self::new(index)
+    }
+}
+
+impl<B> From<Key<B>> for usize {
+    fn from(key: Key<B>) -> Self {
+        key.index
+    }
+}
+
+/// A `SlicedSlab` using typed keys.
+///
+/// Choosing a distinct brand per slab makes it a compile
+/// error to use a key with the wrong slab.
+/// ```compile_fail
+/// use sliced::*;
+/// struct Meshes;
+/// struct Lights;
+/// let mut meshes = KeyedSlab::<f32, Meshes>::new(3);
+/// let lights = KeyedSlab::<f32, Lights>::new(3);
+/// let key = meshes.insert(&[0.0; 3]);
+/// let _ = lights.get(key);
+/// ```
+/// ```
+/// use sliced::*;
+/// struct Meshes;
+/// let mut meshes = KeyedSlab::<f32, Meshes>::new(3);
+/// let key = meshes.insert(&[1.0, 2.0, 3.0]);
+/// assert_eq!(meshes[key], [1.0, 2.0, 3.0]);
+/// meshes.release(key);
+/// assert_eq!(meshes.get(key), None);
+/// ```
+pub struct KeyedSlab<T, B = ()>
+where
+    T: Copy + Clone,
+{
+    slab: SlicedSlab<T>,
+    brand: PhantomData<fn() -> B>,
+}
+
+impl<T, B> KeyedSlab<T, B>
+where
+    T: Copy + Clone,
+{
+    /// Construct a new `KeyedSlab`.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn new(segment_len: usize) -> Self {
+        SlicedSlab::new(segment_len).into()
+    }
+    /// Initialize a `KeyedSlab` and set the capacity and segment size.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn with_capacity(segment_len: usize, size: usize) -> Self {
+        SlicedSlab::with_capacity(segment_len, size).into()
+    }
+    /// Returns the number of occupied slots.
+    pub fn len(&self) -> usize {
+        self.slab.occupied_len()
+    }
+    /// Test if there are no occupied slots.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.slab.segment_len()
+    }
+    /// Insert a segment into the slab.
+    ///
+    /// See `SlicedSlab::insert`.
+    pub fn insert(&mut self, segment: &[T]) -> Key<B> {
+        Key::new(self.slab.insert(segment))
+    }
+    /// Acquire a previously released slot.
+    ///
+    /// See `SlicedSlab::acquire`.
+    pub fn acquire(&mut self) -> Option<Key<B>> {
+        self.slab.acquire().map(Key::new)
+    }
+    /// Mark the slot as open for future overwrite.
+    ///
+    /// # Panics
+    /// If the slot is already marked as available.
+    pub fn release(&mut self, key: Key<B>) {
+        self.slab.release(key.index)
+    }
+    /// Test if `key` refers to an occupied slot.
+    pub fn contains_key(&self, key: Key<B>) -> bool {
+        self.slab.contains_key(key.index)
+    }
+    /// Get a reference to a segment.
+    ///
+    /// Returns `None` if `key` is out of range
+    /// or the slot is marked as unoccupied.
+    pub fn get(&self, key: Key<B>) -> Option<&[T]> {
+        self.slab.get(key.index)
+    }
+    /// Get a mutable reference to a segment.
+    ///
+    /// Returns `None` if `key` is out of range
+    /// or the slot is marked as unoccupied.
+    pub fn get_mut(&mut self, key: Key<B>) -> Option<&mut [T]> {
+        self.slab.get_mut(key.index)
+    }
+    /// Iterate over key, slice pairs of occupied slots.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (Key<B>, &[T])> {
+        self.slab.iter().map(|(index, segment)| (Key::new(index), segment))
+    }
+    /// Get a reference to the underlying slab.
+    pub fn as_slab(&self) -> &SlicedSlab<T> {
+        &self.slab
+    }
+    /// Convert into the underlying slab.
+    pub fn into_slab(self) -> SlicedSlab<T> {
+        self.slab
+    }
+}
+
+impl<T, B> From<SlicedSlab<T>> for KeyedSlab<T, B>
+where
+    T: Copy + Clone,
+{
+    fn from(slab: SlicedSlab<T>) -> Self {
+        Self {
+            slab,
+            brand: PhantomData,
+        }
+    }
+}
+
+impl<T, B> Clone for KeyedSlab<T, B>
+where
+    T: Copy + Clone,
+{
+    fn clone(&self) -> Self {
+        self.slab.clone().into()
+    }
+}
+
+impl<T, B> fmt::Debug for KeyedSlab<T, B>
+where
+    T: Copy + Clone + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedSlab").field("slab", &self.slab).finish()
+    }
+}
+
+/// Get segment from slab.
+///
+/// # Panics
+/// If `key` is out of range.
+impl<T, B> Index<Key<B>> for KeyedSlab<T, B>
+where
+    T: Copy + Clone,
+{
+    type Output = [T];
+    fn index(&self, key: Key<B>) -> &Self::Output {
+        &self.slab[key.index]
+    }
+}
+
+/// Get mutable segment from slab.
+///
+/// # Panics
+/// If `key` is out of range.
+impl<T, B> IndexMut<Key<B>> for KeyedSlab<T, B>
+where
+    T: Copy + Clone,
+{
+    fn index_mut(&mut self, key: Key<B>) -> &mut Self::Output {
+        &mut self.slab[key.index]
+    }
+}
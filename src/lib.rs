@@ -48,9 +48,134 @@ pub use slicedvec::*;
 mod slicedslab;
 pub use slicedslab::*;
 
+mod genslab;
+pub use genslab::*;
+
+mod key;
+pub use key::*;
+
+mod handleslab;
+pub use handleslab::*;
+
+mod varslicedslab;
+pub use varslicedslab::*;
+
+mod guard;
+pub use guard::*;
+
+mod cache;
+pub use cache::*;
+
+mod metaslab;
+pub use metaslab::*;
+
+mod frozen;
+pub use frozen::*;
+
+mod journal;
+pub use journal::*;
+
+mod set;
+pub use set::*;
+
+mod map;
+pub use map::*;
+
+mod heap;
+pub use heap::*;
+
+mod sorted;
+pub use sorted::*;
+
+mod bitvec;
+pub use bitvec::*;
+
+mod grid;
+pub use grid::*;
+
+mod neighbors;
+pub use neighbors::*;
+
+mod adjacency;
+pub use adjacency::*;
+
 mod varslicedvec;
 pub use varslicedvec::*;
 
+mod builder;
+pub use builder::*;
+
+mod varslicedslice;
+pub use varslicedslice::*;
+
+mod positionindex;
+pub use positionindex::*;
+
+mod compressed;
+pub use compressed::*;
+
+mod packed;
+pub use packed::*;
+
+mod strings;
+pub use strings::*;
+
+mod deque;
+pub use deque::*;
+
+mod ring;
+pub use ring::*;
+
+mod cow;
+pub use cow::*;
+
+mod concurrent;
+pub use concurrent::*;
+
+mod offset;
+pub use offset::*;
+
+mod format;
+pub use format::*;
+
+mod view;
+pub use view::*;
+
+mod stream;
+pub use stream::*;
+
+mod gpu;
+pub use gpu::*;
+
+#[cfg(feature = "serde")]
+mod serde_impl;
+
+#[cfg(feature = "arrow")]
+mod arrow_impl;
+
+#[cfg(feature = "mmap")]
+mod mmap_impl;
+#[cfg(feature = "mmap")]
+pub use mmap_impl::*;
+
+#[cfg(feature = "nalgebra")]
+mod nalgebra_impl;
+
+#[cfg(feature = "petgraph")]
+mod petgraph_impl;
+#[cfg(feature = "petgraph")]
+pub use petgraph_impl::*;
+
+#[cfg(feature = "rayon")]
+mod rayon_impl;
+#[cfg(feature = "rayon")]
+pub use rayon_impl::*;
+
+#[cfg(feature = "zeroize")]
+mod zeroize_impl;
+#[cfg(feature = "zeroize")]
+pub use zeroize_impl::*;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,8 +240,544 @@ mod tests {
         w.overwrite_remove(0);
         assert_eq!(w.len(), 0);
         assert!(w.is_empty());
+        assert_eq!(w.first(), None);
+        assert_eq!(w.last(), None);
         let a = slicedvec![[1, 2, 3], [4, 5, 6]];
         let aa: Vec<_> = a.into();
         assert_eq!(aa.len(), 6);
     }
+
+    #[test]
+    fn test_varslicedvec_value_traits() {
+        use std::collections::HashSet;
+        let a = varslicedvec![[1, 2], [3]];
+        let mut b = a.clone();
+        assert_eq!(a, b);
+        b.merge_segments(0);
+        assert_ne!(a, b);
+        assert_eq!(b, varslicedvec![[1, 2, 3]]);
+        let set: HashSet<_> = [a.clone(), b, a].into_iter().collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_varslicedvec_offset_overflow() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        let mut vv = VarSlicedVec::<u8, u16>::default();
+        vv.push(&[1; 65000]);
+        let other = vv.clone();
+        let saved = vv.clone();
+        let overflows = [
+            catch_unwind(AssertUnwindSafe(|| vv.push_from_iter([2; 1000]))).map(drop),
+            catch_unwind(AssertUnwindSafe(|| vv.extend_from_other(&other))),
+            catch_unwind(AssertUnwindSafe(|| vv.insert_many(0, [[3; 600].as_slice()]))),
+            catch_unwind(AssertUnwindSafe(|| {
+                vv.push_from_iter((0..10).map(|n| if n < 5 { n } else { panic!() }))
+            }))
+            .map(drop),
+        ];
+        assert!(overflows.iter().all(Result::is_err));
+        assert_eq!(vv, saved);
+        assert_eq!(vv.storage_len(), 65000);
+        let mut sv = slicedvec![[4u8; 300], [5; 300]];
+        assert!(catch_unwind(AssertUnwindSafe(|| vv.append_sliced(&mut sv))).is_err());
+        assert_eq!(vv, saved);
+        assert_eq!(sv.len(), 2);
+    }
+
+    #[test]
+    fn test_compressed() {
+        let segments = [vec![0, u32::MAX, 5, 127, 128, 16384], vec![], vec![9; 10]];
+        let mut cv = CompressedVarSlicedVec::new();
+        segments.iter().for_each(|segment| cv.push(segment));
+        for (decoded, segment) in cv.iter().zip(segments.iter()) {
+            assert_eq!(&decoded.collect::<Vec<_>>(), segment);
+        }
+        assert_eq!(cv.get(3), None);
+    }
+
+    #[test]
+    fn test_packed() {
+        let vv: VarSlicedVec<u8> = (0..5000).map(|n| vec![0; n % 13]).collect();
+        let lengths = vv.lengths();
+        let pv = vv.clone().into_packed();
+        assert_eq!(pv.extent_bits(), 15);
+        assert!(pv.iter().map(|s| s.len()).eq(lengths.iter().copied()));
+        assert_eq!(pv.unpack::<u32>().lengths(), lengths);
+        let empty = VarSlicedVec::<u8>::new().into_packed();
+        assert!(empty.is_empty());
+        assert_eq!(empty.extent_bits(), 0);
+    }
+
+    #[test]
+    fn test_deque() {
+        let mut vd = VarSlicedDeque::new();
+        let mut expected = std::collections::VecDeque::new();
+        for n in 0..200 {
+            let segment: Vec<_> = (0..n % 5).collect();
+            vd.push_back(&segment);
+            expected.push_back(segment);
+            if n % 3 != 0 {
+                assert_eq!(vd.pop_front(), expected.pop_front());
+            }
+        }
+        assert_eq!(vd.len(), expected.len());
+        assert!(vd.iter().eq(expected.iter().map(|s| s.as_slice())));
+        assert_eq!(vd.back(), expected.back().map(|s| s.as_slice()));
+        assert_eq!(vd.into_var_sliced_vec().len(), expected.len());
+    }
+
+    #[test]
+    fn test_slab_free_list() {
+        let mut ss = SlicedSlab::new(2);
+        let mut expected = std::collections::BTreeMap::new();
+        for n in 0..300usize {
+            let key = ss.insert(&[n, n]);
+            assert!(expected.insert(key, n).is_none());
+            if n % 3 == 0 {
+                let key = *expected.keys().nth(n % expected.len()).unwrap();
+                expected.remove(&key);
+                ss.release(key);
+            }
+            if n % 50 == 49 {
+                ss.compact();
+            }
+        }
+        assert_eq!(ss.occupied_len(), expected.len());
+        assert!((0..ss.len() + 64).all(|k| ss.contains_key(k) == expected.contains_key(&k)));
+        assert!(ss.iter().map(|(k, s)| (k, s[0])).eq(expected.iter().map(|(k, v)| (*k, *v))));
+        let remap = ss.compact_remap();
+        assert_eq!(ss.len(), expected.len());
+        assert_eq!(ss.acquire(), None);
+        for (old, new) in remap {
+            let value = expected.remove(&old).unwrap();
+            assert!(expected.insert(new, value).is_none());
+        }
+        assert!(ss.iter().map(|(k, s)| (k, s[0])).eq(expected.iter().map(|(k, v)| (*k, *v))));
+    }
+
+    #[test]
+    fn test_slab_rekey_compact() {
+        let mut ss = SlicedSlab::from_vec(1, (0..4).collect());
+        ss.release(1);
+        ss.release(0);
+        let keys: Vec<_> = ss.iter_keys().collect();
+        let moved: Vec<_> = keys.into_iter().map(|key| (ss[key][0], ss.rekey(key))).collect();
+        assert_eq!(moved, [(2, 0), (3, 1)]);
+        ss.compact();
+        assert_eq!(ss.sparsity(), 0.0);
+        assert_eq!(ss.len(), 2);
+        assert_eq!(ss.insert(&[4]), 2);
+        let mut ss = SlicedSlab::from_vec(1, (0..200).collect());
+        (0..200).rev().filter(|key| key % 3 != 0).for_each(|key| ss.release(key));
+        ss.compact();
+        assert_eq!(ss.len(), 199);
+        assert_eq!(ss.acquire(), Some(1));
+        assert_eq!(ss.acquire(), Some(2));
+        let mut ss = SlicedSlab::from_vec(1, (0..10).collect());
+        ss.release_many([2, 5, 7, 9]);
+        assert_eq!(ss.insert_at(5, &[50]), Ok(()));
+        assert_eq!(ss.insert_at(9, &[90]), Ok(()));
+        assert_eq!(ss.iter_vacant_keys().collect::<Vec<_>>(), [7, 2]);
+        assert_eq!(ss.rekey(8), 2);
+        ss.release(0);
+        assert_eq!(ss.rekey(6), 0);
+        assert_eq!(ss.rekey(9), 6);
+        assert_eq!(ss.rekey(3), 3);
+        assert_eq!(ss.iter_vacant_keys().collect::<Vec<_>>(), [9, 8, 7]);
+        assert_eq!(ss.vacant_len(), 3);
+        assert_eq!(ss.get_keys(), [0, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_slab_snapshot() {
+        let mut ss = SlicedSlab::new(2);
+        for n in 0..100usize {
+            ss.insert(&[n, n]);
+            if n % 3 == 0 {
+                ss.release(n / 2);
+            }
+        }
+        let snapshot = ss.snapshot();
+        let saved = ss.clone();
+        for (before, after) in [(40, 10), (200, 0)] {
+            for n in 0..before {
+                ss.insert(&[n, 0]);
+            }
+            for key in ss.get_keys().into_iter().take(after) {
+                ss.release(key);
+            }
+            ss.compact();
+            ss.restore(&snapshot);
+            assert_eq!(ss, saved);
+            assert_eq!(ss.len(), saved.len());
+            let mut other = saved.clone();
+            for n in 0..60 {
+                assert_eq!(ss.insert(&[n, n]), other.insert(&[n, n]));
+            }
+            ss.restore(&snapshot);
+        }
+        ss.clear();
+        ss.restore(&snapshot);
+        assert_eq!(ss, saved);
+        // Every mutation path is undone, including the
+        // contents of open slots and the free list order
+        let state = |ss: &SlicedSlab<usize>| {
+            let vacant: Vec<_> = ss.iter_vacant_keys().collect();
+            (ss.slots.storage.clone(), vacant, ss.get_keys())
+        };
+        let before = state(&ss);
+        let edits: [fn(&mut SlicedSlab<usize>); 10] = [
+            |ss| ss[1][0] = 7,
+            |ss| ss.iter_mut().for_each(|(_, s)| s[1] += 1),
+            |ss| ss.retain(|key, s| key % 4 != 0 && { s[0] = 0; true }),
+            |ss| ss.get_disjoint_mut([1, 2]).unwrap()[0].fill(3),
+            |ss| { ss.rekey_all(); ss.compact(); },
+            |ss| { ss.compact_remap(); },
+            |ss| {
+                ss.release_many([1, 2]);
+                ss.insert_at(1, &[5, 5]).unwrap();
+                ss.insert_at(400, &[6, 6]).unwrap();
+            },
+            |ss| { ss.release_many(ss.get_keys()); ss.compact(); },
+            |ss| { let key = ss.get_keys()[3]; ss.rekey(key); },
+            |ss| { ss.insert_with(|s| s.fill(8)); drop(ss.vacant_entry()); },
+        ];
+        for edit in edits {
+            edit(&mut ss);
+            ss.insert(&[9, 9]);
+            ss.restore(&snapshot);
+            assert_eq!(state(&ss), before);
+        }
+        let mut other = ss.clone();
+        other.clear();
+        other.restore(&snapshot);
+        assert_eq!(state(&other), before);
+        let next = ss.snapshot();
+        assert_eq!(next.occupied_len(), ss.occupied_len());
+        ss.discard_snapshot();
+        ss.clear();
+        assert!(ss.is_empty());
+    }
+
+    #[test]
+    fn test_sliced_set() {
+        let mut set = SlicedSet::with_capacity(3, 4);
+        let mut expected = std::collections::HashSet::new();
+        for n in 0..2000u32 {
+            let segment = [n % 97, n % 13, 0];
+            assert_eq!(set.insert(&segment), expected.insert(segment));
+        }
+        assert_eq!(set.len(), expected.len());
+        assert!(expected.iter().all(|segment| set.contains(segment)));
+        assert!(!set.contains(&[1, 2, 3]));
+        assert!(!set.contains(&[1, 2]));
+        set.clear();
+        assert!(set.is_empty());
+        assert!(!set.contains(&[0, 0, 0]));
+    }
+
+    #[test]
+    fn test_sliced_heap() {
+        let mut heap = SlicedBinaryHeap::new_by_key(2, |s: &[u32]| std::cmp::Reverse(s[0]));
+        let mut values: Vec<u32> = (0..500).map(|n| n * 7919 % 1009).collect();
+        values.iter().for_each(|&v| heap.push(&[v, v + 1]));
+        values.sort_unstable();
+        let mut top = [0; 2];
+        for v in values {
+            assert!(heap.pop_into(&mut top));
+            assert_eq!(top, [v, v + 1]);
+        }
+        assert!(!heap.pop_into(&mut top));
+        assert_eq!(heap.pop(), None);
+    }
+
+    #[test]
+    fn test_neighbor_list() {
+        let positions: Vec<f32> = (0..600u32).map(|n| (n * 7919 % 997) as f32 / 100.0).collect();
+        let positions = SlicedVec::from_vec(3, positions);
+        let cutoff = 1.5f32;
+        let lists = NeighborListBuilder::new(cutoff).build(&positions);
+        assert_eq!(lists.len(), positions.len());
+        for (i, p) in positions.iter().enumerate() {
+            let expected: Vec<u32> = positions
+                .iter()
+                .enumerate()
+                .filter(|(j, q)| {
+                    *j != i && p.iter().zip(*q).map(|(a, b)| (a - b) * (a - b)).sum::<f32>() <= cutoff * cutoff
+                })
+                .map(|(j, _)| j as u32)
+                .collect();
+            assert_eq!(lists[i], expected);
+        }
+        assert!(NeighborListBuilder::new(1.0).build(&SlicedVec::new(2)).is_empty());
+    }
+
+    #[test]
+    fn test_concurrent_sliced_vec() {
+        let cv = ConcurrentSlicedVec::new(2);
+        std::thread::scope(|s| {
+            for t in 0..4u32 {
+                let cv = &cv;
+                s.spawn(move || (0..1000).for_each(|n| {
+                    cv.push(&[t, n]);
+                }));
+            }
+        });
+        assert_eq!(cv.len(), 4000);
+        let mut segments: Vec<_> = cv.into_sliced_vec().iter().map(|s| (s[0], s[1])).collect();
+        segments.sort();
+        assert!(segments.into_iter().eq((0..4).flat_map(|t| (0..1000).map(move |n| (t, n)))));
+        assert!(ConcurrentSlicedVec::<u8>::new(3).into_sliced_vec().is_empty());
+    }
+
+    #[test]
+    fn test_journal() {
+        let mut jv = JournaledVec::new(SlicedVec::from_vec(2, (0..20).collect()));
+        let mut history = vec![jv.as_sliced_vec().clone()];
+        for n in 0..60 {
+            let len = jv.len();
+            match n % 6 {
+                0 => jv.push(&[n, n]),
+                1 => jv.overwrite(n as usize % len, &[-n, -n]),
+                2 => jv.insert(n as usize % len, &[n, 0]),
+                3 => drop(jv.remove(n as usize % len)),
+                4 => drop(jv.swap_remove(n as usize % len)),
+                _ => drop(jv.pop()),
+            }
+            history.push(jv.as_sliced_vec().clone());
+        }
+        while let Some(expected) = history.pop() {
+            assert!(jv.iter().eq(expected.iter()));
+            jv.undo(1);
+        }
+        assert_eq!(jv.journal_len(), 0);
+
+        let mut js = JournaledSlab::new(1);
+        let start = js.checkpoint();
+        let keys: Vec<usize> = (0..10).map(|n| js.insert(&[n])).collect();
+        let middle = js.checkpoint();
+        let before = js.as_slab().clone();
+        keys.iter().step_by(3).for_each(|key| js.release(*key));
+        js.modify(1, |s| s[0] = 100);
+        (0..5).for_each(|n| {
+            js.insert(&[n]);
+        });
+        js.rollback_to(middle);
+        assert_eq!(js.as_slab(), &before);
+        js.rollback_to(start);
+        assert!(js.as_slab().is_empty());
+    }
+
+    #[test]
+    fn test_handle_slab() {
+        let mut hs = HandleSlab::new(1);
+        let mut live = Vec::new();
+        for n in 0..200 {
+            live.push((hs.insert(&[n]), n));
+            if n % 3 == 1 {
+                let (handle, _) = live.swap_remove(n % live.len());
+                hs.release(handle);
+            }
+            if n % 40 == 39 {
+                hs.compact();
+                assert_eq!(hs.slot_len(), live.len());
+            }
+            assert!(live.iter().all(|(handle, n)| hs[*handle] == [*n]));
+        }
+        assert_eq!(hs.iter().len(), live.len());
+    }
+
+    #[test]
+    fn test_var_sliced_slab() {
+        let mut vs = VarSlicedSlab::new();
+        let mut expected = std::collections::BTreeMap::new();
+        for n in 0..500usize {
+            let segment: Vec<_> = (0..n * 7 % 11).map(|i| i + n).collect();
+            let key = vs.insert(&segment);
+            assert!(expected.insert(key, segment).is_none());
+            if n % 2 == 0 {
+                let key = *expected.keys().nth(n * 13 % expected.len()).unwrap();
+                expected.remove(&key);
+                vs.release(key);
+            }
+            assert!(vs.free_len() <= vs.storage_len());
+        }
+        assert_eq!(vs.len(), expected.len());
+        assert!(vs.iter().eq(expected.iter().map(|(k, v)| (*k, v.as_slice()))));
+        vs.defragment();
+        let live: usize = expected.values().map(|v| v.len()).sum();
+        assert_eq!(vs.storage_len(), live);
+        assert!(vs.iter().eq(expected.iter().map(|(k, v)| (*k, v.as_slice()))));
+    }
+
+    #[test]
+    fn test_view() {
+        let vv: VarSlicedVec<u16, u32> = (0..100u16).map(|n| 0..n % 7).collect();
+        let bytes = vv.to_bytes();
+        let view = VarSlicedView::<u16>::from_bytes(&bytes).unwrap();
+        assert!(view.iter().eq(vv.iter()));
+        let mut bad = bytes.clone();
+        bad[48..56].copy_from_slice(&99u64.to_ne_bytes());
+        assert_eq!(VarSlicedView::<u16>::from_bytes(&bad).err(), Some(FormatError::Invalid));
+        bad[12..16].reverse();
+        assert_eq!(VarSlicedView::<u16>::from_bytes(&bad).err(), Some(FormatError::Endianness));
+        let sv = SlicedVec::from_vec(4, (0..40i64).collect());
+        let bytes = sv.to_bytes();
+        let view = SlicedView::<i64>::from_bytes(&bytes).unwrap();
+        assert_eq!(view.len(), 10);
+        assert!(view.to_sliced_vec().iter().eq(sv.iter()));
+        let truncated = &bytes[..bytes.len() - 8];
+        assert_eq!(SlicedView::<i64>::from_bytes(truncated).err(), Some(FormatError::Truncated));
+    }
+
+    #[test]
+    fn test_save_load() {
+        use std::io::ErrorKind;
+        let vv: VarSlicedVec<u16, u32> = (0..100u16).map(|n| 0..n % 7).collect();
+        let mut bytes = Vec::new();
+        vv.save_to(&mut bytes).unwrap();
+        assert_eq!(bytes, vv.to_bytes());
+        assert_eq!(VarSlicedVec::<u16, u32>::load_from(bytes.as_slice()).unwrap(), vv);
+        let err = VarSlicedVec::<u16>::load_from(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let mut ss = SlicedSlab::from_vec(3, (0..300u64).collect());
+        ss.release_many([7, 99, 3, 50]);
+        let mut bytes = Vec::new();
+        ss.save_to(&mut bytes).unwrap();
+        let mut loaded = SlicedSlab::<u64>::load_from(bytes.as_slice()).unwrap();
+        assert_eq!(loaded, ss);
+        assert!(loaded.iter_vacant_keys().eq(ss.iter_vacant_keys()));
+        assert_eq!(loaded.insert(&[0; 3]), ss.insert(&[0; 3]));
+        bytes[56..64].copy_from_slice(&1000u64.to_ne_bytes());
+        let err = SlicedSlab::<u64>::load_from(bytes.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        bytes[0] = 0;
+        assert!(SlicedSlab::<u64>::load_from(bytes.as_slice()).is_err());
+        // Open slots that are too large to allocate or
+        // whose keys repeat are rejected
+        for (segment_len, free) in [(1 << 40, vec![0u64]), (u64::MAX, vec![0, 1]), (1, vec![1, 1])] {
+            let len = free.len() as u64;
+            let header = format::Header::new::<u64>(format::SLAB_MAGIC, [segment_len, len]);
+            let mut bytes = header.to_bytes().to_vec();
+            bytes.extend(len.to_ne_bytes());
+            free.iter().for_each(|key| bytes.extend(key.to_ne_bytes()));
+            let err = SlicedSlab::<u64>::load_from(bytes.as_slice()).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn test_stream() {
+        use std::io::ErrorKind;
+        let mut writer = SlicedWriter::with_capacity(Vec::new(), 2, 3).unwrap();
+        writer.push(&[1u16, 2]).unwrap();
+        writer.push(&(3..=10).collect::<Vec<_>>()).unwrap();
+        writer.push(&[11, 12]).unwrap();
+        assert_eq!(writer.len(), 6);
+        let bytes = writer.finish().unwrap();
+        let reader = SlicedReader::<u16, _>::new(bytes.as_slice()).unwrap();
+        let segments: Vec<_> = reader.map(Result::unwrap).collect();
+        assert_eq!(segments.len(), 6);
+        assert_eq!(segments[5], [11, 12]);
+        let mut reader = SlicedReader::<u16, _>::new(&bytes[..bytes.len() - 1]).unwrap();
+        (0..5).for_each(|_| assert!(reader.next_segment().unwrap().is_some()));
+        let err = reader.next_segment().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        assert!(SlicedReader::<u32, _>::new(bytes.as_slice()).is_err());
+        for segment_len in [u64::MAX, 1 << 60] {
+            let header = format::Header::new::<u32>(format::STREAM_MAGIC, [segment_len, 0]);
+            let err = SlicedReader::<u32, _>::new(header.to_bytes().as_slice()).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidData);
+        }
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize() {
+        // The vacated storage between the length and `end`
+        fn vacated(storage: &mut Vec<u8>, end: usize) -> Vec<u8> {
+            let len = storage.len();
+            // Safety: the storage was initialized up to `end`
+            // before being vacated
+            storage.spare_capacity_mut()[..end - len]
+                .iter()
+                .map(|b| unsafe { b.assume_init() })
+                .collect()
+        }
+        let mut sv = SlicedVec::from_vec(2, (1..=10).collect());
+        sv.swap_remove_zeroize(1);
+        sv.pop_zeroize();
+        sv.remove_zeroize(0);
+        assert_eq!(sv.storage, [9, 10, 5, 6]);
+        assert_eq!(vacated(&mut sv.storage, 10), [0; 6]);
+        sv.clear_zeroize();
+        assert_eq!(vacated(&mut sv.storage, 10), [0; 10]);
+        let mut vv: VarSlicedVec<u8> = varslicedvec![[1], [2, 3], [4, 5, 6]];
+        vv.remove_zeroize(1);
+        assert_eq!(vv.storage, [1, 4, 5, 6]);
+        assert_eq!(vacated(&mut vv.storage, 6), [0, 0]);
+        let mut ss = SlicedSlab::from_vec(2, (1..=6).collect());
+        ss.set_auto_compact(0.1, |_, _| {});
+        ss.release_zeroize(2);
+        assert_eq!(ss.slots.storage, [1, 2, 3, 4]);
+        assert_eq!(vacated(&mut ss.slots.storage, 6), [0, 0]);
+        ss.release_zeroize(0);
+        assert_eq!(ss.slots.storage, [3, 4]);
+        assert_eq!(vacated(&mut ss.slots.storage, 4), [0, 0]);
+        let mut scrubbed = Scrubbed::new(ss);
+        scrubbed.clear_zeroize();
+        assert!(scrubbed.is_empty());
+        assert_eq!(vacated(&mut scrubbed.slots.storage, 6), [0; 6]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let vv: VarSlicedVec<i32> = varslicedvec![[1, 2], [], [3, 4, 5]];
+        let json = serde_json::to_string(&vv).unwrap();
+        assert_eq!(json, "[[1,2],[],[3,4,5]]");
+        let ww: VarSlicedVec<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(ww.lengths(), vec![2, 0, 3]);
+        let bytes = bincode::serialize(&vv).unwrap();
+        let ww: VarSlicedVec<i32> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(ww.offsets(), &[0, 2, 2, 5]);
+        let bad = bincode::serialize(&(vec![1, 2, 3], vec![0usize, 2, 1, 3])).unwrap();
+        assert!(bincode::deserialize::<VarSlicedVec<i32>>(&bad).is_err());
+        let mut ss = SlicedSlab::from_vec(2, (0..8).collect::<Vec<i32>>());
+        ss.release(1);
+        ss.release(2);
+        let json = serde_json::to_string(&ss).unwrap();
+        assert_eq!(json, r#"[2,{"0":[0,1],"3":[6,7]}]"#);
+        let mut tt: SlicedSlab<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(tt, ss);
+        assert_eq!(tt.insert(&[9, 9]), 1);
+        let bytes = bincode::serialize(&ss).unwrap();
+        assert_eq!(bincode::deserialize::<SlicedSlab<i32>>(&bytes).unwrap(), ss);
+        assert!(serde_json::from_str::<SlicedSlab<i32>>(r#"[2,{"0":[1]}]"#).is_err());
+        let huge = format!(r#"[2,{{"{}":[1,2]}}]"#, usize::MAX - 1);
+        assert!(serde_json::from_str::<SlicedSlab<i32>>(&huge).is_err());
+        let huge = format!(r#"[1,{{"{}":[1]}}]"#, usize::MAX);
+        assert!(serde_json::from_str::<SlicedSlab<i32>>(&huge).is_err());
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn test_arrow() {
+        use arrow_array::{types::Int64Type, Array};
+        let vv: VarSlicedVec<i64> = varslicedvec![[1, 2], [], [3, 4, 5]];
+        let ptr = vv[0].as_ptr();
+        let list = vv.into_large_list_array::<Int64Type>();
+        let vv = VarSlicedVec::<i64>::from_list_array::<Int64Type, _>(list.slice(2, 1));
+        assert_eq!(vv.offsets(), &[0, 3]);
+        assert_eq!(vv[0], [3, 4, 5]);
+        let vv = VarSlicedVec::<i64, u32>::from_list_array::<Int64Type, _>(list);
+        assert_eq!(vv[0].as_ptr(), ptr);
+        let sv = slicedvec![[1, 2], [3, 4], [5, 6]];
+        let list = sv.into_fixed_size_list_array::<Int64Type>().slice(1, 2);
+        assert_eq!(list.len(), 2);
+        let sv = SlicedVec::from_fixed_size_list_array::<Int64Type>(list);
+        assert_eq!(sv[0], [3, 4]);
+        assert_eq!(sv.len(), 2);
+    }
 }
@@ -0,0 +1,311 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hash},
+    ops::Index,
+};
+
+use crate::{set::SlicedSet, slicedvec::SlicedVec};
+
+/// A hash map from fixed-length segment keys to values.
+///
+/// Keys are stored contiguously in a `SlicedSet` and values in a
+/// parallel `Vec`, both in insertion order.
+/// ```
+/// use sliced::*;
+/// let seq = b"ACGTACGTAC";
+/// let mut counts = SlicedMap::new(3);
+/// for kmer in seq.windows(3) {
+///     *counts.entry(kmer).or_insert(0) += 1;
+/// }
+/// assert_eq!(counts.len(), 4);
+/// assert_eq!(counts.get(b"ACG"), Some(&2));
+/// assert_eq!(counts[b"GTA".as_slice()], 2);
+/// assert_eq!(counts.get(b"TAC"), Some(&2));
+/// assert_eq!(counts.get(b"AAA"), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SlicedMap<T, V, S = RandomState>
+where
+    T: Copy + Clone,
+{
+    keys: SlicedSet<T, S>,
+    values: Vec<V>,
+}
+
+impl<T, V> SlicedMap<T, V, RandomState>
+where
+    T: Copy + Clone + Hash + Eq,
+{
+    /// Construct a new `SlicedMap`.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn new(segment_len: usize) -> Self {
+        Self::with_hasher(segment_len, RandomState::new())
+    }
+    /// Initialize a `SlicedMap` with room for `size` keys.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn with_capacity(segment_len: usize, size: usize) -> Self {
+        let mut map = Self::new(segment_len);
+        map.reserve(size);
+        map
+    }
+}
+
+impl<T, V, S> SlicedMap<T, V, S>
+where
+    T: Copy + Clone + Hash + Eq,
+    S: BuildHasher,
+{
+    /// Construct a new `SlicedMap` using `hasher` to hash keys.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn with_hasher(segment_len: usize, hasher: S) -> Self {
+        Self {
+            keys: SlicedSet::with_hasher(segment_len, hasher),
+            values: Vec::new(),
+        }
+    }
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+    /// Test if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+    /// Returns the length of each key.
+    pub fn segment_len(&self) -> usize {
+        self.keys.segment_len()
+    }
+    /// Reserve room for at least `additional` more keys.
+    pub fn reserve(&mut self, additional: usize) {
+        self.keys.reserve(additional);
+        self.values.reserve(additional);
+    }
+    /// Insert a key, value pair.
+    ///
+    /// Returns the previous value if the key was present.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut map = SlicedMap::new(2);
+    /// assert_eq!(map.insert(&[1, 2], "a"), None);
+    /// assert_eq!(map.insert(&[1, 2], "b"), Some("a"));
+    /// assert_eq!(map.get(&[1, 2]), Some(&"b"));
+    /// ```
+    /// # Panics
+    /// If the length of the key does
+    /// not match the segments size of the map.
+    pub fn insert(&mut self, key: &[T], value: V) -> Option<V> {
+        match self.entry(key) {
+            MapEntry::Occupied(mut entry) => Some(entry.insert(value)),
+            MapEntry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+    /// Get the entry for `key` for in-place manipulation.
+    ///
+    /// # Panics
+    /// If the length of the key does
+    /// not match the segments size of the map.
+    pub fn entry<'a>(&'a mut self, key: &'a [T]) -> MapEntry<'a, T, V, S> {
+        assert_eq!(key.len(), self.segment_len());
+        self.keys.reserve(1);
+        let hash = self.keys.hash(key);
+        match self.keys.find(hash, key) {
+            Ok(index) => MapEntry::Occupied(OccupiedMapEntry { map: self, index }),
+            Err(bucket) => MapEntry::Vacant(VacantMapEntry {
+                map: self,
+                key,
+                hash,
+                bucket,
+            }),
+        }
+    }
+    /// Test if the map contains `key`.
+    pub fn contains_key(&self, key: &[T]) -> bool {
+        self.keys.contains(key)
+    }
+    /// Get a reference to the value of `key`.
+    pub fn get(&self, key: &[T]) -> Option<&V> {
+        Some(&self.values[self.keys.get_index_of(key)?])
+    }
+    /// Get a mutable reference to the value of `key`.
+    pub fn get_mut(&mut self, key: &[T]) -> Option<&mut V> {
+        let index = self.keys.get_index_of(key)?;
+        Some(&mut self.values[index])
+    }
+    /// Iterate over key, value pairs in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&[T], &V)> {
+        self.keys.iter().zip(self.values.iter())
+    }
+    /// Iterate over key, mutable value pairs in insertion order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&[T], &mut V)> {
+        self.keys.iter().zip(self.values.iter_mut())
+    }
+    /// Iterate over keys in insertion order.
+    pub fn keys(&self) -> impl Iterator<Item = &[T]> {
+        self.keys.iter()
+    }
+    /// Get the values in insertion order.
+    pub fn values(&self) -> &[V] {
+        &self.values
+    }
+    /// Get the mutable values in insertion order.
+    pub fn values_mut(&mut self) -> &mut [V] {
+        &mut self.values
+    }
+    /// Remove all entries, keeping the allocated storage.
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.values.clear();
+    }
+    /// Convert into the keys and values in insertion order.
+    pub fn into_parts(self) -> (SlicedVec<T>, Vec<V>) {
+        (self.keys.into_sliced_vec(), self.values)
+    }
+}
+
+/// Get the value of a key.
+///
+/// # Panics
+/// If the key is not present.
+impl<T, V, S> Index<&[T]> for SlicedMap<T, V, S>
+where
+    T: Copy + Clone + Hash + Eq,
+    S: BuildHasher,
+{
+    type Output = V;
+    fn index(&self, key: &[T]) -> &Self::Output {
+        self.get(key).expect("key not found")
+    }
+}
+
+/// An entry of a `SlicedMap` returned by `SlicedMap::entry`
+#[derive(Debug)]
+pub enum MapEntry<'a, T, V, S>
+where
+    T: Copy + Clone,
+{
+    /// The key is present
+    Occupied(OccupiedMapEntry<'a, T, V, S>),
+    /// The key is absent
+    Vacant(VacantMapEntry<'a, T, V, S>),
+}
+
+impl<'a, T, V, S> MapEntry<'a, T, V, S>
+where
+    T: Copy + Clone + Hash + Eq,
+    S: BuildHasher,
+{
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &[T] {
+        match self {
+            Self::Occupied(entry) => entry.key(),
+            Self::Vacant(entry) => entry.key,
+        }
+    }
+    /// Insert `value` if absent and return the value.
+    pub fn or_insert(self, value: V) -> &'a mut V {
+        self.or_insert_with(|| value)
+    }
+    /// Insert the result of `f` if absent and return the value.
+    pub fn or_insert_with<F>(self, f: F) -> &'a mut V
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => entry.insert(f()),
+        }
+    }
+    /// Insert a default value if absent and return the value.
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+    /// Modify the value if present.
+    pub fn and_modify<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        if let Self::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+/// An entry whose key is present in a `SlicedMap`
+#[derive(Debug)]
+pub struct OccupiedMapEntry<'a, T, V, S>
+where
+    T: Copy + Clone,
+{
+    map: &'a mut SlicedMap<T, V, S>,
+    index: usize,
+}
+
+impl<'a, T, V, S> OccupiedMapEntry<'a, T, V, S>
+where
+    T: Copy + Clone + Hash + Eq,
+    S: BuildHasher,
+{
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &[T] {
+        self.map.keys.get_index(self.index).unwrap()
+    }
+    /// Get a reference to the value.
+    pub fn get(&self) -> &V {
+        &self.map.values[self.index]
+    }
+    /// Get a mutable reference to the value.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.values[self.index]
+    }
+    /// Convert into a mutable reference to the value.
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.values[self.index]
+    }
+    /// Replace the value, returning the previous one.
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+}
+
+/// An entry whose key is absent from a `SlicedMap`
+#[derive(Debug)]
+pub struct VacantMapEntry<'a, T, V, S>
+where
+    T: Copy + Clone,
+{
+    map: &'a mut SlicedMap<T, V, S>,
+    key: &'a [T],
+    hash: u64,
+    bucket: usize,
+}
+
+impl<'a, T, V, S> VacantMapEntry<'a, T, V, S>
+where
+    T: Copy + Clone + Hash + Eq,
+    S: BuildHasher,
+{
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &[T] {
+        self.key
+    }
+    /// Insert the key with `value` and return the value.
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.map.keys.push_at(self.bucket, self.hash, self.key);
+        self.map.values.push(value);
+        self.map.values.last_mut().unwrap()
+    }
+}
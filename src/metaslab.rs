@@ -0,0 +1,192 @@
+use std::ops::{Index, IndexMut};
+
+use crate::slicedslab::SlicedSlab;
+
+/// A `SlicedSlab` with a metadata value for each slot.
+///
+/// The metadata lives in a vector parallel to the slots and
+/// is moved along with its segment by `compact`, so it stays
+/// aligned with keys without any bookkeeping by the caller.
+/// ```
+/// use sliced::*;
+/// let mut ms = MetaSlab::new(2);
+/// let a = ms.insert(&[1, 2], 10u64);
+/// let b = ms.insert(&[3, 4], 20);
+/// ms.release(a);
+/// *ms.meta_mut(b).unwrap() += 1;
+/// let moved = ms.compact();
+/// assert_eq!(moved, [(1, 0)]);
+/// assert_eq!(ms[0], [3, 4]);
+/// assert_eq!(ms.meta(0), Some(&21));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MetaSlab<T, M>
+where
+    T: Copy + Clone,
+    M: Copy + Clone,
+{
+    slab: SlicedSlab<T>,
+    meta: Vec<M>,
+}
+
+impl<T, M> MetaSlab<T, M>
+where
+    T: Copy + Clone,
+    M: Copy + Clone,
+{
+    /// Construct a new `MetaSlab`.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn new(segment_len: usize) -> Self {
+        Self {
+            slab: SlicedSlab::new(segment_len),
+            meta: Vec::new(),
+        }
+    }
+    /// Initialize a `MetaSlab` and set the capacity and segment size.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn with_capacity(segment_len: usize, size: usize) -> Self {
+        Self {
+            slab: SlicedSlab::with_capacity(segment_len, size),
+            meta: Vec::with_capacity(size),
+        }
+    }
+    /// Returns the number of occupied slots.
+    pub fn len(&self) -> usize {
+        self.slab.occupied_len()
+    }
+    /// Test if there are no occupied slots.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.slab.segment_len()
+    }
+    /// Insert a segment with its metadata and return its key.
+    ///
+    /// # Panics
+    /// If the length of the slice does
+    /// not match the segments size of the slab.
+    pub fn insert(&mut self, segment: &[T], meta: M) -> usize {
+        let key = self.slab.insert(segment);
+        if key == self.meta.len() {
+            self.meta.push(meta);
+        } else {
+            self.meta[key] = meta;
+        }
+        key
+    }
+    /// Release a slot, returning its metadata.
+    ///
+    /// # Panics
+    /// If the slot is already marked as available.
+    pub fn release(&mut self, key: usize) -> M {
+        self.slab.release(key);
+        self.meta[key]
+    }
+    /// Test if `key` refers to an occupied slot.
+    pub fn contains_key(&self, key: usize) -> bool {
+        self.slab.contains_key(key)
+    }
+    /// Get a reference to a segment.
+    ///
+    /// Returns `None` if `key` is out of range
+    /// or the slot is marked as unoccupied.
+    pub fn get(&self, key: usize) -> Option<&[T]> {
+        self.slab.get(key)
+    }
+    /// Get a mutable reference to a segment.
+    ///
+    /// Returns `None` if `key` is out of range
+    /// or the slot is marked as unoccupied.
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut [T]> {
+        self.slab.get_mut(key)
+    }
+    /// Get the metadata of an occupied slot.
+    pub fn meta(&self, key: usize) -> Option<&M> {
+        self.contains_key(key).then(|| &self.meta[key])
+    }
+    /// Get the mutable metadata of an occupied slot.
+    pub fn meta_mut(&mut self, key: usize) -> Option<&mut M> {
+        self.contains_key(key).then(|| &mut self.meta[key])
+    }
+    /// Move segments and their metadata down into open slots.
+    ///
+    /// Returns the `(old_key, new_key)` pairs that moved.
+    pub fn compact(&mut self) -> Vec<(usize, usize)> {
+        let Self { slab, meta } = self;
+        let mut moved = Vec::new();
+        slab.compact_with(|old, new| {
+            meta[new] = meta[old];
+            moved.push((old, new));
+        });
+        meta.truncate(slab.len());
+        moved
+    }
+    /// Release every occupied slot for which `f` returns `false`.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut ms = MetaSlab::new(1);
+    /// (0..6).for_each(|n| { ms.insert(&[n], n % 2 == 0); });
+    /// ms.retain(|_, _, even| *even);
+    /// assert_eq!(ms.iter().map(|(key, _, _)| key).collect::<Vec<_>>(), [0, 2, 4]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, &mut [T], &mut M) -> bool,
+    {
+        let meta = &mut self.meta;
+        self.slab
+            .retain(|key, segment| f(key, segment, &mut meta[key]));
+    }
+    /// Iterate over key, slice, metadata triples of occupied slots.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (usize, &[T], &M)> {
+        self.slab
+            .iter()
+            .map(|(key, segment)| (key, segment, &self.meta[key]))
+    }
+    /// Clear the contents.
+    pub fn clear(&mut self) {
+        self.slab.clear();
+        self.meta.clear();
+    }
+    /// Get a reference to the underlying slab.
+    pub fn as_slab(&self) -> &SlicedSlab<T> {
+        &self.slab
+    }
+}
+
+/// Get segment from slab.
+///
+/// # Panics
+/// If `key` is out of range.
+impl<T, M> Index<usize> for MetaSlab<T, M>
+where
+    T: Copy + Clone,
+    M: Copy + Clone,
+{
+    type Output = [T];
+    fn index(&self, key: usize) -> &Self::Output {
+        &self.slab[key]
+    }
+}
+
+/// Get mutable segment from slab.
+///
+/// # Panics
+/// If `key` is out of range.
+impl<T, M> IndexMut<usize> for MetaSlab<T, M>
+where
+    T: Copy + Clone,
+    M: Copy + Clone,
+{
+    fn index_mut(&mut self, key: usize) -> &mut Self::Output {
+        &mut self.slab[key]
+    }
+}
@@ -0,0 +1,311 @@
+use std::{
+    fs::{File, OpenOptions},
+    io,
+    marker::PhantomData,
+    ops::{Index, IndexMut, Range},
+    path::Path,
+};
+
+use memmap2::{Mmap, MmapMut};
+
+use crate::{
+    format::{cast_slice, cast_slice_mut, Pod, HEADER_LEN},
+    varslicedslice::{VarSlicedSlice, VarSlicedSliceMut},
+    view::{SlicedView, VarSlicedView},
+};
+
+// A read-only or read-write mapping of a whole file
+#[derive(Debug)]
+enum Map {
+    ReadOnly(Mmap),
+    ReadWrite(MmapMut),
+}
+
+impl Map {
+    // Safety: the file must not be changed by others while mapped
+    unsafe fn open(path: &Path, writable: bool) -> io::Result<Self> {
+        if writable {
+            let file = OpenOptions::new().read(true).write(true).open(path)?;
+            Ok(Self::ReadWrite(MmapMut::map_mut(&file)?))
+        } else {
+            Ok(Self::ReadOnly(Mmap::map(&File::open(path)?)?))
+        }
+    }
+    // The whole mapping
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Self::ReadOnly(map) => map,
+            Self::ReadWrite(map) => map,
+        }
+    }
+    // The whole mapping, which must be writable
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        match self {
+            Self::ReadOnly(_) => panic!("file is mapped read-only"),
+            Self::ReadWrite(map) => map,
+        }
+    }
+    // Test if mapped read-write
+    fn is_writable(&self) -> bool {
+        matches!(self, Self::ReadWrite(_))
+    }
+    // Write a read-write mapping back to the file
+    fn flush(&self) -> io::Result<()> {
+        match self {
+            Self::ReadOnly(_) => Ok(()),
+            Self::ReadWrite(map) => map.flush(),
+        }
+    }
+}
+
+/// Fixed length segments in a memory-mapped file.
+///
+/// The file holds the binary format written by
+/// `SlicedVec::to_bytes`. It is validated once when opened and
+/// then read in place through a `SlicedView`, so the data may
+/// be larger than memory. Files opened with `open_mut` can be
+/// edited in place and the changes written back with `flush`.
+/// ```
+/// use sliced::*;
+/// let path = std::env::temp_dir().join("sliced_mapped_doctest.bin");
+/// std::fs::write(&path, slicedvec![[1u32, 2], [3, 4]].to_bytes()).unwrap();
+/// let mut mapped = unsafe { MappedSlicedVec::<u32>::open_mut(&path) }.unwrap();
+/// mapped[1][0] = 30;
+/// mapped.flush().unwrap();
+/// let mapped = unsafe { MappedSlicedVec::<u32>::open(&path) }.unwrap();
+/// assert_eq!(mapped.len(), 2);
+/// assert_eq!(mapped.view().iter().collect::<Vec<_>>(), [[1, 2], [30, 4]]);
+/// std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct MappedSlicedVec<T>
+where
+    T: Copy + Clone + Pod,
+{
+    map: Map,
+    segment_len: usize,
+    marker: PhantomData<T>,
+}
+
+impl<T> MappedSlicedVec<T>
+where
+    T: Copy + Clone + Pod,
+{
+    /// Map a file read-only.
+    ///
+    /// Malformed files are reported as `InvalidData`
+    /// wrapping a `FormatError`.
+    /// # Safety
+    /// The file must not be modified or truncated by
+    /// another process while it is mapped.
+    pub unsafe fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_map(Map::open(path.as_ref(), false)?)
+    }
+    /// Map a file for reading and writing.
+    ///
+    /// # Safety
+    /// The file must not be modified or truncated by
+    /// another process while it is mapped.
+    pub unsafe fn open_mut<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_map(Map::open(path.as_ref(), true)?)
+    }
+    // Validate the mapped bytes
+    fn from_map(map: Map) -> io::Result<Self> {
+        let segment_len = SlicedView::<T>::from_bytes(map.bytes())?.segment_len();
+        Ok(Self {
+            map,
+            segment_len,
+            marker: PhantomData,
+        })
+    }
+    /// Test if the file was opened for writing.
+    pub fn is_writable(&self) -> bool {
+        self.map.is_writable()
+    }
+    /// Get a read-only view of the segments.
+    pub fn view(&self) -> SlicedView<'_, T> {
+        SlicedView {
+            storage: cast_slice(&self.map.bytes()[HEADER_LEN..]).unwrap(),
+            segment_len: self.segment_len,
+        }
+    }
+    /// Returns the number of segments.
+    pub fn len(&self) -> usize {
+        self.view().len()
+    }
+    /// Test if there are no segments.
+    pub fn is_empty(&self) -> bool {
+        self.view().is_empty()
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.segment_len
+    }
+    /// Get a reference to a segment.
+    ///
+    /// Returns `None` if `index` is out of range.
+    pub fn get(&self, index: usize) -> Option<&[T]> {
+        self.view().get(index)
+    }
+    /// Get a mutable reference to a segment.
+    ///
+    /// Returns `None` if `index` is out of range.
+    /// # Panics
+    /// If the file is mapped read-only.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut [T]> {
+        let segment_len = self.segment_len;
+        self.storage_mut()
+            .get_mut(index * segment_len..(index + 1) * segment_len)
+    }
+    /// Iterate over the segments.
+    pub fn iter(&self) -> impl Iterator<Item = &[T]> {
+        self.view().iter()
+    }
+    /// Iterate mutably over the segments.
+    ///
+    /// # Panics
+    /// If the file is mapped read-only.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        let segment_len = self.segment_len;
+        self.storage_mut().chunks_exact_mut(segment_len)
+    }
+    /// Write changes back to the file.
+    ///
+    /// Does nothing if the file is mapped read-only.
+    pub fn flush(&self) -> io::Result<()> {
+        self.map.flush()
+    }
+    // Mutable storage after the header
+    fn storage_mut(&mut self) -> &mut [T] {
+        cast_slice_mut(&mut self.map.bytes_mut()[HEADER_LEN..]).unwrap()
+    }
+}
+
+/// Get a segment by position.
+///
+/// # Panics
+/// If `index` is out of range.
+impl<T> Index<usize> for MappedSlicedVec<T>
+where
+    T: Copy + Clone + Pod,
+{
+    type Output = [T];
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("index out of range")
+    }
+}
+
+/// Get a mutable segment by position.
+///
+/// # Panics
+/// If `index` is out of range or the file is mapped read-only.
+impl<T> IndexMut<usize> for MappedSlicedVec<T>
+where
+    T: Copy + Clone + Pod,
+{
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("index out of range")
+    }
+}
+
+/// Variable length segments in a memory-mapped file.
+///
+/// The `VarSlicedVec` analogue of `MappedSlicedVec`, reading the
+/// format written by `VarSlicedVec::to_bytes`. The offsets are
+/// part of the mapping, so segment contents can be edited in
+/// place but segment lengths are fixed.
+/// ```
+/// use sliced::*;
+/// let path = std::env::temp_dir().join("sliced_mapped_var_doctest.bin");
+/// std::fs::write(&path, varslicedvec![[1u16], [2, 3, 4]].to_bytes()).unwrap();
+/// let mut mapped = unsafe { MappedVarSlicedVec::<u16>::open_mut(&path) }.unwrap();
+/// mapped.view_mut()[1][2] = 40;
+/// assert_eq!(mapped.view()[1], [2, 3, 40]);
+/// assert_eq!(mapped.len(), 2);
+/// std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct MappedVarSlicedVec<T>
+where
+    T: Copy + Clone + Pod,
+{
+    map: Map,
+    // Byte ranges of the offsets and the storage
+    extents: Range<usize>,
+    storage: Range<usize>,
+    marker: PhantomData<T>,
+}
+
+impl<T> MappedVarSlicedVec<T>
+where
+    T: Copy + Clone + Pod,
+{
+    /// Map a file read-only.
+    ///
+    /// Malformed files are reported as `InvalidData`
+    /// wrapping a `FormatError`.
+    /// # Safety
+    /// The file must not be modified or truncated by
+    /// another process while it is mapped.
+    pub unsafe fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_map(Map::open(path.as_ref(), false)?)
+    }
+    /// Map a file for reading and writing.
+    ///
+    /// # Safety
+    /// The file must not be modified or truncated by
+    /// another process while it is mapped.
+    pub unsafe fn open_mut<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_map(Map::open(path.as_ref(), true)?)
+    }
+    // Validate the mapped bytes and locate the offsets
+    fn from_map(map: Map) -> io::Result<Self> {
+        let len = VarSlicedView::<T>::from_bytes(map.bytes())?.len();
+        let extents = HEADER_LEN..HEADER_LEN + 8 * (len + 1);
+        let storage = extents.end..map.bytes().len();
+        Ok(Self {
+            map,
+            extents,
+            storage,
+            marker: PhantomData,
+        })
+    }
+    /// Test if the file was opened for writing.
+    pub fn is_writable(&self) -> bool {
+        self.map.is_writable()
+    }
+    /// Get a read-only view of the segments.
+    pub fn view(&self) -> VarSlicedView<'_, T> {
+        let bytes = self.map.bytes();
+        VarSlicedSlice {
+            storage: cast_slice(&bytes[self.storage.clone()]).unwrap(),
+            extents: cast_slice(&bytes[self.extents.clone()]).unwrap(),
+        }
+    }
+    /// Get a mutable view of the segment contents.
+    ///
+    /// # Panics
+    /// If the file is mapped read-only.
+    pub fn view_mut(&mut self) -> VarSlicedSliceMut<'_, T, u64> {
+        let (head, storage) = self.map.bytes_mut().split_at_mut(self.storage.start);
+        VarSlicedSliceMut {
+            storage: cast_slice_mut(storage).unwrap(),
+            extents: cast_slice(&head[self.extents.clone()]).unwrap(),
+            base: 0,
+        }
+    }
+    /// Returns the number of segments.
+    pub fn len(&self) -> usize {
+        self.extents.len() / 8 - 1
+    }
+    /// Test if there are no segments.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Write changes back to the file.
+    ///
+    /// Does nothing if the file is mapped read-only.
+    pub fn flush(&self) -> io::Result<()> {
+        self.map.flush()
+    }
+}
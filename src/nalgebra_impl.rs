@@ -0,0 +1,176 @@
+use nalgebra::{
+    Const, DMatrixView, DMatrixViewMut, DVectorView, DVectorViewMut, Dyn, MatrixView,
+    MatrixViewMut, SVector, SVectorView, SVectorViewMut, Scalar,
+};
+
+use crate::{offset::Offset, slicedvec::SlicedVec, varslicedvec::VarSlicedVec};
+
+impl<T> SlicedVec<T>
+where
+    T: Copy + Clone + Scalar,
+{
+    /// Iterate over the segments as vector views.
+    ///
+    /// The views borrow the storage, nothing is copied.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let sv = slicedvec![[3.0, 4.0], [6.0, 8.0]];
+    /// let norms: Vec<f64> = sv.vector_views().map(|v| v.norm()).collect();
+    /// assert_eq!(norms, [5.0, 10.0]);
+    /// ```
+    pub fn vector_views(&self) -> impl Iterator<Item = DVectorView<'_, T>> {
+        self.iter()
+            .map(|segment| DVectorView::from_slice(segment, segment.len()))
+    }
+    /// Iterate over the segments as mutable vector views.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut sv = slicedvec![[3.0, 4.0], [6.0, 8.0]];
+    /// sv.vector_views_mut().for_each(|mut v| {
+    ///     v.normalize_mut();
+    /// });
+    /// assert_eq!(sv[1], [0.6, 0.8]);
+    /// ```
+    pub fn vector_views_mut(&mut self) -> impl Iterator<Item = DVectorViewMut<'_, T>> {
+        self.iter_mut().map(|segment| {
+            let len = segment.len();
+            DVectorViewMut::from_slice(segment, len)
+        })
+    }
+    /// View the storage as a matrix with one segment per column.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let sv = slicedvec![[1, 2, 3], [4, 5, 6]];
+    /// let m = sv.matrix_view();
+    /// assert_eq!(m.shape(), (3, 2));
+    /// assert_eq!(m[(2, 1)], 6);
+    /// assert_eq!(m.row(0).iter().sum::<i32>(), 5);
+    /// ```
+    pub fn matrix_view(&self) -> DMatrixView<'_, T> {
+        DMatrixView::from_slice(&self.storage, self.segment_len(), self.len())
+    }
+    /// View the storage as a mutable matrix with one segment per column.
+    pub fn matrix_view_mut(&mut self) -> DMatrixViewMut<'_, T> {
+        let (rows, cols) = (self.segment_len(), self.len());
+        DMatrixViewMut::from_slice(&mut self.storage, rows, cols)
+    }
+    /// View the storage as a matrix with `N` rows known at compile time.
+    ///
+    /// Each column is one segment.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let sv = slicedvec![[1.0, 0.0, 0.0], [0.0, 2.0, 0.0]];
+    /// let m = sv.fixed_matrix_view::<3>();
+    /// assert_eq!(m.column(1).norm(), 2.0);
+    /// assert_eq!(m.column_sum(), nalgebra::Vector3::new(1.0, 2.0, 0.0));
+    /// ```
+    /// # Panics
+    /// If `N` is not the segment length.
+    pub fn fixed_matrix_view<const N: usize>(&self) -> MatrixView<'_, T, Const<N>, Dyn> {
+        assert_eq!(N, self.segment_len());
+        MatrixView::from_slice_generic(&self.storage, Const::<N>, Dyn(self.len()))
+    }
+    /// View the storage as a mutable matrix with `N` rows known at compile time.
+    ///
+    /// # Panics
+    /// If `N` is not the segment length.
+    pub fn fixed_matrix_view_mut<const N: usize>(&mut self) -> MatrixViewMut<'_, T, Const<N>, Dyn> {
+        assert_eq!(N, self.segment_len());
+        let cols = Dyn(self.len());
+        MatrixViewMut::from_slice_generic(&mut self.storage, Const::<N>, cols)
+    }
+    /// Iterate over the segments as vector views of length `N`.
+    ///
+    /// # Example
+    /// ```
+    /// use nalgebra::Vector3;
+    /// use sliced::*;
+    /// let mut positions = slicedvec![[0.0, 0.0, 0.0], [1.0, 1.0, 1.0]];
+    /// let velocity = Vector3::new(0.5, 0.0, -1.0);
+    /// positions.fixed_vector_views_mut::<3>().for_each(|mut p| p += velocity);
+    /// let moved: Vec<Vector3<f64>> = positions.fixed_vector_views::<3>().map(|p| p.into()).collect();
+    /// assert_eq!(moved[1], Vector3::new(1.5, 1.0, 0.0));
+    /// ```
+    /// # Panics
+    /// If `N` is not the segment length.
+    pub fn fixed_vector_views<const N: usize>(
+        &self,
+    ) -> impl Iterator<Item = SVectorView<'_, T, N>> {
+        assert_eq!(N, self.segment_len());
+        self.storage.chunks_exact(N).map(SVectorView::from_slice)
+    }
+    /// Iterate over the segments as mutable vector views of length `N`.
+    ///
+    /// # Panics
+    /// If `N` is not the segment length.
+    pub fn fixed_vector_views_mut<const N: usize>(
+        &mut self,
+    ) -> impl Iterator<Item = SVectorViewMut<'_, T, N>> {
+        assert_eq!(N, self.segment_len());
+        self.storage
+            .chunks_exact_mut(N)
+            .map(SVectorViewMut::from_slice)
+    }
+}
+
+/// Copy a vector of fixed size vectors into a `SlicedVec` with one segment per vector.
+///
+/// # Example
+/// ```
+/// use nalgebra::Vector2;
+/// use sliced::*;
+/// let sv = SlicedVec::from(vec![Vector2::new(1, 2), Vector2::new(3, 4)]);
+/// assert_eq!(sv.segment_len(), 2);
+/// assert_eq!(sv[1], [3, 4]);
+/// ```
+/// # Panics
+/// If `N` is zero.
+impl<T, const N: usize> From<Vec<SVector<T, N>>> for SlicedVec<T>
+where
+    T: Copy + Clone + Scalar,
+{
+    fn from(vectors: Vec<SVector<T, N>>) -> Self {
+        Self::from(vectors.as_slice())
+    }
+}
+
+/// Copy a slice of fixed size vectors into a `SlicedVec` with one segment per vector.
+///
+/// # Panics
+/// If `N` is zero.
+impl<T, const N: usize> From<&[SVector<T, N>]> for SlicedVec<T>
+where
+    T: Copy + Clone + Scalar,
+{
+    fn from(vectors: &[SVector<T, N>]) -> Self {
+        let mut sv = Self::with_capacity(N, vectors.len());
+        vectors.iter().for_each(|vector| sv.push(vector.as_slice()));
+        sv
+    }
+}
+
+impl<T, O> VarSlicedVec<T, O>
+where
+    T: Copy + Clone + Scalar,
+    O: Offset,
+{
+    /// Iterate over the segments as vector views.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1, 2, 3], [4], []];
+    /// let sums: Vec<i32> = vv.vector_views().map(|v| v.sum()).collect();
+    /// assert_eq!(sums, [6, 4, 0]);
+    /// ```
+    pub fn vector_views(&self) -> impl Iterator<Item = DVectorView<'_, T>> {
+        self.iter()
+            .map(|segment| DVectorView::from_slice(segment, segment.len()))
+    }
+}
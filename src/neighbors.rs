@@ -0,0 +1,158 @@
+use crate::{slicedvec::SlicedVec, varslicedvec::VarSlicedVec};
+
+/// Builds per-particle neighbor lists using cell binning.
+///
+/// Positions are segments of coordinates in a `SlicedVec<f32>`,
+/// one segment per particle. Particles are binned into cells
+/// the size of the cutoff, so only adjacent cells are searched.
+/// The result holds the sorted indices of the particles within
+/// `cutoff` of each particle, excluding itself. Binning allocates
+/// one cell per cutoff-sized box of the bounding box, so it is
+/// meant for low dimensional positions. With the `rayon` feature,
+/// `par_build` computes the same lists in parallel.
+/// ```
+/// use sliced::*;
+/// let positions = slicedvec![[0.0f32, 0.0], [0.5, 0.0], [3.0, 3.0], [0.0, 0.9]];
+/// let lists = NeighborListBuilder::new(1.0).build(&positions);
+/// assert_eq!(lists[0], [1, 3]);
+/// assert_eq!(lists[1], [0]);
+/// assert!(lists[2].is_empty());
+/// assert_eq!(lists[3], [0]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct NeighborListBuilder {
+    cutoff: f32,
+}
+
+impl NeighborListBuilder {
+    /// Construct a builder for the given cutoff radius.
+    ///
+    /// # Panics
+    /// If `cutoff` is not positive and finite.
+    pub fn new(cutoff: f32) -> Self {
+        assert!(cutoff > 0.0 && cutoff.is_finite());
+        Self { cutoff }
+    }
+    /// Returns the cutoff radius.
+    pub fn cutoff(&self) -> f32 {
+        self.cutoff
+    }
+    /// Build the neighbor lists.
+    pub fn build(&self, positions: &SlicedVec<f32>) -> VarSlicedVec<u32> {
+        let bins = CellBins::new(positions, self.cutoff);
+        bins.build_range(positions, 0..positions.len())
+    }
+}
+
+// Particles sorted by cell in compressed sparse layout
+#[derive(Debug)]
+pub(crate) struct CellBins {
+    dims: Vec<usize>,
+    cutoff2: f32,
+    // Cell of each particle
+    cells: Vec<usize>,
+    // Particles of cell c are members[starts[c]..starts[c + 1]]
+    starts: Vec<usize>,
+    members: Vec<u32>,
+}
+
+impl CellBins {
+    pub(crate) fn new(positions: &SlicedVec<f32>, cutoff: f32) -> Self {
+        let ndim = positions.segment_len();
+        let mut lo = vec![f32::INFINITY; ndim];
+        let mut hi = vec![f32::NEG_INFINITY; ndim];
+        for p in positions.iter() {
+            for d in 0..ndim {
+                lo[d] = lo[d].min(p[d]);
+                hi[d] = hi[d].max(p[d]);
+            }
+        }
+        let dims: Vec<usize> = lo
+            .iter()
+            .zip(&hi)
+            .map(|(lo, hi)| ((hi - lo) / cutoff).max(0.0) as usize + 1)
+            .collect();
+        let cells: Vec<usize> = positions
+            .iter()
+            .map(|p| {
+                let mut cell = 0;
+                for d in (0..ndim).rev() {
+                    let x = (((p[d] - lo[d]) / cutoff) as usize).min(dims[d] - 1);
+                    cell = cell * dims[d] + x;
+                }
+                cell
+            })
+            .collect();
+        let ncells = if positions.is_empty() {
+            0
+        } else {
+            dims.iter().product()
+        };
+        let mut starts = vec![0; ncells + 1];
+        cells.iter().for_each(|cell| starts[cell + 1] += 1);
+        for c in 0..ncells {
+            starts[c + 1] += starts[c];
+        }
+        let mut fill = starts.clone();
+        let mut members = vec![0; cells.len()];
+        for (i, cell) in cells.iter().enumerate() {
+            members[fill[*cell]] = i as u32;
+            fill[*cell] += 1;
+        }
+        Self {
+            dims,
+            cutoff2: cutoff * cutoff,
+            cells,
+            starts,
+            members,
+        }
+    }
+    // Neighbor lists of the particles in `range`
+    pub(crate) fn build_range(
+        &self,
+        positions: &SlicedVec<f32>,
+        range: std::ops::Range<usize>,
+    ) -> VarSlicedVec<u32> {
+        let mut lists = VarSlicedVec::new();
+        let mut found = Vec::new();
+        for i in range {
+            self.neighbors_of(positions, i, &mut found);
+            lists.push(&found);
+        }
+        lists
+    }
+    // Collect the sorted neighbors of particle `i` into `found`
+    fn neighbors_of(&self, positions: &SlicedVec<f32>, i: usize, found: &mut Vec<u32>) {
+        found.clear();
+        let p = &positions[i];
+        for offset in 0..3usize.pow(self.dims.len() as u32) {
+            let Some(cell) = self.adjacent(self.cells[i], offset) else {
+                continue;
+            };
+            for &j in &self.members[self.starts[cell]..self.starts[cell + 1]] {
+                let q = &positions[j as usize];
+                let dist2: f32 = p.iter().zip(q).map(|(a, b)| (a - b) * (a - b)).sum();
+                if j as usize != i && dist2 <= self.cutoff2 {
+                    found.push(j);
+                }
+            }
+        }
+        found.sort_unstable();
+    }
+    // Cell shifted by base-3 digits of `offset`, each in -1..=1
+    fn adjacent(&self, mut cell: usize, mut offset: usize) -> Option<usize> {
+        let mut shifted = 0;
+        let mut stride = 1;
+        for n in &self.dims {
+            let x = (cell % n + offset % 3).checked_sub(1)?;
+            if x >= *n {
+                return None;
+            }
+            shifted += x * stride;
+            stride *= n;
+            cell /= n;
+            offset /= 3;
+        }
+        Some(shifted)
+    }
+}
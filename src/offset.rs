@@ -0,0 +1,42 @@
+use std::fmt::Debug;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Integer type used to store segment offsets.
+///
+/// Implemented for `u16`, `u32`, `u64` and `usize`. A narrower
+/// offset type reduces the size of the extents at the cost of
+/// limiting the total storage length.
+pub trait Offset: private::Sealed + Copy + Ord + Debug {
+    /// Convert from `usize`.
+    ///
+    /// # Panics
+    /// If `value` does not fit in the offset type.
+    fn from_usize(value: usize) -> Self;
+    /// Convert to `usize`.
+    fn to_usize(self) -> usize;
+}
+
+macro_rules! impl_offset {
+    ($($t:ty),*) => {
+        $(
+            impl private::Sealed for $t {}
+            impl Offset for $t {
+                #[inline]
+                fn from_usize(value: usize) -> Self {
+                    Self::try_from(value).expect("offset overflow")
+                }
+                #[inline]
+                fn to_usize(self) -> usize {
+                    // Offsets never exceed the storage length,
+                    // which is itself a usize
+                    self as usize
+                }
+            }
+        )*
+    };
+}
+
+impl_offset!(u16, u32, u64, usize);
@@ -0,0 +1,169 @@
+use std::ops::{Index, Range};
+
+use crate::{offset::Offset, varslicedvec::VarSlicedVec};
+
+/// A read-only variable length segmented vector with bit-packed extents.
+///
+/// Each extent is stored using only as many bits as the largest
+/// extent requires, so the metadata of a container whose storage
+/// holds a million elements uses 20 bits per segment rather than 64.
+/// Looking up a segment costs a few extra shifts and masks.
+/// ```
+/// use sliced::*;
+/// let vv = varslicedvec![[1, 2], [3], [4, 5, 6]];
+/// let pv = vv.into_packed();
+/// assert_eq!(pv.extent_bits(), 3);
+/// assert_eq!(pv[2], [4, 5, 6]);
+/// let vv: VarSlicedVec<i32> = pv.unpack();
+/// assert_eq!(vv.lengths(), vec![2, 1, 3]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PackedVarSlicedVec<T>
+where
+    T: Copy + Clone,
+{
+    storage: Vec<T>,
+    extents: BitPacked,
+}
+
+impl<T> PackedVarSlicedVec<T>
+where
+    T: Copy + Clone,
+{
+    /// Convert back into a `VarSlicedVec`.
+    ///
+    /// The storage is moved without copying.
+    pub fn unpack<O: Offset>(self) -> VarSlicedVec<T, O> {
+        let extents = (0..self.extents.len)
+            .map(|i| O::from_usize(self.extents.get(i)))
+            .collect();
+        VarSlicedVec {
+            storage: self.storage,
+            extents,
+        }
+    }
+    /// Returns the number of segments.
+    pub fn len(&self) -> usize {
+        self.extents.len - 1
+    }
+    /// Test if there are no segments.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the number of bits used for each extent.
+    pub fn extent_bits(&self) -> u32 {
+        self.extents.width
+    }
+    /// Returns the length of the underlying storage.
+    pub fn storage_len(&self) -> usize {
+        self.storage.len()
+    }
+    /// Get a reference to a segment.
+    ///
+    /// Returns `None` if `index` is out of range.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let pv = varslicedvec![[1, 2], [3]].into_packed();
+    /// assert_eq!(pv.get(1), Some([3].as_slice()));
+    /// assert_eq!(pv.get(2), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&[T]> {
+        if index < self.len() {
+            Some(&self.storage[self.storage_range(index)])
+        } else {
+            None
+        }
+    }
+    /// Get the segment length at `index`.
+    ///
+    /// Returns zero if `index` is out of range.
+    pub fn segment_len(&self, index: usize) -> usize {
+        if index < self.len() {
+            self.storage_range(index).len()
+        } else {
+            0
+        }
+    }
+    /// Return iterator over slices.
+    pub fn iter(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.len()).map(|index| &self.storage[self.storage_range(index)])
+    }
+    fn storage_range(&self, index: usize) -> Range<usize> {
+        self.extents.get(index)..self.extents.get(index + 1)
+    }
+}
+
+impl<T, O> VarSlicedVec<T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    /// Convert into a read-only container with bit-packed extents.
+    ///
+    /// The storage is moved without copying.
+    pub fn into_packed(self) -> PackedVarSlicedVec<T> {
+        let extents = BitPacked::new(self.extents.iter().map(|extent| extent.to_usize()));
+        PackedVarSlicedVec {
+            storage: self.storage,
+            extents,
+        }
+    }
+}
+
+impl<T> Index<usize> for PackedVarSlicedVec<T>
+where
+    T: Copy + Clone,
+{
+    type Output = [T];
+    fn index(&self, index: usize) -> &Self::Output {
+        assert!(index < self.len());
+        &self.storage[self.storage_range(index)]
+    }
+}
+
+// Fixed-width unsigned integers packed into words
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct BitPacked {
+    words: Vec<u64>,
+    width: u32,
+    len: usize,
+}
+
+impl BitPacked {
+    // Values must be non-decreasing so the last is the largest
+    fn new<I>(values: I) -> Self
+    where
+        I: ExactSizeIterator<Item = usize> + Clone,
+    {
+        let len = values.len();
+        let max = values.clone().last().unwrap_or(0) as u64;
+        let width = u64::BITS - max.leading_zeros();
+        let mut words = vec![0u64; (len * width as usize).div_ceil(64)];
+        if width > 0 {
+            for (i, value) in values.enumerate() {
+                let bit = i * width as usize;
+                let (word, shift) = (bit / 64, bit % 64);
+                words[word] |= (value as u64) << shift;
+                if shift + width as usize > 64 {
+                    words[word + 1] |= (value as u64) >> (64 - shift);
+                }
+            }
+        }
+        Self { words, width, len }
+    }
+    fn get(&self, index: usize) -> usize {
+        debug_assert!(index < self.len);
+        if self.width == 0 {
+            return 0;
+        }
+        let bit = index * self.width as usize;
+        let (word, shift) = (bit / 64, bit % 64);
+        let mut value = self.words[word] >> shift;
+        if shift + self.width as usize > 64 {
+            value |= self.words[word + 1] << (64 - shift);
+        }
+        (value & (u64::MAX >> (64 - self.width))) as usize
+    }
+}
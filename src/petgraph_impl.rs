@@ -0,0 +1,222 @@
+use std::{iter::Copied, ops::Range, slice::Iter};
+
+use fixedbitset::FixedBitSet;
+use petgraph::{
+    csr::Csr,
+    visit::{
+        Data, GraphBase, GraphProp, IntoEdgeReferences, IntoEdges, IntoNeighbors,
+        IntoNodeIdentifiers, NodeCompactIndexable, NodeCount, NodeIndexable, Visitable,
+    },
+    Directed, Graph,
+};
+
+use crate::{adjacency::AdjacencyList, varslicedvec::VarSlicedVec};
+
+impl AdjacencyList {
+    /// Copy the finalized edges into a petgraph `Csr`.
+    ///
+    /// `Csr` rows are sorted and do not allow parallel edges,
+    /// so each row is sorted and duplicates are dropped.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut graph = AdjacencyList::with_nodes(4);
+    /// graph.add_edge(0, 2);
+    /// graph.add_edge(0, 1);
+    /// graph.add_edge(0, 2);
+    /// graph.finalize();
+    /// let csr = graph.to_csr();
+    /// assert_eq!(csr.node_count(), 4);
+    /// assert_eq!(csr.neighbors_slice(0), [1, 2]);
+    /// assert_eq!(AdjacencyList::from(&csr).neighbors(0), [1, 2]);
+    /// ```
+    pub fn to_csr(&self) -> Csr<(), (), Directed, u32> {
+        let mut edges = Vec::with_capacity(self.as_var_sliced_vec().storage_len());
+        let mut row = Vec::new();
+        for (u, neighbors) in self.as_var_sliced_vec().iter().enumerate() {
+            row.clear();
+            row.extend_from_slice(neighbors);
+            row.sort_unstable();
+            row.dedup();
+            edges.extend(row.iter().map(|v| (u as u32, *v)));
+        }
+        let mut csr = Csr::from_sorted_edges(&edges).unwrap();
+        while csr.node_count() < self.node_count() {
+            csr.add_node(());
+        }
+        csr
+    }
+    /// Copy the finalized edges into a petgraph `Graph`.
+    ///
+    /// Node indices are preserved and edges keep their order.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut graph = AdjacencyList::new();
+    /// graph.add_edge(0, 1);
+    /// graph.add_edge(1, 2);
+    /// graph.finalize();
+    /// let pg = graph.to_graph();
+    /// assert_eq!(pg.node_count(), 3);
+    /// assert_eq!(pg.edge_count(), 2);
+    /// assert_eq!(AdjacencyList::from(&pg).neighbors(1), [2]);
+    /// ```
+    pub fn to_graph(&self) -> Graph<(), (), Directed, u32> {
+        let mut graph = Graph::with_capacity(self.node_count(), self.edge_count());
+        (0..self.node_count()).for_each(|_| {
+            graph.add_node(());
+        });
+        graph.extend_with_edges(self.edges());
+        graph
+    }
+}
+
+/// Copy the rows of a petgraph `Csr`, dropping weights.
+impl<N, E> From<&Csr<N, E, Directed, u32>> for AdjacencyList {
+    fn from(csr: &Csr<N, E, Directed, u32>) -> Self {
+        let mut rows = VarSlicedVec::with_capacity(csr.edge_count());
+        (0..csr.node_count() as u32).for_each(|u| rows.push(csr.neighbors_slice(u)));
+        Self::from(rows)
+    }
+}
+
+/// Copy the edges of a petgraph `Graph`, dropping weights.
+impl<N, E> From<&Graph<N, E, Directed, u32>> for AdjacencyList {
+    fn from(graph: &Graph<N, E, Directed, u32>) -> Self {
+        let mut list = Self::with_nodes(graph.node_count());
+        graph.raw_edges().iter().for_each(|edge| {
+            list.add_edge(edge.source().index() as u32, edge.target().index() as u32)
+        });
+        list.finalize();
+        list
+    }
+}
+
+/// Nodes are `u32` indices and edges are `(source, target)` pairs.
+///
+/// With the visit traits, petgraph algorithms run directly
+/// over the finalized rows. Staged edges are not visited.
+/// # Example
+/// ```
+/// use petgraph::{algo::dijkstra, visit::Bfs};
+/// use sliced::*;
+/// let mut graph = AdjacencyList::new();
+/// graph.add_edge(0, 1);
+/// graph.add_edge(1, 2);
+/// graph.add_edge(0, 3);
+/// graph.finalize();
+/// let mut bfs = Bfs::new(&graph, 0);
+/// let mut order = Vec::new();
+/// while let Some(u) = bfs.next(&graph) {
+///     order.push(u);
+/// }
+/// assert_eq!(order, [0, 1, 3, 2]);
+/// let hops = dijkstra(&graph, 0, None, |_| 1);
+/// assert_eq!(hops[&2], 2);
+/// ```
+impl GraphBase for AdjacencyList {
+    type NodeId = u32;
+    type EdgeId = (u32, u32);
+}
+
+impl GraphProp for AdjacencyList {
+    type EdgeType = Directed;
+}
+
+impl Data for AdjacencyList {
+    type NodeWeight = ();
+    type EdgeWeight = ();
+}
+
+impl NodeCount for AdjacencyList {
+    fn node_count(&self) -> usize {
+        AdjacencyList::node_count(self)
+    }
+}
+
+impl NodeIndexable for AdjacencyList {
+    fn node_bound(&self) -> usize {
+        AdjacencyList::node_count(self)
+    }
+    fn to_index(&self, a: u32) -> usize {
+        a as usize
+    }
+    fn from_index(&self, i: usize) -> u32 {
+        i as u32
+    }
+}
+
+impl NodeCompactIndexable for AdjacencyList {}
+
+impl Visitable for AdjacencyList {
+    type Map = FixedBitSet;
+    fn visit_map(&self) -> FixedBitSet {
+        FixedBitSet::with_capacity(AdjacencyList::node_count(self))
+    }
+    fn reset_map(&self, map: &mut FixedBitSet) {
+        map.clear();
+        map.grow(AdjacencyList::node_count(self));
+    }
+}
+
+impl<'a> IntoNeighbors for &'a AdjacencyList {
+    type Neighbors = Copied<Iter<'a, u32>>;
+    fn neighbors(self, a: u32) -> Self::Neighbors {
+        AdjacencyList::neighbors(self, a).iter().copied()
+    }
+}
+
+impl IntoNodeIdentifiers for &AdjacencyList {
+    type NodeIdentifiers = Range<u32>;
+    fn node_identifiers(self) -> Range<u32> {
+        0..AdjacencyList::node_count(self) as u32
+    }
+}
+
+impl<'a> IntoEdgeReferences for &'a AdjacencyList {
+    type EdgeRef = (u32, u32, &'a ());
+    type EdgeReferences = AdjacencyEdges<'a>;
+    fn edge_references(self) -> AdjacencyEdges<'a> {
+        AdjacencyEdges {
+            graph: self,
+            nodes: 0..AdjacencyList::node_count(self) as u32,
+            row: [].iter(),
+            source: 0,
+        }
+    }
+}
+
+impl<'a> IntoEdges for &'a AdjacencyList {
+    type Edges = AdjacencyEdges<'a>;
+    fn edges(self, a: u32) -> AdjacencyEdges<'a> {
+        AdjacencyEdges {
+            graph: self,
+            nodes: 0..0,
+            row: AdjacencyList::neighbors(self, a).iter(),
+            source: a,
+        }
+    }
+}
+
+/// Iterator over edge references of an `AdjacencyList`
+#[derive(Debug, Clone)]
+pub struct AdjacencyEdges<'a> {
+    graph: &'a AdjacencyList,
+    // Nodes whose rows are not yet visited
+    nodes: Range<u32>,
+    row: Iter<'a, u32>,
+    source: u32,
+}
+
+impl<'a> Iterator for AdjacencyEdges<'a> {
+    type Item = (u32, u32, &'a ());
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(v) = self.row.next() {
+                return Some((self.source, *v, &()));
+            }
+            self.source = self.nodes.next()?;
+            self.row = AdjacencyList::neighbors(self.graph, self.source).iter();
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{offset::Offset, varslicedvec::VarSlicedVec};
+
+// Target number of segments per sampled block
+const SEGMENTS_PER_BLOCK: usize = 8;
+
+/// Sampled index mapping flat positions to segments.
+///
+/// Every `2^shift` storage positions the index records the
+/// segment containing that position. A lookup reads one sample
+/// pair and then binary searches the extents between them, so
+/// the cost is logarithmic in the number of segments within one
+/// sample block. Blocks are sized to hold about eight segments
+/// of mean length, but skewed lengths can pack many short
+/// segments into one block, up to all of them. The index
+/// borrows its `VarSlicedVec`, which therefore cannot change
+/// while the index is alive.
+#[derive(Debug)]
+pub struct PositionIndex<'a, T, O = usize>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    data: &'a VarSlicedVec<T, O>,
+    shift: u32,
+    samples: Vec<usize>,
+}
+
+impl<'a, T, O> PositionIndex<'a, T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    pub(crate) fn new(data: &'a VarSlicedVec<T, O>) -> Self {
+        let storage_len = data.storage.len();
+        let mean_len = storage_len / data.len().max(1);
+        let shift = (mean_len * SEGMENTS_PER_BLOCK)
+            .max(1)
+            .next_power_of_two()
+            .trailing_zeros();
+        let mut samples = Vec::with_capacity((storage_len >> shift) + 1);
+        let mut segment = 0;
+        let mut position = 0;
+        while position < storage_len {
+            while data.extents[segment + 1].to_usize() <= position {
+                segment += 1;
+            }
+            samples.push(segment);
+            position += 1 << shift;
+        }
+        Self {
+            data,
+            shift,
+            samples,
+        }
+    }
+    /// Find the segment containing a flat storage position.
+    ///
+    /// Returns `None` if `position` is not less than the
+    /// storage length. Empty segments never contain a position.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1, 2], [], [3, 4, 5], [6]];
+    /// let index = vv.build_position_index();
+    /// assert_eq!(index.segment_containing(0), Some(0));
+    /// assert_eq!(index.segment_containing(2), Some(2));
+    /// assert_eq!(index.segment_containing(5), Some(3));
+    /// assert_eq!(index.segment_containing(6), None);
+    /// ```
+    pub fn segment_containing(&self, position: usize) -> Option<usize> {
+        if position >= self.data.storage.len() {
+            return None;
+        }
+        let block = position >> self.shift;
+        let lo = self.samples[block];
+        let hi = match self.samples.get(block + 1) {
+            Some(&segment) => segment,
+            None => self.data.len() - 1,
+        };
+        Some(
+            lo + self.data.extents[lo + 1..=hi + 1]
+                .partition_point(|extent| extent.to_usize() <= position),
+        )
+    }
+    /// Find the segment and offset of a flat storage position.
+    ///
+    /// Returns `None` if `position` is out of range.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1, 2], [], [3, 4, 5], [6]];
+    /// let index = vv.build_position_index();
+    /// assert_eq!(index.locate(3), Some((2, 1)));
+    /// ```
+    pub fn locate(&self, position: usize) -> Option<(usize, usize)> {
+        let segment = self.segment_containing(position)?;
+        Some((segment, position - self.data.extents[segment].to_usize()))
+    }
+}
@@ -0,0 +1,421 @@
+use rayon::iter::{
+    plumbing::{
+        bridge, bridge_unindexed, Consumer, Folder, Producer, ProducerCallback,
+        UnindexedConsumer, UnindexedProducer,
+    },
+    IndexedParallelIterator, IntoParallelIterator, ParallelIterator,
+};
+
+use crate::{
+    neighbors::{CellBins, NeighborListBuilder},
+    offset::Offset,
+    slicedvec::SlicedVec,
+    slicedslab::{OccupiedKeys, SlicedSlab},
+    varslicedslice::{VarSlicedSlice, VarSlicedSliceIter},
+    varslicedvec::VarSlicedVec,
+};
+
+/// Parallel iterator over the segments of a `VarSlicedVec`.
+///
+/// Work is split at segment boundaries by halving the extents.
+#[derive(Debug)]
+pub struct VarSlicedParIter<'a, T, O = usize>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    slice: VarSlicedSlice<'a, T, O>,
+}
+
+impl<'a, T, O> ParallelIterator for VarSlicedParIter<'a, T, O>
+where
+    T: Copy + Clone + Sync,
+    O: Offset + Sync,
+{
+    type Item = &'a [T];
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.slice.len())
+    }
+}
+
+impl<'a, T, O> IndexedParallelIterator for VarSlicedParIter<'a, T, O>
+where
+    T: Copy + Clone + Sync,
+    O: Offset + Sync,
+{
+    fn len(&self) -> usize {
+        self.slice.len()
+    }
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> CB::Output {
+        callback.callback(VarSlicedProducer { slice: self.slice })
+    }
+}
+
+struct VarSlicedProducer<'a, T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    slice: VarSlicedSlice<'a, T, O>,
+}
+
+impl<'a, T, O> Producer for VarSlicedProducer<'a, T, O>
+where
+    T: Copy + Clone + Sync,
+    O: Offset + Sync,
+{
+    type Item = &'a [T];
+    type IntoIter = VarSlicedSliceIter<'a, T, O>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.slice.iter()
+    }
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let (left, right) = self.slice.split_at(index);
+        (Self { slice: left }, Self { slice: right })
+    }
+}
+
+impl<'a, T, O> VarSlicedSlice<'a, T, O>
+where
+    T: Copy + Clone + Sync,
+    O: Offset + Sync,
+{
+    /// Return a parallel iterator over slices.
+    pub fn par_iter(&self) -> VarSlicedParIter<'a, T, O> {
+        VarSlicedParIter { slice: *self }
+    }
+}
+
+impl<T, O> VarSlicedVec<T, O>
+where
+    T: Copy + Clone + Sync,
+    O: Offset + Sync,
+{
+    /// Return a parallel iterator over slices.
+    ///
+    /// # Example
+    /// ```
+    /// use rayon::prelude::*;
+    /// use sliced::*;
+    /// let vv: VarSlicedVec<u64> = (0..1000).map(|n| 0..n % 10).collect();
+    /// let total: u64 = vv.par_iter().map(|slice| slice.iter().sum::<u64>()).sum();
+    /// assert_eq!(total, 12000);
+    /// let lens = vv.par_iter().map(|slice| slice.len()).collect::<Vec<_>>();
+    /// assert_eq!(lens, vv.lengths());
+    /// ```
+    pub fn par_iter(&self) -> VarSlicedParIter<'_, T, O> {
+        self.as_slice().par_iter()
+    }
+}
+
+/// Parallel iterator over the occupied slots of a `SlicedSlab`.
+///
+/// Work is split by halving the occupancy bitmap, so each
+/// part covers an equal run of slots.
+#[derive(Debug)]
+pub struct SlicedSlabParIter<'a, T>
+where
+    T: Copy + Clone,
+{
+    producer: SlabProducer<'a, T>,
+}
+
+impl<'a, T> ParallelIterator for SlicedSlabParIter<'a, T>
+where
+    T: Copy + Clone + Sync,
+{
+    type Item = (usize, &'a [T]);
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge_unindexed(self.producer, consumer)
+    }
+}
+
+#[derive(Debug)]
+struct SlabProducer<'a, T> {
+    storage: &'a [T],
+    words: &'a [u64],
+    segment_len: usize,
+    // Key of the first slot
+    base: usize,
+}
+
+impl<'a, T> UnindexedProducer for SlabProducer<'a, T>
+where
+    T: Copy + Clone + Sync,
+{
+    type Item = (usize, &'a [T]);
+    fn split(self) -> (Self, Option<Self>) {
+        if self.words.len() < 2 {
+            return (self, None);
+        }
+        let mid = self.words.len() / 2;
+        let at = (mid * 64 * self.segment_len).min(self.storage.len());
+        let (left_storage, right_storage) = self.storage.split_at(at);
+        let (left_words, right_words) = self.words.split_at(mid);
+        let right = Self {
+            storage: right_storage,
+            words: right_words,
+            segment_len: self.segment_len,
+            base: self.base + mid * 64,
+        };
+        let left = Self {
+            storage: left_storage,
+            words: left_words,
+            ..self
+        };
+        (left, Some(right))
+    }
+    fn fold_with<F>(self, mut folder: F) -> F
+    where
+        F: Folder<Self::Item>,
+    {
+        let mut slots = self.storage.chunks_exact(self.segment_len);
+        let mut next = 0;
+        for key in OccupiedKeys::new(self.words) {
+            let segment = slots.nth(key - next).unwrap();
+            next = key + 1;
+            folder = folder.consume((self.base + key, segment));
+            if folder.full() {
+                break;
+            }
+        }
+        folder
+    }
+}
+
+/// Mutable parallel iterator over the occupied slots of a `SlicedSlab`.
+#[derive(Debug)]
+pub struct SlicedSlabParIterMut<'a, T>
+where
+    T: Copy + Clone,
+{
+    producer: SlabProducerMut<'a, T>,
+}
+
+impl<'a, T> ParallelIterator for SlicedSlabParIterMut<'a, T>
+where
+    T: Copy + Clone + Send,
+{
+    type Item = (usize, &'a mut [T]);
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge_unindexed(self.producer, consumer)
+    }
+}
+
+#[derive(Debug)]
+struct SlabProducerMut<'a, T> {
+    storage: &'a mut [T],
+    words: &'a [u64],
+    segment_len: usize,
+    // Key of the first slot
+    base: usize,
+}
+
+impl<'a, T> UnindexedProducer for SlabProducerMut<'a, T>
+where
+    T: Copy + Clone + Send,
+{
+    type Item = (usize, &'a mut [T]);
+    fn split(self) -> (Self, Option<Self>) {
+        if self.words.len() < 2 {
+            return (self, None);
+        }
+        let mid = self.words.len() / 2;
+        let at = (mid * 64 * self.segment_len).min(self.storage.len());
+        let (left_storage, right_storage) = self.storage.split_at_mut(at);
+        let (left_words, right_words) = self.words.split_at(mid);
+        let right = Self {
+            storage: right_storage,
+            words: right_words,
+            segment_len: self.segment_len,
+            base: self.base + mid * 64,
+        };
+        let left = Self {
+            storage: left_storage,
+            words: left_words,
+            ..self
+        };
+        (left, Some(right))
+    }
+    fn fold_with<F>(self, mut folder: F) -> F
+    where
+        F: Folder<Self::Item>,
+    {
+        let mut slots = self.storage.chunks_exact_mut(self.segment_len);
+        let mut next = 0;
+        for key in OccupiedKeys::new(self.words) {
+            let segment = slots.nth(key - next).unwrap();
+            next = key + 1;
+            folder = folder.consume((self.base + key, segment));
+            if folder.full() {
+                break;
+            }
+        }
+        folder
+    }
+}
+
+/// Parallel draining iterator over the occupied slots of a `SlicedSlab`.
+///
+/// The slab is cleared once the iterator is dropped,
+/// whether or not it was driven.
+#[derive(Debug)]
+pub struct SlicedSlabParDrain<'a, T>
+where
+    T: Copy + Clone,
+{
+    slab: &'a mut SlicedSlab<T>,
+}
+
+impl<'a, T> ParallelIterator for SlicedSlabParDrain<'a, T>
+where
+    T: Copy + Clone + Send + Sync,
+{
+    type Item = (usize, Vec<T>);
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.slab
+            .par_iter()
+            .map(|(key, segment)| (key, segment.to_vec()))
+            .drive_unindexed(consumer)
+    }
+}
+
+impl<'a, T> Drop for SlicedSlabParDrain<'a, T>
+where
+    T: Copy + Clone,
+{
+    fn drop(&mut self) {
+        self.slab.clear();
+    }
+}
+
+impl<T> SlicedSlab<T>
+where
+    T: Copy + Clone + Sync,
+{
+    /// Return a parallel iterator over key, slice pairs of occupied slots.
+    ///
+    /// # Example
+    /// ```
+    /// use rayon::prelude::*;
+    /// use sliced::*;
+    /// let mut ss = SlicedSlab::from_vec(2, (0..1000).collect());
+    /// (0..500).step_by(2).for_each(|key| ss.release(key));
+    /// let total: usize = ss.par_iter().map(|(key, _)| key).sum();
+    /// assert_eq!(total, ss.iter_keys().sum());
+    /// ```
+    pub fn par_iter(&self) -> SlicedSlabParIter<'_, T> {
+        SlicedSlabParIter {
+            producer: SlabProducer {
+                storage: &self.slots.storage,
+                words: &self.occupied.words,
+                segment_len: self.segment_len(),
+                base: 0,
+            },
+        }
+    }
+}
+
+impl<T> SlicedSlab<T>
+where
+    T: Copy + Clone + Send,
+{
+    /// Return a mutable parallel iterator over key, slice pairs of occupied slots.
+    ///
+    /// # Example
+    /// ```
+    /// use rayon::prelude::*;
+    /// use sliced::*;
+    /// let mut ss = SlicedSlab::from_vec(2, vec![0; 1000]);
+    /// ss.release(7);
+    /// ss.par_iter_mut().for_each(|(key, slice)| slice.fill(key));
+    /// assert_eq!(ss[499], [499, 499]);
+    /// assert_eq!(ss[7], [0, 0]);
+    /// ```
+    pub fn par_iter_mut(&mut self) -> SlicedSlabParIterMut<'_, T> {
+        self.touch_occupied();
+        let segment_len = self.segment_len();
+        SlicedSlabParIterMut {
+            producer: SlabProducerMut {
+                storage: &mut self.slots.storage,
+                words: &self.occupied.words,
+                segment_len,
+                base: 0,
+            },
+        }
+    }
+}
+
+impl<T> SlicedSlab<T>
+where
+    T: Copy + Clone + Send + Sync,
+{
+    /// Remove all slots, yielding key, vector pairs of occupied slots in parallel.
+    ///
+    /// The slab is empty once the iterator is dropped.
+    /// The storage capacity is kept.
+    /// # Example
+    /// ```
+    /// use rayon::prelude::*;
+    /// use sliced::*;
+    /// let mut ss = SlicedSlab::from_vec(2, (0..1000).collect());
+    /// ss.release(3);
+    /// let mut drained: Vec<_> = ss.par_drain().collect();
+    /// drained.sort_unstable();
+    /// assert_eq!(drained.len(), 499);
+    /// assert_eq!(drained[3], (4, vec![8, 9]));
+    /// assert!(ss.is_empty());
+    /// assert!(ss.capacity() >= 500);
+    /// ```
+    pub fn par_drain(&mut self) -> SlicedSlabParDrain<'_, T> {
+        SlicedSlabParDrain { slab: self }
+    }
+}
+
+impl NeighborListBuilder {
+    /// Build the neighbor lists in parallel.
+    ///
+    /// Particles are processed in blocks and the per-block
+    /// lists are appended in order, so the result matches `build`.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let positions = SlicedVec::from_vec(2, (0..2000).map(|n| (n % 97) as f32).collect());
+    /// let builder = NeighborListBuilder::new(2.0);
+    /// assert_eq!(builder.par_build(&positions), builder.build(&positions));
+    /// ```
+    pub fn par_build(&self, positions: &SlicedVec<f32>) -> VarSlicedVec<u32> {
+        const BLOCK: usize = 1024;
+        let bins = CellBins::new(positions, self.cutoff());
+        let blocks: Vec<_> = (0..positions.len().div_ceil(BLOCK))
+            .into_par_iter()
+            .map(|block| {
+                let end = (block * BLOCK + BLOCK).min(positions.len());
+                bins.build_range(positions, block * BLOCK..end)
+            })
+            .collect();
+        let mut lists = VarSlicedVec::new();
+        for block in &blocks {
+            lists.extend_from_other(block);
+        }
+        lists
+    }
+}
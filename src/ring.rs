@@ -0,0 +1,128 @@
+use crate::slicedvec::SlicedVec;
+
+/// A fixed-capacity circular buffer of segments.
+///
+/// Holds the last `capacity` segments pushed. Once full, each
+/// push overwrites the oldest segment in place, so there is no
+/// allocation at steady state. Segments are addressed by age,
+/// with `0` being the most recent.
+/// ```
+/// use sliced::*;
+/// let mut ring = SlicedRing::new(2, 3);
+/// ring.push_overwrite(&[1, 1]);
+/// ring.push_overwrite(&[2, 2]);
+/// ring.push_overwrite(&[3, 3]);
+/// ring.push_overwrite(&[4, 4]);
+/// assert_eq!(ring.len(), 3);
+/// assert_eq!(ring.latest(), Some([4, 4].as_slice()));
+/// assert_eq!(ring.oldest(), Some([2, 2].as_slice()));
+/// let frames: Vec<_> = ring.iter_newest_first().collect();
+/// assert_eq!(frames, [[4, 4], [3, 3], [2, 2]]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SlicedRing<T>
+where
+    T: Copy + Clone,
+{
+    data: SlicedVec<T>,
+    capacity: usize,
+    // Slot of the next push once full
+    head: usize,
+}
+
+impl<T> SlicedRing<T>
+where
+    T: Copy + Clone,
+{
+    /// Construct an empty ring holding up to `capacity` segments.
+    ///
+    /// Storage for all segments is reserved up front.
+    /// # Panics
+    /// If `segment_len` or `capacity` is zero.
+    pub fn new(segment_len: usize, capacity: usize) -> Self {
+        assert_ne!(capacity, 0);
+        Self {
+            data: SlicedVec::with_capacity(segment_len, capacity),
+            capacity,
+            head: 0,
+        }
+    }
+    /// Returns the number of segments.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+    /// Test if there are no segments.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+    /// Test if the next push will overwrite a segment.
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity
+    }
+    /// Returns the maximum number of segments.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.data.segment_len()
+    }
+    /// Append a segment, overwriting the oldest if full.
+    ///
+    /// Returns `true` if a segment was overwritten.
+    /// # Panics
+    /// If the length of the slice does
+    /// not match the segments size of the ring.
+    pub fn push_overwrite(&mut self, segment: &[T]) -> bool {
+        if self.is_full() {
+            self.data[self.head].copy_from_slice(segment);
+            self.head = (self.head + 1) % self.capacity;
+            true
+        } else {
+            self.data.push(segment);
+            false
+        }
+    }
+    /// Get the most recent segment.
+    pub fn latest(&self) -> Option<&[T]> {
+        self.get(0)
+    }
+    /// Get the least recent segment.
+    pub fn oldest(&self) -> Option<&[T]> {
+        self.get(self.len().checked_sub(1)?)
+    }
+    /// Get a segment by age, where `0` is the most recent.
+    ///
+    /// Returns `None` if `age` is not less than the length.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut ring = SlicedRing::new(1, 2);
+    /// (0..5).for_each(|i| { ring.push_overwrite(&[i]); });
+    /// assert_eq!(ring.get(1), Some([3].as_slice()));
+    /// assert_eq!(ring.get(2), None);
+    /// ```
+    pub fn get(&self, age: usize) -> Option<&[T]> {
+        if age >= self.len() {
+            return None;
+        }
+        self.data
+            .get((self.head + self.len() - 1 - age) % self.len())
+    }
+    /// Iterate over segments from the most to the least recent.
+    pub fn iter_newest_first(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.len()).map(|age| self.get(age).unwrap())
+    }
+    /// Iterate over segments from the least to the most recent.
+    pub fn iter(&self) -> impl Iterator<Item = &[T]> {
+        let (newer, older) = self.data.storage.split_at(self.head * self.segment_len());
+        older
+            .chunks_exact(self.segment_len())
+            .chain(newer.chunks_exact(self.segment_len()))
+    }
+    /// Remove all segments, keeping the storage.
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.head = 0;
+    }
+}
@@ -0,0 +1,137 @@
+use std::{collections::BTreeMap, fmt, marker::PhantomData};
+
+use serde::{
+    de::{Error, SeqAccess, Visitor},
+    ser::SerializeSeq,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{offset::Offset, slicedslab::SlicedSlab, varslicedvec::VarSlicedVec};
+
+/// Human-readable formats receive a sequence of sequences.
+/// Binary formats receive a `(storage, offsets)` pair.
+impl<T, O> Serialize for VarSlicedVec<T, O>
+where
+    T: Copy + Clone + Serialize,
+    O: Offset + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let mut seq = serializer.serialize_seq(Some(self.len()))?;
+            for segment in self.iter() {
+                seq.serialize_element(segment)?;
+            }
+            seq.end()
+        } else {
+            (self.storage.as_slice(), self.extents.as_slice()).serialize(serializer)
+        }
+    }
+}
+
+struct NestedVisitor<T, O> {
+    marker: PhantomData<(T, O)>,
+}
+
+impl<'de, T, O> Visitor<'de> for NestedVisitor<T, O>
+where
+    T: Copy + Clone + Deserialize<'de>,
+    O: Offset,
+{
+    type Value = VarSlicedVec<T, O>;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of sequences")
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut vv = VarSlicedVec::default();
+        while let Some(segment) = seq.next_element::<Vec<T>>()? {
+            vv.push_vec(segment);
+        }
+        Ok(vv)
+    }
+}
+
+/// Offsets read from binary formats are validated.
+impl<'de, T, O> Deserialize<'de> for VarSlicedVec<T, O>
+where
+    T: Copy + Clone + Deserialize<'de>,
+    O: Offset + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_seq(NestedVisitor {
+                marker: PhantomData,
+            })
+        } else {
+            let (storage, extents) = <(Vec<T>, Vec<O>)>::deserialize(deserializer)?;
+            let vv = VarSlicedVec { storage, extents };
+            if vv.check_invariants() {
+                Ok(vv)
+            } else {
+                Err(D::Error::custom("invalid segment offsets"))
+            }
+        }
+    }
+}
+
+struct SlabEntries<'a, T>(&'a SlicedSlab<T>)
+where
+    T: Copy + Clone;
+
+impl<'a, T> Serialize for SlabEntries<'a, T>
+where
+    T: Copy + Clone + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter())
+    }
+}
+
+/// Serialized as `(segment_len, entries)` where the entries
+/// map the key of each occupied slot to its segment.
+impl<T> Serialize for SlicedSlab<T>
+where
+    T: Copy + Clone + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.segment_len(), SlabEntries(self)).serialize(serializer)
+    }
+}
+
+/// Keys are preserved and the slots between them are
+/// marked open. Open slots after the last key are dropped.
+/// The slab holds a slot for every key up to the largest,
+/// so memory and time grow with the largest key rather than
+/// the number of entries. A size that overflows or cannot be
+/// allocated is reported as an error instead of panicking.
+impl<'de, T> Deserialize<'de> for SlicedSlab<T>
+where
+    T: Copy + Clone + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (segment_len, entries) = <(usize, BTreeMap<usize, Vec<T>>)>::deserialize(deserializer)?;
+        if segment_len == 0 {
+            return Err(D::Error::custom("segment length is zero"));
+        }
+        if entries.values().any(|segment| segment.len() != segment_len) {
+            return Err(D::Error::custom("segment length mismatch"));
+        }
+        let (len, filler) = match (entries.last_key_value(), entries.first_key_value()) {
+            (Some((last, _)), Some((_, first))) => (last.checked_add(1), first.clone()),
+            _ => return Ok(SlicedSlab::new(segment_len)),
+        };
+        let too_large = || D::Error::custom("slab size overflow");
+        let len = len.ok_or_else(too_large)?;
+        let size = len.checked_mul(segment_len).ok_or_else(too_large)?;
+        // Open slots hold a copy of the first segment
+        let mut storage = Vec::new();
+        storage.try_reserve_exact(size).map_err(|_| too_large())?;
+        for key in 0..len {
+            storage.extend_from_slice(entries.get(&key).unwrap_or(&filler));
+        }
+        let mut slab = SlicedSlab::from_vec(segment_len, storage);
+        for key in (0..len).rev().filter(|key| !entries.contains_key(key)) {
+            slab.release(key);
+        }
+        Ok(slab)
+    }
+}
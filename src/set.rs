@@ -0,0 +1,230 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hash},
+};
+
+use crate::slicedvec::SlicedVec;
+
+// Marks an empty bucket
+const EMPTY: usize = usize::MAX;
+
+/// A hash set of fixed-length segments.
+///
+/// Unique segments are stored contiguously in a `SlicedVec` in
+/// insertion order, with their hashes in a side vector. Lookup
+/// uses an open addressing table of indices with linear probing,
+/// so inserting a segment never allocates per entry.
+/// ```
+/// use sliced::*;
+/// let mut set = SlicedSet::new(2);
+/// assert!(set.insert(&[1, 2]));
+/// assert!(set.insert(&[3, 4]));
+/// assert!(!set.insert(&[1, 2]));
+/// assert_eq!(set.len(), 2);
+/// assert!(set.contains(&[3, 4]));
+/// assert!(!set.contains(&[2, 1]));
+/// assert_eq!(set.iter().collect::<Vec<_>>(), [[1, 2], [3, 4]]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SlicedSet<T, S = RandomState>
+where
+    T: Copy + Clone,
+{
+    segments: SlicedVec<T>,
+    hashes: Vec<u64>,
+    // Buckets holding an index into `segments` or EMPTY
+    table: Vec<usize>,
+    hasher: S,
+}
+
+impl<T> SlicedSet<T, RandomState>
+where
+    T: Copy + Clone + Hash + Eq,
+{
+    /// Construct a new `SlicedSet`.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn new(segment_len: usize) -> Self {
+        Self::with_hasher(segment_len, RandomState::new())
+    }
+    /// Initialize a `SlicedSet` with room for `size` segments.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn with_capacity(segment_len: usize, size: usize) -> Self {
+        let mut set = Self::new(segment_len);
+        set.reserve(size);
+        set
+    }
+}
+
+impl<T, S> SlicedSet<T, S>
+where
+    T: Copy + Clone + Hash + Eq,
+    S: BuildHasher,
+{
+    /// Construct a new `SlicedSet` using `hasher` to hash segments.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn with_hasher(segment_len: usize, hasher: S) -> Self {
+        Self {
+            segments: SlicedVec::new(segment_len),
+            hashes: Vec::new(),
+            table: Vec::new(),
+            hasher,
+        }
+    }
+    /// Returns the number of unique segments.
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+    /// Test if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.segments.segment_len()
+    }
+    /// Reserve room for at least `additional` more segments.
+    pub fn reserve(&mut self, additional: usize) {
+        let needed = self.len() + additional;
+        if needed * 4 > self.table.len() * 3 {
+            let buckets = (needed * 4 / 3 + 1).next_power_of_two().max(8);
+            self.rehash(buckets);
+        }
+        self.segments.reserve(additional);
+        self.hashes.reserve(additional);
+    }
+    /// Insert a segment, returning `true` if it was not present.
+    ///
+    /// # Panics
+    /// If the length of the slice does
+    /// not match the segments size of the set.
+    pub fn insert(&mut self, segment: &[T]) -> bool {
+        self.insert_full(segment).1
+    }
+    /// Insert a segment and return its index and whether it was added.
+    ///
+    /// Indices count segments in insertion order.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut set = SlicedSet::new(1);
+    /// assert_eq!(set.insert_full(&['a']), (0, true));
+    /// assert_eq!(set.insert_full(&['b']), (1, true));
+    /// assert_eq!(set.insert_full(&['a']), (0, false));
+    /// assert_eq!(set.get_index(1), Some(['b'].as_slice()));
+    /// ```
+    /// # Panics
+    /// If the length of the slice does
+    /// not match the segments size of the set.
+    pub fn insert_full(&mut self, segment: &[T]) -> (usize, bool) {
+        assert_eq!(segment.len(), self.segment_len());
+        self.reserve(1);
+        let hash = self.hash(segment);
+        match self.find(hash, segment) {
+            Ok(index) => (index, false),
+            Err(bucket) => (self.push_at(bucket, hash, segment), true),
+        }
+    }
+    /// Test if the set contains `segment`.
+    pub fn contains(&self, segment: &[T]) -> bool {
+        self.get_index_of(segment).is_some()
+    }
+    /// Returns the insertion index of `segment` if present.
+    pub fn get_index_of(&self, segment: &[T]) -> Option<usize> {
+        if self.table.is_empty() || segment.len() != self.segment_len() {
+            return None;
+        }
+        self.find(self.hash(segment), segment).ok()
+    }
+    /// Get the segment at insertion index `index`.
+    pub fn get_index(&self, index: usize) -> Option<&[T]> {
+        self.segments.get(index)
+    }
+    /// Iterate over unique segments in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = &[T]> {
+        self.segments.iter()
+    }
+    /// Remove all segments, keeping the allocated storage.
+    pub fn clear(&mut self) {
+        self.segments.clear();
+        self.hashes.clear();
+        self.table.fill(EMPTY);
+    }
+    /// Get the unique segments as a `SlicedVec`.
+    pub fn as_sliced_vec(&self) -> &SlicedVec<T> {
+        &self.segments
+    }
+    /// Convert into the unique segments in insertion order.
+    pub fn into_sliced_vec(self) -> SlicedVec<T> {
+        self.segments
+    }
+    // Find the index of a segment, or the empty bucket where it belongs
+    pub(crate) fn find(&self, hash: u64, segment: &[T]) -> Result<usize, usize> {
+        let mask = self.table.len() - 1;
+        let mut bucket = hash as usize & mask;
+        loop {
+            let index = self.table[bucket];
+            if index == EMPTY {
+                return Err(bucket);
+            }
+            if self.hashes[index] == hash && self.segments[index] == *segment {
+                return Ok(index);
+            }
+            bucket = (bucket + 1) & mask;
+        }
+    }
+    // Hash a segment with the set's hasher
+    pub(crate) fn hash(&self, segment: &[T]) -> u64 {
+        self.hasher.hash_one(segment)
+    }
+    // Append a new segment into an empty bucket found by `find`
+    pub(crate) fn push_at(&mut self, bucket: usize, hash: u64, segment: &[T]) -> usize {
+        let index = self.len();
+        self.table[bucket] = index;
+        self.segments.push(segment);
+        self.hashes.push(hash);
+        index
+    }
+    // Rebuild the bucket table with `buckets` entries
+    fn rehash(&mut self, buckets: usize) {
+        let mask = buckets - 1;
+        self.table.clear();
+        self.table.resize(buckets, EMPTY);
+        for (index, hash) in self.hashes.iter().enumerate() {
+            let mut bucket = *hash as usize & mask;
+            while self.table[bucket] != EMPTY {
+                bucket = (bucket + 1) & mask;
+            }
+            self.table[bucket] = index;
+        }
+    }
+}
+
+impl<T, S> Extend<Vec<T>> for SlicedSet<T, S>
+where
+    T: Copy + Clone + Hash + Eq,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = Vec<T>>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|segment| {
+            self.insert(&segment);
+        })
+    }
+}
+
+impl<'a, T, S> Extend<&'a [T]> for SlicedSet<T, S>
+where
+    T: Copy + Clone + Hash + Eq,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = &'a [T]>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|segment| {
+            self.insert(segment);
+        })
+    }
+}
@@ -1,14 +1,60 @@
-use std::{collections::BTreeSet, ops::{IndexMut, Index}};
+use std::{
+    collections::{HashSet, TryReserveError},
+    error::Error,
+    fmt, mem,
+    ops::{Index, IndexMut, Range},
+    slice::{self, ChunksExact, ChunksExactMut},
+    sync::atomic::{AtomicU64, Ordering},
+};
 use crate::slicedvec::*;
 
+// Link value of an occupied slot
+const OCCUPIED: usize = usize::MAX;
+// Link value past either end of the free list
+const END: usize = usize::MAX - 1;
+
+// Free list neighbours of a slot, both OCCUPIED if occupied
+#[derive(Debug, Clone, Copy)]
+struct Link {
+    next: usize,
+    prev: usize,
+}
+
+const OCCUPIED_LINK: Link = Link {
+    next: OCCUPIED,
+    prev: OCCUPIED,
+};
+
+// Source of snapshot identities, unique across slabs
+static NEXT_SNAPSHOT: AtomicU64 = AtomicU64::new(0);
+
 /// A segmented slab with stable keys.
+///
+/// Open slots form a doubly linked free list threaded
+/// through a per-slot link vector, so inserting, releasing
+/// and claiming a particular open slot are constant time. A bitmap of occupied slots keeps
+/// key checks cache friendly and lets iteration skip
+/// runs of open slots 64 at a time.
 #[derive(Debug)]
 pub struct SlicedSlab<T>
 where
     T: Copy + Clone,
 {
-    slots: SlicedVec<T>,
-    open_slots: BTreeSet<usize>,
+    pub(crate) slots: SlicedVec<T>,
+    // Per slot: OCCUPIED_LINK or the neighbouring open slots
+    links: Vec<Link>,
+    pub(crate) occupied: Occupancy,
+    free_head: usize,
+    open_len: usize,
+    // No open slot has a lower key
+    low_water: usize,
+    // Prior state of slots changed since the latest snapshot
+    pub(crate) undo: Option<UndoLog<T>>,
+    auto_compact: Option<AutoCompact>,
+    counters: Counters,
+    strict: bool,
+    // Rows per key
+    arity: usize,
 }
 
 impl<T> SlicedSlab<T>
@@ -23,7 +69,16 @@ where
         assert_ne!(segment_len, 0);
         Self {
             slots: SlicedVec::new(segment_len),
-            open_slots: BTreeSet::new(),
+            links: Vec::new(),
+            occupied: Occupancy::default(),
+            free_head: END,
+            open_len: 0,
+            low_water: 0,
+            undo: None,
+            auto_compact: None,
+            counters: Counters::default(),
+            strict: false,
+            arity: 1,
         }
     }
     /// Initialize a `SlicedSlab` and set the capacity and segment size.
@@ -34,9 +89,44 @@ where
         assert_ne!(segment_len, 0);
         Self {
             slots: SlicedVec::with_capacity(segment_len, size),
-            open_slots: BTreeSet::new(),
+            links: Vec::with_capacity(size),
+            occupied: Occupancy::with_capacity(size),
+            free_head: END,
+            open_len: 0,
+            low_water: 0,
+            undo: None,
+            auto_compact: None,
+            counters: Counters::default(),
+            strict: false,
+            arity: 1,
         }
     }
+    /// Construct a `SlicedSlab` where each key owns `arity` rows.
+    ///
+    /// The rows of a key are stored consecutively, so every
+    /// segment has length `row_len * arity` and is accessed as
+    /// one flat slice. Use `rows` to visit them one at a time.
+    /// The arity is not serialized.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::with_arity(3, 2);
+    /// let key = ss.insert(&[1, 2, 3, 4, 5, 6]);
+    /// assert_eq!(ss.segment_len(), 6);
+    /// assert_eq!(ss.arity(), 2);
+    /// assert_eq!(ss.row_len(), 3);
+    /// assert_eq!(ss[key], [1, 2, 3, 4, 5, 6]);
+    /// let rows: Vec<_> = ss.rows(key).unwrap().collect();
+    /// assert_eq!(rows, [[1, 2, 3], [4, 5, 6]]);
+    /// ```
+    /// # Panics
+    /// If `row_len` or `arity` is zero.
+    pub fn with_arity(row_len: usize, arity: usize) -> Self {
+        assert_ne!(arity, 0);
+        let mut slab = Self::new(row_len * arity);
+        slab.arity = arity;
+        slab
+    }
     /// Initialize a `SlicedSlab` from a vector.
     /// 
     /// # Example
@@ -48,11 +138,71 @@ where
     /// If `segment_len` is zero.
     pub fn from_vec(segment_len: usize, data: Vec<T>) -> Self {
         assert_ne!(segment_len, 0);
+        let slots = SlicedVec::from_vec(segment_len, data);
         Self {
-            slots: SlicedVec::from_vec(segment_len, data),
-            open_slots: BTreeSet::new(),
+            links: vec![OCCUPIED_LINK; slots.len()],
+            occupied: Occupancy::full(slots.len()),
+            slots,
+            free_head: END,
+            open_len: 0,
+            low_water: 0,
+            undo: None,
+            auto_compact: None,
+            counters: Counters::default(),
+            strict: false,
+            arity: 1,
         }
     }
+    /// Returns the number of slots, occupied or open.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut ss = SlicedSlab::from_vec(2, (0..6).collect());
+    /// ss.release(1);
+    /// assert_eq!(ss.len(), 3);
+    /// assert_eq!(ss.occupied_len(), 2);
+    /// assert_eq!(ss.segment_len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+    /// Test if there are no slots.
+    ///
+    /// A slab whose slots are all open is not empty
+    /// until `compact` or `clear` is called.
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+    /// Returns the number of occupied slots.
+    pub fn occupied_len(&self) -> usize {
+        self.slots.len() - self.open_len
+    }
+    /// Get the capacity in number of slots.
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.slots.segment_len()
+    }
+    /// Returns the number of rows per key.
+    ///
+    /// This is one unless the slab was built with `with_arity`.
+    pub fn arity(&self) -> usize {
+        self.arity
+    }
+    /// Returns the length of each row.
+    pub fn row_len(&self) -> usize {
+        self.segment_len() / self.arity
+    }
+    /// Iterate over the rows of a segment.
+    ///
+    /// Returns `None` if `key` is out of range
+    /// or the slot is marked as unoccupied.
+    pub fn rows(&self, key: usize) -> Option<ChunksExact<'_, T>> {
+        Some(self.get(key)?.chunks_exact(self.row_len()))
+    }
     /// Iterate over active keys.
     /// 
     /// # Example
@@ -65,7 +215,76 @@ where
     /// assert_eq!(sv[1], ss[2]);
     /// ```
     pub fn iter_keys(&self) -> impl Iterator<Item = usize> + '_ {
-        (0..self.slots.len()).filter(|key| !self.open_slots.contains(key))
+        self.occupied.keys()
+    }
+    /// Iterate over open keys in the order `insert` will reuse them.
+    ///
+    /// Unless strict indexing is enabled, open slots can be
+    /// indexed to pre-initialize them before a burst of insertions.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut ss = SlicedSlab::from_vec(2, (0..8).collect());
+    /// ss.release(1);
+    /// ss.release(3);
+    /// assert_eq!(ss.vacant_len(), 2);
+    /// assert_eq!(ss.iter_vacant_keys().collect::<Vec<_>>(), vec![3, 1]);
+    /// for key in ss.iter_vacant_keys().collect::<Vec<_>>() {
+    ///     ss[key].fill(0);
+    /// }
+    /// assert_eq!(ss.acquire(), Some(3));
+    /// assert_eq!(ss[3], [0, 0]);
+    /// ```
+    pub fn iter_vacant_keys(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut key = self.free_head;
+        std::iter::from_fn(move || {
+            let current = key;
+            (current != END).then(|| {
+                key = self.links[current].next;
+                current
+            })
+        })
+    }
+    /// Returns the number of open slots.
+    pub fn vacant_len(&self) -> usize {
+        self.open_len
+    }
+    /// Iterate over active keys within `range`.
+    ///
+    /// Open slots are skipped using the occupancy bitmap, so
+    /// the cost depends on the width of the range rather than
+    /// the size of the slab.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::from_vec(1, (0..10).collect());
+    /// ss.release_many([3, 4, 8]);
+    /// assert_eq!(ss.keys_in_range(2..9).collect::<Vec<_>>(), vec![2, 5, 6, 7]);
+    /// assert_eq!(ss.keys_in_range(20..30).count(), 0);
+    /// ```
+    pub fn keys_in_range(&self, range: Range<usize>) -> impl Iterator<Item = usize> + '_ {
+        let mut next = range.start;
+        std::iter::from_fn(move || {
+            let key = self
+                .occupied
+                .next_occupied(next)
+                .filter(|key| *key < range.end)?;
+            next = key + 1;
+            Some(key)
+        })
+    }
+    /// Iterate over key, slice pairs of occupied slots within `range`.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::from_vec(2, (0..10).collect());
+    /// ss.release(2);
+    /// let sums: Vec<_> = ss.iter_range(1..4).map(|(_, s)| s[0] + s[1]).collect();
+    /// assert_eq!(sums, vec![5, 13]);
+    /// ```
+    pub fn iter_range(&self, range: Range<usize>) -> impl Iterator<Item = (usize, &[T])> {
+        self.keys_in_range(range).map(|key| (key, &self.slots[key]))
     }
     /// Get active keys.
     /// 
@@ -82,7 +301,7 @@ where
     }
     /// Insert a segment into the slab.
     /// 
-    /// The first available slot is overwritten
+    /// The most recently released slot is overwritten
     /// with the contents of the slice. Otherwise,
     /// the slice is appended to the storage. Returns
     /// a key for later retrieval.
@@ -102,7 +321,7 @@ where
     /// not match the segments size of the slab.
     pub fn insert(&mut self, segment: &[T]) -> usize {
         assert_eq!(segment.len(), self.slots.segment_len());
-        match self.open_slots.pop_first() {
+        match self.acquire() {
             Some(key) => {
                 debug_assert!(key < self.slots.len());
                 unsafe {
@@ -113,11 +332,160 @@ where
             }
             None => {
                 let key = self.slots.len();
+                let capacity = self.slots.storage.capacity();
                 self.slots.push(segment);
+                self.count_growth(capacity);
+                self.links.push(OCCUPIED_LINK);
+                self.occupied.insert(key);
                 key
             }
         }
     }
+    /// Insert a segment without growing the storage.
+    ///
+    /// Like `insert`, but only an open slot or spare capacity
+    /// is used, so the backing storage is never reallocated.
+    /// Returns `SlabFull` if neither is available.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut ss = SlicedSlab::with_capacity(2, 1);
+    /// assert_eq!(ss.try_insert(&[1, 2]), Ok(0));
+    /// assert_eq!(ss.try_insert(&[3, 4]), Err(SlabFull));
+    /// ss.release(0);
+    /// assert_eq!(ss.try_insert(&[3, 4]), Ok(0));
+    /// assert_eq!(ss.capacity(), 1);
+    /// ```
+    /// # Panics
+    /// If the length of the slice does
+    /// not match the segments size of the slab.
+    pub fn try_insert(&mut self, segment: &[T]) -> Result<usize, SlabFull> {
+        assert_eq!(segment.len(), self.slots.segment_len());
+        if self.free_head == END
+            && (self.slots.storage.capacity() - self.slots.storage.len() < segment.len()
+                || self.links.len() == self.links.capacity()
+                || self.occupied.capacity() == self.slots.len())
+        {
+            return Err(SlabFull);
+        }
+        Ok(self.insert(segment))
+    }
+    /// Insert every segment of `segments`, returning their keys.
+    ///
+    /// Open slots are filled first and the remaining segments
+    /// are appended after a single reservation.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut ss = SlicedSlab::from_vec(2, (0..6).collect());
+    /// ss.release(1);
+    /// let keys = ss.insert_many(&slicedvec![[6, 7], [8, 9], [10, 11]]);
+    /// assert_eq!(keys, vec![1, 3, 4]);
+    /// assert_eq!(ss[4], [10, 11]);
+    /// ```
+    /// # Panics
+    /// If the segment length of `segments` does
+    /// not match the segments size of the slab.
+    pub fn insert_many(&mut self, segments: &SlicedVec<T>) -> Vec<usize> {
+        assert_eq!(segments.segment_len(), self.slots.segment_len());
+        let mut keys = Vec::with_capacity(segments.len());
+        let mut iter = segments.iter();
+        while self.free_head != END {
+            let Some(segment) = iter.next() else {
+                break;
+            };
+            keys.push(self.insert(segment));
+        }
+        let first = self.slots.len();
+        let rest = segments.len() - keys.len();
+        self.reserve(rest);
+        let begin = segments.storage_begin(keys.len());
+        self.slots.storage.extend_from_slice(&segments.storage[begin..]);
+        self.links.resize(first + rest, OCCUPIED_LINK);
+        (first..first + rest).for_each(|key| self.occupied.insert(key));
+        keys.extend(first..first + rest);
+        keys
+    }
+    /// Insert a segment under a specific key.
+    ///
+    /// An open slot is claimed directly. A key past the end
+    /// grows the slab, marking the slots in between open with
+    /// copies of `segment` as placeholders. Claiming an open
+    /// slot is constant time.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut ss = SlicedSlab::new(2);
+    /// assert_eq!(ss.insert_at(3, &[1, 2]), Ok(()));
+    /// assert_eq!(ss.len(), 4);
+    /// assert_eq!(ss.get_keys(), vec![3]);
+    /// assert_eq!(ss.insert_at(1, &[3, 4]), Ok(()));
+    /// assert_eq!(ss.insert_at(3, &[5, 6]), Err(KeyOccupied));
+    /// assert_eq!(ss.insert(&[7, 8]), 0);
+    /// assert_eq!(ss.insert(&[9, 9]), 2);
+    /// ```
+    /// # Panics
+    /// If the length of the slice does
+    /// not match the segments size of the slab.
+    pub fn insert_at(&mut self, key: usize, segment: &[T]) -> Result<(), KeyOccupied> {
+        assert_eq!(segment.len(), self.slots.segment_len());
+        if self.occupied.contains(key) {
+            return Err(KeyOccupied);
+        }
+        if key < self.slots.len() {
+            self.take_open(key);
+            unsafe {
+                // Safety: key is in-bounds and segment length is checked
+                self.slots.overwrite(key, segment);
+            }
+        } else {
+            let first = self.slots.len();
+            let capacity = self.slots.storage.capacity();
+            (first..=key).for_each(|_| self.slots.push(segment));
+            self.count_growth(capacity);
+            self.links.resize(key + 1, OCCUPIED_LINK);
+            (first..=key).for_each(|key| self.occupied.insert(key));
+            (first..key).rev().for_each(|key| self.free_slot(key));
+        }
+        Ok(())
+    }
+    // Mark a particular open slot as occupied
+    fn take_open(&mut self, key: usize) {
+        self.touch(key);
+        self.unlink(key);
+        self.links[key] = OCCUPIED_LINK;
+        self.occupied.insert(key);
+        self.open_len -= 1;
+        self.counters.free_list_ops += 1;
+    }
+    // Remove an open slot from the free list
+    fn unlink(&mut self, key: usize) {
+        debug_assert!(!self.occupied.contains(key));
+        let Link { next, prev } = self.links[key];
+        self.touch(prev);
+        self.touch(next);
+        match prev {
+            END => self.free_head = next,
+            _ => self.links[prev].next = next,
+        }
+        if next != END {
+            self.links[next].prev = prev;
+        }
+    }
+    // Push an open slot onto the front of the free list
+    fn link_front(&mut self, key: usize) {
+        self.touch(key);
+        self.touch(self.free_head);
+        self.links[key] = Link {
+            next: self.free_head,
+            prev: END,
+        };
+        if self.free_head != END {
+            self.links[self.free_head].prev = key;
+        }
+        self.free_head = key;
+        self.low_water = self.low_water.min(key);
+    }
     /// Insert a vector into the slab.
     ///
     /// # Example
@@ -131,12 +499,16 @@ where
     }
     /// Copy a segment and return a new key.
     ///
-    /// If there exists an open slot closer to the
-    /// start of the slab, then the data pointed
+    /// If the lowest open slot is closer
+    /// to the start of the slab, then the data pointed
     /// to by `oldkey` will be moved there and
     /// a new key will be returned. Otherwise, no
     /// action is taken and `oldkey` is returned
-    /// unchanged.
+    /// unchanged. The lowest open slot is found by
+    /// scanning the occupancy bitmap, 64 slots per step,
+    /// from a low-water mark that only moves back when a
+    /// lower slot is released. A pass of rekeys over the
+    /// slab therefore scans each bitmap word about once.
     /// # Example
     /// ```
     /// use sliced::SlicedSlab;
@@ -151,18 +523,17 @@ where
     /// If the old key is already marked as available.
     pub fn rekey(&mut self, oldkey: usize) -> usize {
         debug_assert!(oldkey < self.slots.len());
-        if self.open_slots.first() < Some(&oldkey) {
-            match self.acquire() {
-                Some(newkey) => {
-                    self.release(oldkey);
-                    debug_assert!(newkey < self.slots.len());
-                    let src = self.slots.storage_range(oldkey);
-                    let dst = self.slots.storage_begin(newkey);
-                    self.slots.storage.copy_within(src, dst);
-                    newkey
-                }
-                None => oldkey,
-            }
+        // Keys past the last slot read as vacant, so this
+        // also covers no open slots
+        let newkey = self.occupied.next_vacant(self.low_water);
+        self.low_water = newkey;
+        if newkey < oldkey {
+            self.take_open(newkey);
+            self.free_slot(oldkey);
+            let src = self.slots.storage_range(oldkey);
+            let dst = self.slots.storage_begin(newkey);
+            self.slots.storage.copy_within(src, dst);
+            newkey
         } else {
             oldkey
         }
@@ -172,9 +543,11 @@ where
     /// If all key-holders have called rekey, this
     /// function will remove all open slots, thus
     /// fully compacting the slab. The storage capacity
-    /// is not affected. This will greatly increase the
-    /// speed of key look-ups as there will be no open
-    /// slots to search. Subsequent insertions will all
+    /// is not affected. Any remaining open slots are
+    /// relinked so the lowest is reused first, which
+    /// costs a scan of the occupancy bitmap, one word
+    /// per 64 slots, plus one step per open slot. Once no
+    /// open slots remain, subsequent insertions will all
     /// be pushed to the end of the storage. Or if all
     /// slots are open, the slab will be empty after
     /// this call.
@@ -193,27 +566,382 @@ where
     /// assert_eq!(ss.sparsity(), 0.0);
     /// ```
     pub fn compact(&mut self) {
-        if self.open_slots.len() == self.slots.len() {
+        if self.open_len == self.slots.len() {
             // Covers empty case
-            self.open_slots.clear();
-            self.slots.clear()
+            self.clear()
         } else {
             debug_assert!(!self.slots.is_empty());
-            debug_assert!(self.open_slots.len() < self.slots.len());
-            let mut len = self.slots.len();
-            while self.open_slots.last() == Some(&(len - 1)) {
-                self.open_slots.pop_last();
-                debug_assert!(len > 0);
-                len -= 1;
-            }
+            let len = self.occupied.prev_occupied(self.slots.len()).unwrap() + 1;
+            self.touch_tail(len);
             self.slots.truncate(len);
-            debug_assert!(self.open_slots.len() <= self.slots.len());
-            debug_assert!(self.open_slots.last() < Some(&self.slots.len()));
+            self.links.truncate(len);
+            self.occupied.truncate(len);
+            self.rebuild_free_list();
+        }
+    }
+    /// Fully compact the slab, returning the keys that moved.
+    ///
+    /// Occupied slots at the end are moved down into the lowest
+    /// open slots until no open slots remain. Each relocation is
+    /// reported as an `(old_key, new_key)` pair in the order it
+    /// occurred. Unlike `compact`, no prior calls to `rekey` are
+    /// needed. The storage capacity is not affected.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::from_vec(2, (0..10).collect());
+    /// ss.release(0);
+    /// ss.release(2);
+    /// ss.release(4); // [vac][occ][vac][occ][vac]
+    /// assert_eq!(ss.compact_remap(), vec![(3, 0)]);
+    /// assert_eq!(ss.len(), 2);
+    /// assert_eq!(ss.sparsity(), 0.0);
+    /// assert_eq!(ss[0], [6, 7]);
+    /// assert_eq!(ss[1], [2, 3]);
+    /// ```
+    pub fn compact_remap(&mut self) -> Vec<(usize, usize)> {
+        let mut remap = Vec::new();
+        self.compact_with(|old, new| remap.push((old, new)));
+        remap
+    }
+    /// Fully compact the slab, calling `f(old_key, new_key)`
+    /// for each slot as it is moved.
+    ///
+    /// This lets external index structures be updated during
+    /// the compaction pass. The slot has already been copied
+    /// when `f` is called. See `compact_remap`.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::from_vec(1, (0..4).collect());
+    /// let mut handles = vec![0, 1, 2, 3];
+    /// ss.release(0);
+    /// ss.release(1);
+    /// ss.compact_with(|old, new| handles[old] = new);
+    /// assert_eq!(handles, [0, 1, 1, 0]);
+    /// assert_eq!(ss[handles[2]], [2]);
+    /// assert_eq!(ss[handles[3]], [3]);
+    /// ```
+    pub fn compact_with<F>(&mut self, f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        let len = self.move_down(f);
+        self.touch_tail(len);
+        self.slots.truncate(len);
+        self.links.truncate(len);
+        self.occupied.truncate(len);
+        self.free_head = END;
+        self.open_len = 0;
+        self.low_water = len;
+    }
+    /// Rekey every occupied slot toward the front of the slab.
+    ///
+    /// The batched form of `rekey`. The highest occupied slots
+    /// are moved into the lowest open slots, leaving all open
+    /// slots at the end where `compact` can remove them. Returns
+    /// the `(old_key, new_key)` pairs in the order they moved.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::from_vec(1, (0..5).collect());
+    /// ss.release(0);
+    /// ss.release(2);
+    /// assert_eq!(ss.rekey_all(), vec![(4, 0), (3, 2)]);
+    /// assert_eq!(ss.len(), 5);
+    /// assert_eq!(ss.get_keys(), vec![0, 1, 2]);
+    /// ss.compact();
+    /// assert_eq!(ss.len(), 3);
+    /// ```
+    pub fn rekey_all(&mut self) -> Vec<(usize, usize)> {
+        let mut remap = Vec::new();
+        self.move_down(|old, new| remap.push((old, new)));
+        self.rebuild_free_list();
+        remap
+    }
+    // Move the highest occupied slots into the lowest open slots,
+    // returning the length needed to hold every occupied slot.
+    // The free list must be rebuilt or discarded afterward.
+    fn move_down<F>(&mut self, mut f: F) -> usize
+    where
+        F: FnMut(usize, usize),
+    {
+        let mut len = self.slots.len();
+        let mut first = 0;
+        while let Some(last) = self.occupied.prev_occupied(len) {
+            first = self.occupied.next_vacant(first);
+            if first > last {
+                break;
+            }
+            self.touch(first);
+            self.touch(last);
+            let src = self.slots.storage_range(last);
+            let dst = self.slots.storage_begin(first);
+            self.slots.storage.copy_within(src, dst);
+            self.links[first] = OCCUPIED_LINK;
+            self.links[last] = Link {
+                next: END,
+                prev: END,
+            };
+            self.occupied.insert(first);
+            self.occupied.remove(last);
+            self.counters.bytes_moved += (self.segment_len() * mem::size_of::<T>()) as u64;
+            f(last, first);
+            len = last;
         }
+        self.occupied.prev_occupied(len).map_or(0, |last| last + 1)
+    }
+    /// Remove all slots.
+    ///
+    /// The storage capacity is not affected.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::with_capacity(2, 20);
+    /// ss.insert(&[1, 2]);
+    /// ss.release(0);
+    /// ss.clear();
+    /// assert!(ss.is_empty());
+    /// assert_eq!(ss.capacity(), 20);
+    /// assert_eq!(ss.insert(&[3, 4]), 0);
+    /// ```
+    pub fn clear(&mut self) {
+        self.touch_tail(0);
+        self.slots.clear();
+        self.links.clear();
+        self.occupied.truncate(0);
+        self.free_head = END;
+        self.open_len = 0;
+        self.low_water = 0;
+    }
+    /// Fully compact the slab and release unused capacity.
+    ///
+    /// Combines `compact_remap` and `shrink_to_fit`, so interior
+    /// open slots are reclaimed too. Returns the keys that moved.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::with_capacity(1, 100);
+    /// (0..100).for_each(|n| { ss.insert(&[n]); });
+    /// (0..99).for_each(|key| ss.release(key));
+    /// assert_eq!(ss.shrink(), vec![(99, 0)]);
+    /// assert_eq!(ss[0], [99]);
+    /// assert_eq!(ss.capacity(), 1);
+    /// ```
+    pub fn shrink(&mut self) -> Vec<(usize, usize)> {
+        let remap = self.compact_remap();
+        self.shrink_to_fit();
+        remap
     }
     /// Call `shrink_to_fit` on the storage.
     pub fn shrink_to_fit(&mut self) {
-        self.slots.shrink_to_fit()
+        let capacity = self.slots.storage.capacity();
+        self.slots.shrink_to_fit();
+        self.count_growth(capacity);
+        self.links.shrink_to_fit();
+        self.occupied.words.shrink_to_fit();
+    }
+    /// Reserve capacity for at least `additional` more slots.
+    ///
+    /// Open slots are reused first, so this is only
+    /// needed when inserting beyond them.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::<u8>::new(4);
+    /// ss.reserve(100);
+    /// assert!(ss.capacity() >= 100);
+    /// ```
+    /// # Panics
+    /// If the new capacity overflows `usize`.
+    pub fn reserve(&mut self, additional: usize) {
+        let capacity = self.slots.storage.capacity();
+        self.slots.reserve(additional);
+        self.count_growth(capacity);
+        self.links.reserve(additional);
+        self.occupied.reserve(self.slots.len() + additional);
+    }
+    /// Try to reserve capacity for at least `additional` more slots.
+    ///
+    /// Returns an error if the capacity overflows or the
+    /// allocator reports a failure.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let capacity = self.slots.storage.capacity();
+        self.slots.try_reserve(additional)?;
+        self.count_growth(capacity);
+        self.links.try_reserve(additional)?;
+        self.occupied.try_reserve(self.slots.len() + additional)
+    }
+    /// Report occupancy and activity counters.
+    ///
+    /// The counters accumulate from construction or the last
+    /// call to `reset_stats`.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::<u32>::from_vec(1, (0..5).collect());
+    /// ss.release(1);
+    /// ss.release(4);
+    /// ss.compact_remap();
+    /// let stats = ss.stats();
+    /// assert_eq!(stats.occupied, 3);
+    /// assert_eq!(stats.open, 0);
+    /// assert_eq!(stats.largest_occupied_run, 3);
+    /// assert_eq!(stats.free_list_ops, 2);
+    /// assert_eq!(stats.bytes_moved, 4);
+    /// ss.reset_stats();
+    /// assert_eq!(ss.stats().free_list_ops, 0);
+    /// ```
+    pub fn stats(&self) -> SlabStats {
+        let mut largest_occupied_run = 0;
+        let mut run = 0;
+        let mut next = 0;
+        for key in self.occupied.keys() {
+            run = if key == next { run + 1 } else { 1 };
+            largest_occupied_run = largest_occupied_run.max(run);
+            next = key + 1;
+        }
+        SlabStats {
+            occupied: self.occupied_len(),
+            open: self.open_len,
+            largest_occupied_run,
+            free_list_ops: self.counters.free_list_ops,
+            reallocations: self.counters.reallocations,
+            bytes_moved: self.counters.bytes_moved,
+        }
+    }
+    /// Reset the activity counters reported by `stats`.
+    pub fn reset_stats(&mut self) {
+        self.counters = Counters::default();
+    }
+    /// Start recording changes so they can be rolled back.
+    ///
+    /// Taking a snapshot is constant time and copies nothing.
+    /// Instead, the first change after it to each existing slot
+    /// saves that slot's segment and free list link. A change is
+    /// a release, an insert into an open slot, removal by `clear`
+    /// or compaction, or a mutable borrow of the segment, whether
+    /// or not it is written; `iter_mut` therefore saves every
+    /// occupied slot. Slots appended after the snapshot are not
+    /// saved. Taking another snapshot replaces this one.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::from_vec(2, (0..6).collect());
+    /// ss.release(1);
+    /// let snapshot = ss.snapshot();
+    /// ss[0][0] = 99;
+    /// ss.release(2);
+    /// ss.insert(&[7, 7]);
+    /// ss.insert(&[8, 8]);
+    /// ss.restore(&snapshot);
+    /// assert_eq!(ss.get_keys(), vec![0, 2]);
+    /// assert_eq!(ss[0], [0, 1]);
+    /// assert_eq!(ss.insert(&[9, 9]), 1);
+    /// ```
+    pub fn snapshot(&mut self) -> SlabSnapshot {
+        let id = NEXT_SNAPSHOT.fetch_add(1, Ordering::Relaxed);
+        let mut undo = self
+            .undo
+            .take()
+            .unwrap_or_else(|| UndoLog::new(self.segment_len()));
+        undo.clear();
+        undo.id = id;
+        undo.len = self.slots.len();
+        undo.free_head = self.free_head;
+        undo.open_len = self.open_len;
+        undo.low_water = self.low_water;
+        self.undo = Some(undo);
+        SlabSnapshot {
+            id,
+            len: self.slots.len(),
+            occupied_len: self.occupied_len(),
+        }
+    }
+    /// Roll back to the state captured by `snapshot`.
+    ///
+    /// Keys, segments, including the contents of open slots,
+    /// and the order in which open slots are reused all match
+    /// the snapshot. Complexity is linear in the slots saved
+    /// since the snapshot plus any change in the number of
+    /// slots. The snapshot stays current, so it can be restored
+    /// again later. No auto compaction is run.
+    /// # Example
+    /// ```should_panic
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::<u8>::new(2);
+    /// let first = ss.snapshot();
+    /// let _second = ss.snapshot();
+    /// ss.restore(&first);
+    /// ```
+    /// # Panics
+    /// If `snapshot` is not the latest one taken from this slab,
+    /// or from the slab it was cloned from after the snapshot.
+    pub fn restore(&mut self, snapshot: &SlabSnapshot) {
+        assert!(
+            self.undo.as_ref().is_some_and(|undo| undo.id == snapshot.id),
+            "snapshot is not the latest taken from this slab"
+        );
+        let mut undo = self.undo.take().unwrap();
+        let len = undo.len;
+        let capacity = self.slots.storage.capacity();
+        self.slots.truncate(len);
+        if self.slots.len() < len {
+            // Every slot past the end was saved before it was
+            // removed, so the fill value is overwritten below
+            let fill = undo.saved.storage[0];
+            self.slots.storage.resize(len * self.segment_len(), fill);
+        }
+        self.count_growth(capacity);
+        self.links.truncate(len);
+        self.links.resize(len, OCCUPIED_LINK);
+        self.occupied.words.resize(len.div_ceil(64), 0);
+        self.occupied.truncate(len);
+        for (&(key, link), segment) in undo.links.iter().zip(undo.saved.iter()) {
+            self.slots[key].copy_from_slice(segment);
+            self.links[key] = link;
+            match link.next {
+                OCCUPIED => self.occupied.insert(key),
+                _ => self.occupied.remove(key),
+            }
+        }
+        self.free_head = undo.free_head;
+        self.open_len = undo.open_len;
+        self.low_water = undo.low_water;
+        undo.clear();
+        self.undo = Some(undo);
+    }
+    /// Keep the current contents and stop recording changes.
+    ///
+    /// Any snapshot can no longer be restored.
+    pub fn discard_snapshot(&mut self) {
+        self.undo = None;
+    }
+    // Save slot `key` before its first change since the snapshot
+    fn touch(&mut self, key: usize) {
+        if let Some(undo) = &mut self.undo {
+            undo.save(key, &self.links, &self.slots);
+        }
+    }
+    // Save the slots from `len` on before the slab is shortened
+    fn touch_tail(&mut self, len: usize) {
+        if let Some(undo) = &mut self.undo {
+            (len..self.slots.len()).for_each(|key| undo.save(key, &self.links, &self.slots));
+        }
+    }
+    // Save every occupied slot before handing out mutable access
+    pub(crate) fn touch_occupied(&mut self) {
+        if let Some(undo) = &mut self.undo {
+            for key in self.occupied.keys() {
+                undo.save(key, &self.links, &self.slots);
+            }
+        }
+    }
+    // Count a reallocation if the storage capacity changed
+    fn count_growth(&mut self, capacity: usize) {
+        if self.slots.storage.capacity() != capacity {
+            self.counters.reallocations += 1;
+        }
     }
     /// Compute the proportion of open slots.
     ///
@@ -222,26 +950,194 @@ where
     /// A sparsity of 1.0 indicates only open slots and compaction
     /// will lead to an empty slab.
     pub fn sparsity(&self) -> f32 {
-        self.open_slots.len() as f32 / self.slots.len() as f32
+        self.open_len as f32 / self.slots.len() as f32
+    }
+    /// Compact automatically once sparsity exceeds `max_sparsity`.
+    ///
+    /// After each `release` or `retain`, the slab is compacted
+    /// with `compact_with` if `sparsity()` is greater than
+    /// `max_sparsity`, and `on_remap(old_key, new_key)` is called
+    /// for every slot that moves. Replaces any previous policy.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// use std::sync::{Arc, Mutex};
+    /// let moved = Arc::new(Mutex::new(Vec::new()));
+    /// let log = moved.clone();
+    /// let mut ss = SlicedSlab::from_vec(1, (0..4).collect());
+    /// ss.set_auto_compact(0.5, move |old, new| log.lock().unwrap().push((old, new)));
+    /// ss.release(0);
+    /// ss.release(1);
+    /// assert!(moved.lock().unwrap().is_empty());
+    /// ss.release(2);
+    /// assert_eq!(*moved.lock().unwrap(), [(3, 0)]);
+    /// assert_eq!(ss.len(), 1);
+    /// assert_eq!(ss[0], [3]);
+    /// ```
+    /// # Panics
+    /// If `max_sparsity` is not between 0 and 1.
+    pub fn set_auto_compact<F>(&mut self, max_sparsity: f32, on_remap: F)
+    where
+        F: FnMut(usize, usize) + Send + Sync + 'static,
+    {
+        assert!((0.0..=1.0).contains(&max_sparsity));
+        self.auto_compact = Some(AutoCompact {
+            max_sparsity,
+            on_remap: Box::new(on_remap),
+        });
+    }
+    /// Remove the automatic compaction policy.
+    pub fn clear_auto_compact(&mut self) {
+        self.auto_compact = None;
+    }
+    // Apply the auto compaction policy if one is set
+    fn maybe_compact(&mut self) {
+        if let Some(mut policy) = self.auto_compact.take() {
+            if self.sparsity() > policy.max_sparsity {
+                self.compact_with(&mut policy.on_remap);
+            }
+            self.auto_compact = Some(policy);
+        }
     }
     /// Mark the slot as open for future overwrite.
     ///
-    /// Keys are not globally unique. They will be reused.
-    /// Marking the slot unoccupied is logarithmic in the
-    /// number of open slots.
+    /// Keys are not globally unique. They will be reused,
+    /// most recently released first. Marking the slot
+    /// unoccupied is constant time.
     /// # Panics
     /// If the slot is already marked as available.
     pub fn release(&mut self, key: usize) {
+        self.free_slot(key);
+        self.maybe_compact();
+    }
+    /// Release several slots at once.
+    ///
+    /// Equivalent to calling `release` for each key, except any
+    /// automatic compaction is checked once at the end.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::from_vec(1, (0..6).collect());
+    /// ss.release_many([4, 1, 2]);
+    /// assert_eq!(ss.get_keys(), vec![0, 3, 5]);
+    /// ```
+    /// # Panics
+    /// If a key is out of range or already marked as available.
+    /// Keys preceding it remain released.
+    pub fn release_many<I>(&mut self, keys: I)
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        keys.into_iter().for_each(|key| self.free_slot(key));
+        self.maybe_compact();
+    }
+    /// Release every slot in `range`.
+    ///
+    /// The released slots are reused from the lowest key up.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::from_vec(1, (0..6).collect());
+    /// ss.release_range(1..4);
+    /// assert_eq!(ss.get_keys(), vec![0, 4, 5]);
+    /// assert_eq!(ss.insert(&[9]), 1);
+    /// ```
+    /// # Panics
+    /// If the range is out of bounds or any slot in it
+    /// is already marked as available. No slot is released
+    /// in that case.
+    pub fn release_range(&mut self, range: Range<usize>) {
+        assert!(range.start <= range.end && range.end <= self.slots.len());
+        assert!(range.clone().all(|key| self.occupied.contains(key)));
+        range.rev().for_each(|key| self.free_slot(key));
+        self.maybe_compact();
+    }
+    // Push an occupied slot onto the free list
+    fn free_slot(&mut self, key: usize) {
+        // The assertions ensure that no key is out of bounds
+        // or released twice
         assert!(key < self.slots.len());
-        // This is the only site where keys are added
-        // The assertion ensures that no key is out of bounds
-        assert!(self.open_slots.insert(key));
-        debug_assert!(self.open_slots.len() <= self.slots.len());
+        assert_eq!(self.links[key].next, OCCUPIED);
+        self.link_front(key);
+        self.occupied.remove(key);
+        self.counters.free_list_ops += 1;
+        self.open_len += 1;
+        debug_assert!(self.open_len <= self.slots.len());
+    }
+    // Build a slab from full storage and its open keys in free
+    // list order, which the caller has checked are in range and
+    // distinct
+    pub(crate) fn from_open_keys(segment_len: usize, storage: Vec<T>, free: &[usize]) -> Self {
+        let mut slab = Self::from_vec(segment_len, storage);
+        for &key in free.iter().rev() {
+            slab.occupied.remove(key);
+            slab.link_front(key);
+        }
+        slab.open_len = free.len();
+        slab
+    }
+    // Remove the last slot, which must be occupied
+    pub(crate) fn pop_slot(&mut self, key: usize) {
+        assert_eq!(key + 1, self.slots.len());
+        assert_eq!(self.links[key].next, OCCUPIED);
+        self.touch(key);
+        self.occupied.remove(key);
+        self.links.pop();
+        self.slots.truncate(key);
+    }
+    // Thread the free list through the open slots in key order,
+    // skipping fully occupied words of the bitmap
+    fn rebuild_free_list(&mut self) {
+        let len = self.links.len();
+        self.free_head = END;
+        self.open_len = 0;
+        let mut tail = END;
+        let mut key = self.occupied.next_vacant(0);
+        self.low_water = key;
+        while key < len {
+            self.touch(key);
+            match tail {
+                END => self.free_head = key,
+                _ => self.links[tail].next = key,
+            }
+            self.links[key].prev = tail;
+            tail = key;
+            self.open_len += 1;
+            key = self.occupied.next_vacant(key + 1);
+        }
+        if tail != END {
+            self.links[tail].next = END;
+        }
+    }
+    /// Release every occupied slot for which `f` returns `false`.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::from_vec(2, (0..8).collect());
+    /// ss.release(0);
+    /// ss.retain(|_, slice| slice[0] != 4);
+    /// assert_eq!(ss.get_keys(), vec![1, 3]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, &mut [T]) -> bool,
+    {
+        let mut next = self.occupied.next_occupied(0);
+        while let Some(key) = next {
+            self.touch(key);
+            if !f(key, &mut self.slots[key]) {
+                self.free_slot(key);
+            }
+            next = self.occupied.next_occupied(key + 1);
+        }
+        self.maybe_compact();
     }
     /// Acquire a previously released slot.
     ///
     /// This allows one to directly update
-    /// the internal storage. Returns `None`
+    /// the internal storage. The most recently
+    /// released slot is returned first, or `None`
     /// if there are no open slots.
     /// # Example
     /// ```
@@ -255,16 +1151,35 @@ where
     /// assert_eq!(ss[key], [0, 0]);
     /// ```
     pub fn acquire(&mut self) -> Option<usize> {
-        self.open_slots.pop_first()
+        if self.free_head == END {
+            return None;
+        }
+        let key = self.free_head;
+        self.take_open(key);
+        Some(key)
+    }
+    /// Test if `key` refers to an occupied slot.
+    ///
+    /// The check is constant time.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::from_vec(2, (0..4).collect());
+    /// ss.release(0);
+    /// assert!(!ss.contains_key(0));
+    /// assert!(ss.contains_key(1));
+    /// assert!(!ss.contains_key(2));
+    /// ```
+    pub fn contains_key(&self, key: usize) -> bool {
+        self.occupied.contains(key)
     }
     /// Get a reference to a segment.
     ///
     /// Returns `None` if `key` is out of range
     /// or the slot is marked as unoccupied. Key
-    /// checks are logarithmic in the number of
-    /// open slots.
+    /// checks are constant time.
     pub fn get(&self, key: usize) -> Option<&[T]> {
-        if self.open_slots.contains(&key) {
+        if !self.contains_key(key) {
             return None;
         }
         self.slots.get(key)
@@ -273,39 +1188,433 @@ where
     ///
     /// Returns `None` if `key` is out of range
     /// or the slot is marked as unoccupied. Key
-    /// checks are logarithmic in the number of
-    /// open slots.
+    /// checks are constant time.
     pub fn get_mut(&mut self, key: usize) -> Option<&mut [T]> {
-        if self.open_slots.contains(&key) {
+        if !self.contains_key(key) {
             return None;
         }
+        self.touch(key);
         self.slots.get_mut(key)
     }
-    /// Iterate over key, slice pairs.
+    /// Make indexing panic on open slots.
+    ///
+    /// By default `ss[key]` returns whatever is stored at an
+    /// open slot. In strict mode indexing checks occupancy like
+    /// `get` does. The unchecked accessors are not affected.
+    /// # Example
+    /// ```should_panic
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::from_vec(2, (0..4).collect());
+    /// ss.set_strict_indexing(true);
+    /// ss.release(0);
+    /// let _ = ss[0][0];
+    /// ```
+    pub fn set_strict_indexing(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+    /// Test if indexing panics on open slots.
+    pub fn is_strict_indexing(&self) -> bool {
+        self.strict
+    }
+    // Check a key used for indexing in strict mode
+    fn check_index(&self, key: usize) {
+        if self.strict {
+            assert!(self.contains_key(key), "slot {} is not occupied", key);
+        }
+    }
+    /// Get a reference to a segment without any checks.
     ///
-    /// This will be slow if there are a large number of open slots.
+    /// Neither the bounds nor the occupancy of `key` are
+    /// checked. An open slot yields its stale contents.
     /// # Example
     /// ```
     /// use sliced::SlicedSlab;
-    /// let mut ss = SlicedSlab::from_vec(3, (1..=9).collect());
-    /// ss.release(1);
-    /// let s: usize = ss.enumerate()
-    ///     .map(|(key, slice)| key * slice.len())
-    ///     .sum();
-    /// assert_eq!(s, 6);
+    /// let ss = SlicedSlab::from_vec(2, (0..6).collect());
+    /// // Safety: ss has three slots
+    /// assert_eq!(unsafe { ss.get_unchecked(1) }, [2, 3]);
     /// ```
-    pub fn enumerate(&self) -> impl Iterator<Item = (usize, &[T])> {
-        self.slots
-            .enumerate()
-            .filter(|(key, _)| !self.open_slots.contains(key))
+    /// # Safety
+    /// `key` must be less than `len()`. Calling this
+    /// with an out-of-range key is undefined behavior
+    /// even if the result is not used.
+    pub unsafe fn get_unchecked(&self, key: usize) -> &[T] {
+        debug_assert!(key < self.slots.len());
+        let range = self.slots.storage_range(key);
+        self.slots.storage.get_unchecked(range)
     }
-}
-
+    /// Get a mutable reference to a segment without any checks.
+    ///
+    /// # Safety
+    /// `key` must be less than `len()`. Calling this
+    /// with an out-of-range key is undefined behavior
+    /// even if the result is not used.
+    pub unsafe fn get_unchecked_mut(&mut self, key: usize) -> &mut [T] {
+        debug_assert!(key < self.slots.len());
+        self.touch(key);
+        let range = self.slots.storage_range(key);
+        self.slots.storage.get_unchecked_mut(range)
+    }
+    /// Get mutable references to several segments at once.
+    ///
+    /// Returns `None` if any key is out of range, refers to an
+    /// open slot, or appears more than once.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::from_vec(2, (0..6).collect());
+    /// let [a, b] = ss.get_disjoint_mut([2, 0]).unwrap();
+    /// a.swap_with_slice(b);
+    /// assert_eq!(ss[0], [4, 5]);
+    /// assert_eq!(ss[2], [0, 1]);
+    /// assert!(ss.get_disjoint_mut([1, 1]).is_none());
+    /// ss.release(1);
+    /// assert!(ss.get_disjoint_mut([0, 1]).is_none());
+    /// ```
+    pub fn get_disjoint_mut<const N: usize>(&mut self, keys: [usize; N]) -> Option<[&mut [T]; N]> {
+        for (i, key) in keys.iter().enumerate() {
+            if !self.contains_key(*key) || keys[..i].contains(key) {
+                return None;
+            }
+        }
+        keys.iter().for_each(|key| self.touch(*key));
+        let segment_len = self.segment_len();
+        let ptr = self.slots.storage.as_mut_ptr();
+        // Safety: keys are in range and distinct so the segments do not overlap
+        Some(keys.map(|key| unsafe {
+            std::slice::from_raw_parts_mut(ptr.add(key * segment_len), segment_len)
+        }))
+    }
+    /// Iterate over key, slice pairs.
+    ///
+    /// Equivalent to `iter`.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::from_vec(3, (1..=9).collect());
+    /// ss.release(1);
+    /// let s: usize = ss.enumerate()
+    ///     .map(|(key, slice)| key * slice.len())
+    ///     .sum();
+    /// assert_eq!(s, 6);
+    /// ```
+    pub fn enumerate(&self) -> impl Iterator<Item = (usize, &[T])> {
+        self.iter()
+    }
+    /// Iterate over key, slice pairs of occupied slots.
+    ///
+    /// Open slots are skipped using the occupancy bitmap,
+    /// so long runs of open slots are passed over 64 at
+    /// a time.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::from_vec(2, (0..8).collect());
+    /// ss.release(0);
+    /// ss.release(2);
+    /// let mut iter = ss.iter();
+    /// assert_eq!(iter.len(), 2);
+    /// assert_eq!(iter.next(), Some((1, [2, 3].as_slice())));
+    /// assert_eq!(iter.next(), Some((3, [6, 7].as_slice())));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter(&self) -> SlicedSlabIter<'_, T> {
+        SlicedSlabIter {
+            slots: self.slots.storage.chunks_exact(self.segment_len()),
+            keys: self.occupied.keys(),
+            next_key: 0,
+            remaining: self.occupied_len(),
+        }
+    }
+    /// Iterate over key, mutable slice pairs of occupied slots.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::from_vec(2, (0..6).collect());
+    /// ss.release(1);
+    /// ss.iter_mut().for_each(|(key, slice)| slice.fill(key as i32));
+    /// assert_eq!(ss[0], [0, 0]);
+    /// assert_eq!(ss[1], [2, 3]);
+    /// assert_eq!(ss[2], [2, 2]);
+    /// ```
+    pub fn iter_mut(&mut self) -> SlicedSlabIterMut<'_, T> {
+        self.touch_occupied();
+        let segment_len = self.segment_len();
+        let remaining = self.occupied_len();
+        SlicedSlabIterMut {
+            slots: self.slots.storage.chunks_exact_mut(segment_len),
+            keys: self.occupied.keys(),
+            next_key: 0,
+            remaining,
+        }
+    }
+    /// Convert into a `SlicedVec` of the occupied segments.
+    ///
+    /// Open slots are dropped and the remaining segments keep
+    /// their key order. The storage is reused without copying
+    /// to a new allocation.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::from_vec(2, (0..6).collect());
+    /// ss.release(0);
+    /// let sv = ss.into_sliced_vec();
+    /// assert_eq!(sv.len(), 2);
+    /// assert_eq!(sv[0], [2, 3]);
+    /// ```
+    pub fn into_sliced_vec(mut self) -> SlicedVec<T> {
+        let segment_len = self.segment_len();
+        let mut write = 0;
+        for key in self.occupied.keys() {
+            if write != key {
+                let src = self.slots.storage_range(key);
+                self.slots.storage.copy_within(src, write * segment_len);
+            }
+            write += 1;
+        }
+        self.slots.truncate(write);
+        self.slots
+    }
+    /// Remove all slots, yielding key, vector pairs of occupied slots.
+    ///
+    /// The slab is empty once the iterator is dropped, even if
+    /// it was not fully consumed. The storage capacity is kept.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::from_vec(2, (0..6).collect());
+    /// ss.release(0);
+    /// let drained: Vec<_> = ss.drain().collect();
+    /// assert_eq!(drained, vec![(1, vec![2, 3]), (2, vec![4, 5])]);
+    /// assert!(ss.is_empty());
+    /// assert!(ss.capacity() >= 3);
+    /// ```
+    pub fn drain(&mut self) -> SlicedSlabDrain<'_, T> {
+        SlicedSlabDrain {
+            remaining: self.occupied_len(),
+            key: 0,
+            slab: self,
+        }
+    }
+    /// Iterate over the segments of occupied slots.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::from_vec(2, (0..6).collect());
+    /// ss.release(1);
+    /// let sums: Vec<i32> = ss.values().map(|s| s.iter().sum()).collect();
+    /// assert_eq!(sums, vec![1, 9]);
+    /// ```
+    pub fn values(&self) -> SlicedSlabValues<'_, T> {
+        SlicedSlabValues { iter: self.iter() }
+    }
+}
+
+impl<T> SlicedSlab<T>
+where
+    T: Copy + Clone + Default,
+{
+    /// Reserve a slot and fill it in place before committing.
+    ///
+    /// The key is known before the contents are written. An
+    /// open slot is reused if available and keeps its previous
+    /// contents; otherwise a new slot filled with `T::default()`
+    /// is pushed. Dropping the entry without calling `insert`
+    /// undoes the reservation: a pushed slot is removed and a
+    /// reused slot is reopened. No automatic compaction runs.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::<usize>::new(2);
+    /// let mut entry = ss.vacant_entry();
+    /// let key = entry.key();
+    /// entry.segment_mut().copy_from_slice(&[key, 42]);
+    /// assert_eq!(entry.insert(), key);
+    /// assert_eq!(ss[key], [0, 42]);
+    /// drop(ss.vacant_entry());
+    /// assert_eq!(ss.occupied_len(), 1);
+    /// assert_eq!(ss.len(), 1);
+    /// ss.insert(&[1, 1]);
+    /// ss.release(0);
+    /// ss.set_auto_compact(0.0, |_, _| panic!("no remap expected"));
+    /// drop(ss.vacant_entry());
+    /// assert_eq!(ss.len(), 2);
+    /// ```
+    pub fn vacant_entry(&mut self) -> VacantEntry<'_, T> {
+        let len = self.slots.len();
+        let key = self.acquire_or_push_default();
+        VacantEntry {
+            slab: self,
+            key,
+            appended: key == len,
+            committed: false,
+        }
+    }
+    /// Insert a segment initialized in place by `f`.
+    ///
+    /// An open slot is reused if available and `f` sees its
+    /// previous contents; otherwise a new slot filled with
+    /// `T::default()` is pushed. No temporary segment is built.
+    /// The key is marked occupied before `f` runs, so a pool
+    /// checkout is one call rather than `acquire`, `insert`,
+    /// then a write.
+    /// # Example
+    /// ```
+    /// use sliced::SlicedSlab;
+    /// let mut ss = SlicedSlab::<u32>::new(1000);
+    /// let key = ss.insert_with(|slice| {
+    ///     slice.iter_mut().enumerate().for_each(|(i, x)| *x = i as u32)
+    /// });
+    /// assert_eq!(ss[key][999], 999);
+    ///
+    /// let mut pool = SlicedSlab::<u8>::new(4);
+    /// let a = pool.insert_with(|buf| buf.fill(1));
+    /// pool.release(a);
+    /// let b = pool.insert_with(|buf| {
+    ///     assert_eq!(buf, [1; 4]);
+    ///     buf.fill(0);
+    /// });
+    /// assert_eq!(a, b);
+    /// assert_eq!(pool[b], [0; 4]);
+    /// let c = pool.insert_with(|buf| buf[0] = 9);
+    /// assert_eq!(pool[c], [9, 0, 0, 0]);
+    /// ```
+    #[doc(alias = "acquire_or_insert_with")]
+    pub fn insert_with<F>(&mut self, f: F) -> usize
+    where
+        F: FnOnce(&mut [T]),
+    {
+        let key = self.acquire_or_push_default();
+        f(&mut self.slots[key]);
+        key
+    }
+    // Take an open slot or push a default segment
+    fn acquire_or_push_default(&mut self) -> usize {
+        match self.acquire() {
+            Some(key) => key,
+            None => {
+                let key = self.slots.len();
+                let len = self.slots.storage.len() + self.slots.segment_len();
+                let capacity = self.slots.storage.capacity();
+                self.slots.storage.resize(len, T::default());
+                self.count_growth(capacity);
+                self.links.push(OCCUPIED_LINK);
+                self.occupied.insert(key);
+                key
+            }
+        }
+    }
+}
+
+/// A reserved slot of a `SlicedSlab` returned by `vacant_entry`.
+#[derive(Debug)]
+pub struct VacantEntry<'a, T>
+where
+    T: Copy + Clone,
+{
+    slab: &'a mut SlicedSlab<T>,
+    key: usize,
+    appended: bool,
+    committed: bool,
+}
+
+impl<'a, T> VacantEntry<'a, T>
+where
+    T: Copy + Clone,
+{
+    /// Returns the key the segment will be stored under.
+    pub fn key(&self) -> usize {
+        self.key
+    }
+    /// Get a mutable reference to the reserved segment.
+    pub fn segment_mut(&mut self) -> &mut [T] {
+        &mut self.slab.slots[self.key]
+    }
+    /// Mark the slot occupied and return its key.
+    pub fn insert(mut self) -> usize {
+        self.committed = true;
+        self.key
+    }
+}
+
+impl<'a, T> Drop for VacantEntry<'a, T>
+where
+    T: Copy + Clone,
+{
+    fn drop(&mut self) {
+        // Undo the reservation directly so that no automatic
+        // compaction, and hence no remap callback, runs here
+        if !self.committed {
+            if self.appended {
+                self.slab.pop_slot(self.key);
+            } else {
+                self.slab.free_slot(self.key);
+            }
+        }
+    }
+}
+
+/// Clone the slots.
+///
+/// Any auto compaction policy is not cloned since
+/// the remap callback cannot be duplicated.
+impl<T> Clone for SlicedSlab<T>
+where
+    T: Copy + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            slots: self.slots.clone(),
+            links: self.links.clone(),
+            occupied: self.occupied.clone(),
+            free_head: self.free_head,
+            open_len: self.open_len,
+            low_water: self.low_water,
+            undo: self.undo.clone(),
+            auto_compact: None,
+            counters: self.counters,
+            strict: self.strict,
+            arity: self.arity,
+        }
+    }
+}
+
+/// Compare occupied slots.
+///
+/// Two slabs are equal if they have the same segment length
+/// and the same occupied keys holding equal segments. The
+/// contents of open slots are ignored.
+/// # Example
+/// ```
+/// use sliced::SlicedSlab;
+/// let mut a = SlicedSlab::from_vec(2, (0..6).collect());
+/// let mut b = a.clone();
+/// assert_eq!(a, b);
+/// a.release(1);
+/// assert_ne!(a, b);
+/// b.release(1);
+/// b[1][0] = 99;
+/// assert_eq!(a, b);
+/// ```
+impl<T> PartialEq for SlicedSlab<T>
+where
+    T: Copy + Clone + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.segment_len() == other.segment_len()
+            && self.occupied_len() == other.occupied_len()
+            && self.enumerate().eq(other.enumerate())
+    }
+}
+
+impl<T> Eq for SlicedSlab<T> where T: Copy + Clone + Eq {}
+
 /// Get segment from slab.
 ///
 /// This will return whatever it finds at index
 /// regardless of whether it is occupied
-/// or released.
+/// or released, unless strict indexing is set.
 /// # Example
 /// ```
 /// use sliced::SlicedSlab;
@@ -316,13 +1625,15 @@ where
 /// assert_eq!(ss[1], [3, 2, 1]);
 /// ```
 /// # Panics 
-/// If `index` is out of range.
+/// If `index` is out of range, or the slot is
+/// open and strict indexing is set.
 impl<T> Index<usize> for SlicedSlab<T>
 where
     T: Copy + Clone,
 {
     type Output = [T];
     fn index(&self, index: usize) -> &Self::Output {
+        self.check_index(index);
         &self.slots[index]
     }
 }
@@ -331,7 +1642,7 @@ where
 ///
 /// This will return whatever it finds at index
 /// regardless of whether it is occupied
-/// or released.
+/// or released, unless strict indexing is set.
 /// # Example
 /// ```
 /// use sliced::SlicedSlab;
@@ -341,12 +1652,467 @@ where
 /// assert_eq!(ss[1], [4, 0, 6]);
 /// ```
 /// # Panics
-/// If `index` is out of range.
+/// If `index` is out of range, or the slot is
+/// open and strict indexing is set.
 impl<T> IndexMut<usize> for SlicedSlab<T>
 where
     T: Copy + Clone,
 {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.check_index(index);
+        self.touch(index);
         &mut self.slots[index]
     }
 }
+
+/// Iterator over the occupied slots of a `SlicedSlab`
+#[derive(Debug, Clone)]
+pub struct SlicedSlabIter<'a, T>
+where
+    T: Copy + Clone,
+{
+    slots: ChunksExact<'a, T>,
+    keys: OccupiedKeys<'a>,
+    next_key: usize,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for SlicedSlabIter<'a, T>
+where
+    T: Copy + Clone,
+{
+    type Item = (usize, &'a [T]);
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next()?;
+        // Skipping chunks is constant time
+        let segment = self.slots.nth(key - self.next_key)?;
+        self.next_key = key + 1;
+        self.remaining -= 1;
+        Some((key, segment))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for SlicedSlabIter<'a, T> where T: Copy + Clone {}
+
+/// Mutable iterator over the occupied slots of a `SlicedSlab`
+#[derive(Debug)]
+pub struct SlicedSlabIterMut<'a, T>
+where
+    T: Copy + Clone,
+{
+    slots: ChunksExactMut<'a, T>,
+    keys: OccupiedKeys<'a>,
+    next_key: usize,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for SlicedSlabIterMut<'a, T>
+where
+    T: Copy + Clone,
+{
+    type Item = (usize, &'a mut [T]);
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next()?;
+        // Skipping chunks is constant time
+        let segment = self.slots.nth(key - self.next_key)?;
+        self.next_key = key + 1;
+        self.remaining -= 1;
+        Some((key, segment))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for SlicedSlabIterMut<'a, T> where T: Copy + Clone {}
+
+/// Consume the slab and yield key, vector pairs of occupied slots.
+///
+/// # Example
+/// ```
+/// use sliced::SlicedSlab;
+/// let mut ss = SlicedSlab::from_vec(2, (0..6).collect());
+/// ss.release(1);
+/// let entries: Vec<_> = ss.into_iter().collect();
+/// assert_eq!(entries, vec![(0, vec![0, 1]), (2, vec![4, 5])]);
+/// ```
+impl<T> IntoIterator for SlicedSlab<T>
+where
+    T: Copy + Clone,
+{
+    type Item = (usize, Vec<T>);
+    type IntoIter = SlicedSlabIntoIter<T>;
+    fn into_iter(self) -> Self::IntoIter {
+        SlicedSlabIntoIter {
+            remaining: self.occupied_len(),
+            key: 0,
+            slots: self.slots,
+            occupied: self.occupied,
+        }
+    }
+}
+
+/// Owning iterator over the occupied slots of a `SlicedSlab`
+#[derive(Debug)]
+pub struct SlicedSlabIntoIter<T>
+where
+    T: Copy + Clone,
+{
+    slots: SlicedVec<T>,
+    occupied: Occupancy,
+    key: usize,
+    remaining: usize,
+}
+
+impl<T> Iterator for SlicedSlabIntoIter<T>
+where
+    T: Copy + Clone,
+{
+    type Item = (usize, Vec<T>);
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.occupied.next_occupied(self.key)?;
+        self.key = key + 1;
+        self.remaining -= 1;
+        Some((key, self.slots[key].to_vec()))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for SlicedSlabIntoIter<T> where T: Copy + Clone {}
+
+/// Draining iterator over the occupied slots of a `SlicedSlab`
+#[derive(Debug)]
+pub struct SlicedSlabDrain<'a, T>
+where
+    T: Copy + Clone,
+{
+    slab: &'a mut SlicedSlab<T>,
+    key: usize,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for SlicedSlabDrain<'a, T>
+where
+    T: Copy + Clone,
+{
+    type Item = (usize, Vec<T>);
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.slab.occupied.next_occupied(self.key)?;
+        self.key = key + 1;
+        self.remaining -= 1;
+        Some((key, self.slab.slots[key].to_vec()))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for SlicedSlabDrain<'a, T> where T: Copy + Clone {}
+
+impl<'a, T> Drop for SlicedSlabDrain<'a, T>
+where
+    T: Copy + Clone,
+{
+    fn drop(&mut self) {
+        self.slab.clear();
+    }
+}
+
+/// Iterator over the occupied segments of a `SlicedSlab`
+#[derive(Debug, Clone)]
+pub struct SlicedSlabValues<'a, T>
+where
+    T: Copy + Clone,
+{
+    iter: SlicedSlabIter<'a, T>,
+}
+
+impl<'a, T> Iterator for SlicedSlabValues<'a, T>
+where
+    T: Copy + Clone,
+{
+    type Item = &'a [T];
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(_, segment)| segment)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for SlicedSlabValues<'a, T> where T: Copy + Clone {}
+
+/// Error returned by `SlicedSlab::insert_at` when the key is
+/// already occupied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyOccupied;
+
+impl fmt::Display for KeyOccupied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "slab key is already occupied")
+    }
+}
+
+impl Error for KeyOccupied {}
+
+/// Occupancy and activity summary returned by `SlicedSlab::stats`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SlabStats {
+    /// Number of occupied slots
+    pub occupied: usize,
+    /// Number of open slots
+    pub open: usize,
+    /// Length of the longest run of consecutive occupied slots
+    pub largest_occupied_run: usize,
+    /// Number of slots pushed to or taken from the free list
+    pub free_list_ops: u64,
+    /// Number of times the storage was reallocated
+    pub reallocations: u64,
+    /// Bytes copied while compacting
+    pub bytes_moved: u64,
+}
+
+// Activity counters behind `SlabStats`
+#[derive(Debug, Clone, Copy, Default)]
+struct Counters {
+    free_list_ops: u64,
+    reallocations: u64,
+    bytes_moved: u64,
+}
+
+/// Handle to the state saved by `SlicedSlab::snapshot`.
+///
+/// The slab keeps the saved slots itself, so the
+/// handle only identifies the snapshot.
+#[derive(Debug, Clone)]
+pub struct SlabSnapshot {
+    id: u64,
+    len: usize,
+    occupied_len: usize,
+}
+
+impl SlabSnapshot {
+    /// Returns the number of slots, occupied or open.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Test if the snapshot has no slots.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Returns the number of occupied slots.
+    pub fn occupied_len(&self) -> usize {
+        self.occupied_len
+    }
+}
+
+// Slab state at the latest snapshot: the scalar fields, and the
+// link and segment of each slot as it was before its first change
+#[derive(Debug, Clone)]
+pub(crate) struct UndoLog<T>
+where
+    T: Copy + Clone,
+{
+    id: u64,
+    len: usize,
+    free_head: usize,
+    open_len: usize,
+    low_water: usize,
+    logged: HashSet<usize>,
+    // Saved links by key, in the same order as `saved`
+    links: Vec<(usize, Link)>,
+    pub(crate) saved: SlicedVec<T>,
+}
+
+impl<T> UndoLog<T>
+where
+    T: Copy + Clone,
+{
+    fn new(segment_len: usize) -> Self {
+        Self {
+            id: 0,
+            len: 0,
+            free_head: END,
+            open_len: 0,
+            low_water: 0,
+            logged: HashSet::new(),
+            links: Vec::new(),
+            saved: SlicedVec::new(segment_len),
+        }
+    }
+    // Forget the saved slots, keeping the allocations
+    fn clear(&mut self) {
+        self.logged.clear();
+        self.links.clear();
+        self.saved.clear();
+    }
+    // Save a slot unless it is past the end of the snapshot or
+    // already saved. Slots removed since the snapshot were saved
+    // when removed, so any other key is in bounds.
+    fn save(&mut self, key: usize, links: &[Link], slots: &SlicedVec<T>) {
+        if key < self.len && self.logged.insert(key) {
+            self.links.push((key, links[key]));
+            self.saved.push(&slots[key]);
+        }
+    }
+}
+
+/// Error returned by `SlicedSlab::try_insert` when there is
+/// no open slot and no spare capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlabFull;
+
+impl fmt::Display for SlabFull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "slab has no open slot or spare capacity")
+    }
+}
+
+impl Error for SlabFull {}
+
+// Sparsity threshold and remap callback for automatic compaction
+struct AutoCompact {
+    max_sparsity: f32,
+    on_remap: Box<dyn FnMut(usize, usize) + Send + Sync>,
+}
+
+impl fmt::Debug for AutoCompact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AutoCompact")
+            .field("max_sparsity", &self.max_sparsity)
+            .finish_non_exhaustive()
+    }
+}
+
+// Bitmap of occupied slots
+//
+// Bits at or beyond the number of slots are always zero.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Occupancy {
+    pub(crate) words: Vec<u64>,
+}
+
+impl Occupancy {
+    fn with_capacity(len: usize) -> Self {
+        Self {
+            words: Vec::with_capacity(len.div_ceil(64)),
+        }
+    }
+    fn full(len: usize) -> Self {
+        let mut occupancy = Self {
+            words: vec![u64::MAX; len.div_ceil(64)],
+        };
+        occupancy.truncate(len);
+        occupancy
+    }
+    fn capacity(&self) -> usize {
+        self.words.capacity() * 64
+    }
+    fn reserve(&mut self, len: usize) {
+        let words = len.div_ceil(64).saturating_sub(self.words.len());
+        self.words.reserve(words)
+    }
+    fn try_reserve(&mut self, len: usize) -> Result<(), TryReserveError> {
+        let words = len.div_ceil(64).saturating_sub(self.words.len());
+        self.words.try_reserve(words)
+    }
+    fn contains(&self, key: usize) -> bool {
+        self.words
+            .get(key / 64)
+            .is_some_and(|word| word >> (key % 64) & 1 == 1)
+    }
+    fn insert(&mut self, key: usize) {
+        if key / 64 >= self.words.len() {
+            self.words.resize(key / 64 + 1, 0);
+        }
+        self.words[key / 64] |= 1 << (key % 64);
+    }
+    fn remove(&mut self, key: usize) {
+        self.words[key / 64] &= !(1 << (key % 64));
+    }
+    fn truncate(&mut self, len: usize) {
+        self.words.truncate(len.div_ceil(64));
+        if !len.is_multiple_of(64) {
+            *self.words.last_mut().unwrap() &= (1 << (len % 64)) - 1;
+        }
+    }
+    // First occupied key at or after `from`
+    fn next_occupied(&self, from: usize) -> Option<usize> {
+        let mut index = from / 64;
+        let mut word = self.words.get(index)? & (u64::MAX << (from % 64));
+        while word == 0 {
+            index += 1;
+            word = *self.words.get(index)?;
+        }
+        Some(index * 64 + word.trailing_zeros() as usize)
+    }
+    // First vacant key at or after `from`, including keys past the end
+    fn next_vacant(&self, from: usize) -> usize {
+        let mut index = from / 64;
+        let mut word = match self.words.get(index) {
+            Some(word) => !word & (u64::MAX << (from % 64)),
+            None => return from,
+        };
+        while word == 0 {
+            index += 1;
+            word = match self.words.get(index) {
+                Some(word) => !word,
+                None => return index * 64,
+            };
+        }
+        index * 64 + word.trailing_zeros() as usize
+    }
+    // Last occupied key before `end`
+    fn prev_occupied(&self, end: usize) -> Option<usize> {
+        let highest = |index: usize, word: u64| index * 64 + 63 - word.leading_zeros() as usize;
+        let full = (end / 64).min(self.words.len());
+        if let Some(word) = self.words.get(full) {
+            let word = word & ((1 << (end % 64)) - 1);
+            if word != 0 {
+                return Some(highest(full, word));
+            }
+        }
+        let index = self.words[..full].iter().rposition(|word| *word != 0)?;
+        Some(highest(index, self.words[index]))
+    }
+    fn keys(&self) -> OccupiedKeys<'_> {
+        OccupiedKeys::new(&self.words)
+    }
+}
+
+// Iterator over the set bits of an `Occupancy`
+#[derive(Debug, Clone)]
+pub(crate) struct OccupiedKeys<'a> {
+    words: slice::Iter<'a, u64>,
+    current: u64,
+    // Key of bit zero of the word after `current`
+    base: usize,
+}
+
+impl<'a> OccupiedKeys<'a> {
+    pub(crate) fn new(words: &'a [u64]) -> Self {
+        Self {
+            words: words.iter(),
+            current: 0,
+            base: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for OccupiedKeys<'a> {
+    type Item = usize;
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.current == 0 {
+            self.current = *self.words.next()?;
+            self.base += 64;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+        Some(self.base - 64 + bit)
+    }
+}
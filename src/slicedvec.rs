@@ -1,10 +1,11 @@
 use std::{
+    collections::TryReserveError,
     ops::{Index, IndexMut, Range},
     ptr,
 };
 
 /// A segmented vector for iterating over slices of constant length.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SlicedVec<T>
 where
     T: Copy + Clone,
@@ -112,6 +113,31 @@ where
     pub fn shrink_to_fit(&mut self) {
         self.storage.shrink_to_fit()
     }
+    /// Reserve capacity for at least `additional` more segments.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::SlicedVec;
+    /// let mut sv = SlicedVec::<u8>::new(4);
+    /// sv.reserve(10);
+    /// assert!(sv.capacity() >= 10);
+    /// ```
+    /// # Panics
+    /// If the new capacity overflows `usize`.
+    pub fn reserve(&mut self, additional: usize) {
+        let additional = additional
+            .checked_mul(self.segment_len)
+            .expect("capacity overflow");
+        self.storage.reserve(additional)
+    }
+    /// Try to reserve capacity for at least `additional` more segments.
+    ///
+    /// Returns an error if the capacity overflows or the
+    /// allocator reports a failure.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.storage
+            .try_reserve(additional.saturating_mul(self.segment_len))
+    }
     /// Append the contents of another `SlicedVec`.
     ///
     /// Complexity is the length of `other`, plus any
@@ -133,6 +159,26 @@ where
         assert_eq!(other.segment_len, self.segment_len);
         self.storage.append(&mut other.storage)
     }
+    /// Append a copy of the contents of another `SlicedVec`.
+    ///
+    /// Unlike `append`, `other` is left intact.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sliced::{slicedvec, SlicedVec};
+    /// let mut a = slicedvec![[1, 2, 3], [4, 5, 6]];
+    /// let b = slicedvec![[7, 8, 9]];
+    /// a.extend_from_other(&b);
+    /// assert_eq!(a.len(), 3);
+    /// assert_eq!(b.len(), 1);
+    /// ```
+    ///  # Panics
+    /// If the segment size of `other` is different.
+    pub fn extend_from_other(&mut self, other: &Self) {
+        assert_eq!(other.segment_len, self.segment_len);
+        self.storage.extend_from_slice(&other.storage)
+    }
     /// Insert a slice at position `index`.
     ///
     /// Complexity is linear in `storage_len`.
@@ -229,27 +275,27 @@ where
     }
     /// Get a reference to the first segment.
     ///
-    /// Returns `None` if `index` is out of range.
+    /// Returns `None` if empty.
     pub fn first(&self) -> Option<&[T]> {
         self.get(0)
     }
     /// Get a mutable reference to the first segment.
     ///
-    /// Returns `None` if `index` is out of range.
+    /// Returns `None` if empty.
     pub fn first_mut(&mut self) -> Option<&mut [T]> {
         self.get_mut(0)
     }
     /// Get a reference to the last segment.
     ///
-    /// Returns `None` if `index` is out of range.
+    /// Returns `None` if empty.
     pub fn last(&self) -> Option<&[T]> {
-        self.get(self.last_index())
+        self.get(self.len().checked_sub(1)?)
     }
     /// Get a mutable reference to the last segment.
     ///
-    /// Returns `None` if `index` is out of range.
+    /// Returns `None` if empty.
     pub fn last_mut(&mut self) -> Option<&mut [T]> {
-        self.get_mut(self.last_index())
+        self.get_mut(self.len().checked_sub(1)?)
     }
     /// Remove and return a segment.
     ///
@@ -435,7 +481,7 @@ where
         )
     }
     pub(crate) fn is_valid_length(&self, data: &[T]) -> bool {
-        data.len() % self.segment_len == 0 && !data.is_empty()
+        data.len().is_multiple_of(self.segment_len) && !data.is_empty()
     }
 }
 
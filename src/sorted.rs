@@ -0,0 +1,247 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Index, Range},
+};
+
+use crate::slicedvec::SlicedVec;
+
+type CompareFn<T> = Box<dyn Fn(&[T], &[T]) -> Ordering>;
+
+/// A `SlicedVec` kept in sorted order.
+///
+/// Segments are ordered by a comparator and equal segments
+/// keep their insertion order, so this doubles as a sorted
+/// multimap of fixed-width keys. Lookups are binary searches
+/// and insertion shifts later segments in place.
+/// ```
+/// use sliced::*;
+/// let mut sorted = SortedSlicedVec::new(2);
+/// sorted.insert_sorted(&[3, 0]);
+/// sorted.insert_sorted(&[1, 5]);
+/// sorted.insert_sorted(&[2, 2]);
+/// sorted.insert_sorted(&[1, 2]);
+/// assert_eq!(sorted.iter().collect::<Vec<_>>(), [[1, 2], [1, 5], [2, 2], [3, 0]]);
+/// let between: Vec<_> = sorted.range(&[1, 3], &[3, 0]).collect();
+/// assert_eq!(between, [[1, 5], [2, 2]]);
+/// ```
+pub struct SortedSlicedVec<T>
+where
+    T: Copy + Clone,
+{
+    data: SlicedVec<T>,
+    compare: CompareFn<T>,
+}
+
+impl<T> SortedSlicedVec<T>
+where
+    T: Copy + Clone + Ord,
+{
+    /// Construct a vector ordering segments lexicographically.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn new(segment_len: usize) -> Self {
+        Self::new_by(segment_len, |a: &[T], b: &[T]| a.cmp(b))
+    }
+}
+
+impl<T> SortedSlicedVec<T>
+where
+    T: Copy + Clone,
+{
+    /// Construct a vector ordering segments by `compare`.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn new_by<F>(segment_len: usize, compare: F) -> Self
+    where
+        F: Fn(&[T], &[T]) -> Ordering + 'static,
+    {
+        Self {
+            data: SlicedVec::new(segment_len),
+            compare: Box::new(compare),
+        }
+    }
+    /// Construct a vector ordering segments by a key extracted with `f`.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut by_id = SortedSlicedVec::new_by_key(2, |s: &[u32]| s[0]);
+    /// by_id.insert_sorted(&[7, 100]);
+    /// by_id.insert_sorted(&[3, 200]);
+    /// by_id.insert_sorted(&[7, 50]);
+    /// assert_eq!(by_id.equal_range(&[7, 0]), 1..3);
+    /// assert_eq!(by_id[2], [7, 50]);
+    /// ```
+    /// # Panics
+    /// If `segment_len` is zero.
+    pub fn new_by_key<K, F>(segment_len: usize, f: F) -> Self
+    where
+        K: Ord,
+        F: Fn(&[T]) -> K + 'static,
+    {
+        Self::new_by(segment_len, move |a: &[T], b: &[T]| f(a).cmp(&f(b)))
+    }
+    /// Returns the number of segments.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+    /// Test if there are no segments.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.data.segment_len()
+    }
+    /// Insert a segment after any equal segments.
+    ///
+    /// Returns the index of the inserted segment.
+    /// # Panics
+    /// If the length of the slice does
+    /// not match the segments size of the vector.
+    pub fn insert_sorted(&mut self, segment: &[T]) -> usize {
+        assert_eq!(segment.len(), self.segment_len());
+        let index = self.upper_bound(segment);
+        if index == self.len() {
+            self.data.push(segment);
+        } else {
+            self.data.insert(index, segment);
+        }
+        index
+    }
+    /// Index of the first segment not less than `probe`.
+    pub fn lower_bound(&self, probe: &[T]) -> usize {
+        self.partition_point(|segment| (self.compare)(segment, probe) == Ordering::Less)
+    }
+    /// Index of the first segment greater than `probe`.
+    pub fn upper_bound(&self, probe: &[T]) -> usize {
+        self.partition_point(|segment| (self.compare)(segment, probe) != Ordering::Greater)
+    }
+    /// Indices of the segments equal to `probe`.
+    pub fn equal_range(&self, probe: &[T]) -> Range<usize> {
+        self.lower_bound(probe)..self.upper_bound(probe)
+    }
+    /// Test if a segment equal to `probe` is present.
+    pub fn contains(&self, probe: &[T]) -> bool {
+        !self.equal_range(probe).is_empty()
+    }
+    /// Iterate over segments not less than `lo` and less than `hi`.
+    pub fn range(&self, lo: &[T], hi: &[T]) -> impl Iterator<Item = &[T]> {
+        let begin = self.lower_bound(lo);
+        let end = self.lower_bound(hi).max(begin);
+        (begin..end).map(|index| &self.data[index])
+    }
+    /// Get a segment by position.
+    pub fn get(&self, index: usize) -> Option<&[T]> {
+        self.data.get(index)
+    }
+    /// Returns the least segment.
+    pub fn first(&self) -> Option<&[T]> {
+        self.data.first()
+    }
+    /// Returns the greatest segment.
+    pub fn last(&self) -> Option<&[T]> {
+        self.data.last()
+    }
+    /// Remove and return the segment at `index`, keeping the order.
+    ///
+    /// # Panics
+    /// If `index` is out of range.
+    pub fn remove(&mut self, index: usize) -> Vec<T> {
+        assert!(index < self.len());
+        let range = self.data.storage_range(index);
+        self.data.storage.drain(range).collect()
+    }
+    /// Merge the segments of `other` into this vector.
+    ///
+    /// Both vectors are assumed to be sorted by this vector's
+    /// comparator. The merge is linear and stable, with
+    /// segments of `self` placed before equal ones of `other`.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut a = SortedSlicedVec::new(1);
+    /// let mut b = SortedSlicedVec::new(1);
+    /// [1, 4, 6].iter().for_each(|n| { a.insert_sorted(&[*n]); });
+    /// [2, 4, 9].iter().for_each(|n| { b.insert_sorted(&[*n]); });
+    /// a.merge(&b);
+    /// assert_eq!(a.as_sliced_vec().iter_storage().copied().collect::<Vec<_>>(), [1, 2, 4, 4, 6, 9]);
+    /// ```
+    /// # Panics
+    /// If the segment lengths differ.
+    pub fn merge(&mut self, other: &Self) {
+        assert_eq!(self.segment_len(), other.segment_len());
+        let mut merged = SlicedVec::with_capacity(self.segment_len(), self.len() + other.len());
+        let mut left = self.data.iter().peekable();
+        let mut right = other.data.iter().peekable();
+        while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+            if (self.compare)(b, a) == Ordering::Less {
+                merged.push(right.next().unwrap());
+            } else {
+                merged.push(left.next().unwrap());
+            }
+        }
+        left.chain(right).for_each(|segment| merged.push(segment));
+        self.data = merged;
+    }
+    /// Iterate over segments in sorted order.
+    pub fn iter(&self) -> impl Iterator<Item = &[T]> {
+        self.data.iter()
+    }
+    /// Remove all segments.
+    pub fn clear(&mut self) {
+        self.data.clear()
+    }
+    /// Get the sorted segments as a `SlicedVec`.
+    pub fn as_sliced_vec(&self) -> &SlicedVec<T> {
+        &self.data
+    }
+    /// Convert into the sorted segments.
+    pub fn into_sliced_vec(self) -> SlicedVec<T> {
+        self.data
+    }
+    // Binary search for the first segment failing `pred`
+    fn partition_point<P>(&self, pred: P) -> usize
+    where
+        P: Fn(&[T]) -> bool,
+    {
+        let (mut lo, mut hi) = (0, self.len());
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if pred(&self.data[mid]) {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    }
+}
+
+/// Get a segment by position.
+///
+/// # Panics
+/// If `index` is out of range.
+impl<T> Index<usize> for SortedSlicedVec<T>
+where
+    T: Copy + Clone,
+{
+    type Output = [T];
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.data[index]
+    }
+}
+
+impl<T> fmt::Debug for SortedSlicedVec<T>
+where
+    T: Copy + Clone + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SortedSlicedVec")
+            .field("data", &self.data)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,258 @@
+use std::{
+    io::{self, Read, Write},
+    mem,
+};
+
+use crate::format::{as_bytes, as_bytes_mut, read_header, FormatError, Header, Pod, STREAM_MAGIC};
+
+// Default buffer size in bytes
+const BUFFER_BYTES: usize = 1 << 16;
+
+// Segments that fit in the default buffer, at least one, or
+// `None` if the size of one segment overflows
+fn default_segments<T>(segment_len: usize) -> Option<usize> {
+    let bytes = segment_len.checked_mul(mem::size_of::<T>())?;
+    Some((BUFFER_BYTES / bytes.max(1)).max(1))
+}
+
+/// Appends fixed length segments to a stream.
+///
+/// Segments are buffered and written in the binary stream
+/// format, a header followed by segments until the end of the
+/// stream, so the count need not be known in advance. Read the
+/// stream back with `SlicedReader`. Buffered segments are
+/// written when the writer is dropped, but errors are only
+/// reported by `flush` and `finish`.
+/// ```
+/// use sliced::*;
+/// let mut writer = SlicedWriter::new(Vec::new(), 3).unwrap();
+/// for n in 0..1000u32 {
+///     writer.push(&[n, n + 1, n + 2]).unwrap();
+/// }
+/// assert_eq!(writer.len(), 1000);
+/// let bytes = writer.finish().unwrap();
+/// let mut reader = SlicedReader::<u32, _>::new(bytes.as_slice()).unwrap();
+/// assert_eq!(reader.next_segment().unwrap(), Some([0, 1, 2].as_slice()));
+/// let rest: Vec<Vec<u32>> = reader.collect::<Result<_, _>>().unwrap();
+/// assert_eq!(rest.len(), 999);
+/// assert_eq!(rest[998], [999, 1000, 1001]);
+/// ```
+#[derive(Debug)]
+pub struct SlicedWriter<T, W>
+where
+    T: Copy + Clone + Pod,
+    W: Write,
+{
+    // Taken by `finish`
+    writer: Option<W>,
+    buffer: Vec<T>,
+    segment_len: usize,
+    len: usize,
+}
+
+impl<T, W> SlicedWriter<T, W>
+where
+    T: Copy + Clone + Pod,
+    W: Write,
+{
+    /// Write the stream header and return a writer.
+    ///
+    /// # Panics
+    /// If `segment_len` is zero or a segment is too large to buffer.
+    pub fn new(writer: W, segment_len: usize) -> io::Result<Self> {
+        let size = default_segments::<T>(segment_len).expect("segment size overflow");
+        Self::with_capacity(writer, segment_len, size)
+    }
+    /// Write the stream header and return a writer
+    /// buffering up to `size` segments.
+    ///
+    /// # Panics
+    /// If `segment_len` or `size` is zero, or the
+    /// buffer size overflows.
+    pub fn with_capacity(mut writer: W, segment_len: usize, size: usize) -> io::Result<Self> {
+        assert_ne!(segment_len, 0);
+        assert_ne!(size, 0);
+        let capacity = segment_len.checked_mul(size).expect("buffer size overflow");
+        let header = Header::new::<T>(STREAM_MAGIC, [segment_len as u64, 0]);
+        writer.write_all(&header.to_bytes())?;
+        Ok(Self {
+            writer: Some(writer),
+            buffer: Vec::with_capacity(capacity),
+            segment_len,
+            len: 0,
+        })
+    }
+    /// Returns the number of segments pushed.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Test if no segments were pushed.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.segment_len
+    }
+    /// Append one or more segments.
+    ///
+    /// # Panics
+    /// If the length of the slice is not
+    /// a multiple of the segment length.
+    pub fn push(&mut self, segment: &[T]) -> io::Result<()> {
+        assert_eq!(segment.len() % self.segment_len, 0);
+        if self.buffer.len() + segment.len() > self.buffer.capacity() {
+            self.write_buffer()?;
+        }
+        if segment.len() > self.buffer.capacity() {
+            self.writer.as_mut().unwrap().write_all(as_bytes(segment))?;
+        } else {
+            self.buffer.extend_from_slice(segment);
+        }
+        self.len += segment.len() / self.segment_len;
+        Ok(())
+    }
+    /// Write buffered segments and flush the stream.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.write_buffer()?;
+        self.writer.as_mut().unwrap().flush()
+    }
+    /// Flush and return the underlying stream.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.flush()?;
+        Ok(self.writer.take().unwrap())
+    }
+    // Write out and clear the buffer
+    fn write_buffer(&mut self) -> io::Result<()> {
+        self.writer
+            .as_mut()
+            .unwrap()
+            .write_all(as_bytes(&self.buffer))?;
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+impl<T, W> Drop for SlicedWriter<T, W>
+where
+    T: Copy + Clone + Pod,
+    W: Write,
+{
+    fn drop(&mut self) {
+        if self.writer.is_some() {
+            let _ = self.write_buffer();
+        }
+    }
+}
+
+/// Reads fixed length segments from a stream.
+///
+/// The counterpart of `SlicedWriter`. Segments are read in
+/// blocks into an internal buffer and handed out one at a
+/// time, either borrowed from the buffer by `next_segment`
+/// or copied by the `Iterator` implementation, so a stream
+/// of any length is read in constant memory.
+#[derive(Debug)]
+pub struct SlicedReader<T, R>
+where
+    T: Copy + Clone + Pod,
+    R: Read,
+{
+    reader: R,
+    buffer: Vec<T>,
+    segment_len: usize,
+    // Byte positions of the next segment and the end of data
+    consumed: usize,
+    filled: usize,
+}
+
+impl<T, R> SlicedReader<T, R>
+where
+    T: Copy + Clone + Pod,
+    R: Read,
+{
+    /// Read and check the stream header.
+    ///
+    /// A malformed header, including a segment length too
+    /// large to buffer, is reported as `InvalidData`
+    /// wrapping a `FormatError`.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let header = read_header::<T, _>(&mut reader, STREAM_MAGIC)?;
+        let segment_len = usize::try_from(header.params[0]).map_err(|_| FormatError::Invalid)?;
+        if segment_len == 0 {
+            return Err(FormatError::Invalid.into());
+        }
+        // The segment length is untrusted, so size the
+        // buffer with checked arithmetic and allocation
+        let len = default_segments::<T>(segment_len)
+            .and_then(|size| segment_len.checked_mul(size))
+            .ok_or(FormatError::Invalid)?;
+        let mut buffer = Vec::new();
+        buffer
+            .try_reserve_exact(len)
+            .map_err(|_| FormatError::Invalid)?;
+        // Safety: any bit pattern, including zero, is a valid Pod
+        buffer.resize(len, unsafe { mem::zeroed() });
+        Ok(Self {
+            reader,
+            buffer,
+            segment_len,
+            consumed: 0,
+            filled: 0,
+        })
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.segment_len
+    }
+    /// Read the next segment into the internal buffer.
+    ///
+    /// Returns `None` at the end of the stream. A stream ending
+    /// inside a segment is reported as `UnexpectedEof`.
+    pub fn next_segment(&mut self) -> io::Result<Option<&[T]>> {
+        let segment_bytes = self.segment_len * mem::size_of::<T>();
+        if self.filled - self.consumed < segment_bytes && !self.fill(segment_bytes)? {
+            return Ok(None);
+        }
+        let begin = self.consumed / mem::size_of::<T>();
+        self.consumed += segment_bytes;
+        Ok(Some(&self.buffer[begin..begin + self.segment_len]))
+    }
+    /// Return the underlying stream.
+    ///
+    /// Data read into the buffer but not yet returned is lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+    // Read until a whole segment is buffered or the stream ends
+    fn fill(&mut self, segment_bytes: usize) -> io::Result<bool> {
+        let bytes = as_bytes_mut(&mut self.buffer);
+        bytes.copy_within(self.consumed..self.filled, 0);
+        self.filled -= self.consumed;
+        self.consumed = 0;
+        while self.filled < segment_bytes {
+            match self.reader.read(&mut bytes[self.filled..]) {
+                Ok(0) if self.filled == 0 => return Ok(false),
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(n) => self.filled += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Iterate over copies of the remaining segments.
+impl<T, R> Iterator for SlicedReader<T, R>
+where
+    T: Copy + Clone + Pod,
+    R: Read,
+{
+    type Item = io::Result<Vec<T>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_segment()
+            .map(|segment| segment.map(<[T]>::to_vec))
+            .transpose()
+    }
+}
@@ -0,0 +1,158 @@
+use std::{fmt, ops::Index, str};
+
+use crate::varslicedvec::VarSlicedVec;
+
+/// A variable length segmented vector of strings.
+///
+/// All strings share a single byte buffer and each segment is
+/// guaranteed to be valid UTF-8, so segments are returned as
+/// `&str` without further checks.
+/// ```
+/// use sliced::*;
+/// let mut vs = VarSlicedStrings::new();
+/// vs.push_str("alpha");
+/// vs.push_str("");
+/// vs.push_str("γάμμα");
+/// assert_eq!(vs.len(), 3);
+/// assert_eq!(vs.get_str(2), Some("γάμμα"));
+/// assert_eq!(&vs[0], "alpha");
+/// assert_eq!(vs.iter().collect::<Vec<_>>(), ["alpha", "", "γάμμα"]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct VarSlicedStrings {
+    bytes: VarSlicedVec<u8>,
+}
+
+impl VarSlicedStrings {
+    /// Initialize a `VarSlicedStrings`.
+    pub fn new() -> Self {
+        Self {
+            bytes: VarSlicedVec::new(),
+        }
+    }
+    /// Initialize a `VarSlicedStrings` and set the byte capacity.
+    pub fn with_capacity(size: usize) -> Self {
+        Self {
+            bytes: VarSlicedVec::with_capacity(size),
+        }
+    }
+    /// Add a string to the end.
+    pub fn push_str(&mut self, string: &str) {
+        self.bytes.push(string.as_bytes())
+    }
+    /// Get a string.
+    ///
+    /// Returns `None` if `index` is out of range.
+    pub fn get_str(&self, index: usize) -> Option<&str> {
+        // Safety: segments are only ever added from valid strings
+        Some(unsafe { str::from_utf8_unchecked(self.bytes.get(index)?) })
+    }
+    /// Remove and return the last string.
+    ///
+    /// Returns `None` if empty.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vs: VarSlicedStrings = ["a", "bc"].into_iter().collect();
+    /// assert_eq!(vs.pop(), Some("bc".to_string()));
+    /// assert_eq!(vs.len(), 1);
+    /// ```
+    pub fn pop(&mut self) -> Option<String> {
+        // Safety: segments are only ever added from valid strings
+        Some(unsafe { String::from_utf8_unchecked(self.bytes.pop()?) })
+    }
+    /// Remove and return a string, shifting later strings down.
+    ///
+    /// # Panics
+    /// If `index` is out of range.
+    pub fn remove(&mut self, index: usize) -> String {
+        // Safety: segments are only ever added from valid strings
+        unsafe { String::from_utf8_unchecked(self.bytes.remove(index)) }
+    }
+    /// Return iterator over strings.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &str> + ExactSizeIterator {
+        // Safety: segments are only ever added from valid strings
+        self.bytes
+            .as_slice()
+            .iter()
+            .map(|bytes| unsafe { str::from_utf8_unchecked(bytes) })
+    }
+    /// Returns the number of strings.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+    /// Test if there are no strings.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+    /// Returns the total number of bytes of string data.
+    pub fn storage_len(&self) -> usize {
+        *self.bytes.offsets().last().unwrap()
+    }
+    /// Clear the contents.
+    pub fn clear(&mut self) {
+        self.bytes.clear()
+    }
+    /// Shrink the storage to fit data.
+    pub fn shrink_to_fit(&mut self) {
+        self.bytes.shrink_to_fit()
+    }
+    /// Get a reference to the underlying bytes.
+    pub fn as_bytes(&self) -> &VarSlicedVec<u8> {
+        &self.bytes
+    }
+    /// Convert into the underlying bytes.
+    pub fn into_bytes(self) -> VarSlicedVec<u8> {
+        self.bytes
+    }
+}
+
+impl TryFrom<VarSlicedVec<u8>> for VarSlicedStrings {
+    type Error = str::Utf8Error;
+    /// Validate that every segment is UTF-8.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[104, 105], [0xff]];
+    /// assert!(VarSlicedStrings::try_from(vv).is_err());
+    /// let vs = VarSlicedStrings::try_from(varslicedvec![[104, 105]]).unwrap();
+    /// assert_eq!(&vs[0], "hi");
+    /// ```
+    fn try_from(bytes: VarSlicedVec<u8>) -> Result<Self, Self::Error> {
+        for segment in bytes.iter() {
+            str::from_utf8(segment)?;
+        }
+        Ok(Self { bytes })
+    }
+}
+
+impl Index<usize> for VarSlicedStrings {
+    type Output = str;
+    fn index(&self, index: usize) -> &Self::Output {
+        // Safety: segments are only ever added from valid strings
+        unsafe { str::from_utf8_unchecked(&self.bytes[index]) }
+    }
+}
+
+impl fmt::Debug for VarSlicedStrings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<S: AsRef<str>> Extend<S> for VarSlicedStrings {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        iter.into_iter()
+            .for_each(|string| self.push_str(string.as_ref()))
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for VarSlicedStrings {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut strings = Self::new();
+        strings.extend(iter);
+        strings
+    }
+}
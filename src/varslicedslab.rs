@@ -0,0 +1,254 @@
+use std::{
+    collections::BTreeMap,
+    ops::{Index, IndexMut, Range},
+};
+
+use crate::slicedslab::SlicedSlab;
+
+/// A slab of variable length segments with stable keys.
+///
+/// Segments live in a single storage vector. Released extents
+/// are kept in a free map, coalesced with their neighbors, and
+/// reused first-fit by later insertions. When more than half
+/// of the storage is free the live segments are moved to the
+/// front; their keys are unaffected.
+/// ```
+/// use sliced::*;
+/// let mut vs = VarSlicedSlab::new();
+/// let a = vs.insert(&[1, 2, 3]);
+/// let b = vs.insert(&[4]);
+/// let c = vs.insert(&[5, 6]);
+/// vs.release(a);
+/// let d = vs.insert(&[7, 8]);
+/// assert_eq!(d, a);
+/// assert_eq!(vs[b], [4]);
+/// assert_eq!(vs[c], [5, 6]);
+/// assert_eq!(vs[d], [7, 8]);
+/// assert_eq!(vs.storage_len(), 6);
+/// ```
+#[derive(Debug, Clone)]
+pub struct VarSlicedSlab<T>
+where
+    T: Copy + Clone,
+{
+    storage: Vec<T>,
+    // Key to [begin, end) of each segment
+    extents: SlicedSlab<usize>,
+    // Begin to end of each free extent
+    free: BTreeMap<usize, usize>,
+    free_len: usize,
+}
+
+impl<T> VarSlicedSlab<T>
+where
+    T: Copy + Clone,
+{
+    /// Initialize a `VarSlicedSlab`.
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+    /// Initialize a `VarSlicedSlab` and set the storage capacity.
+    pub fn with_capacity(size: usize) -> Self {
+        Self {
+            storage: Vec::with_capacity(size),
+            extents: SlicedSlab::new(2),
+            free: BTreeMap::new(),
+            free_len: 0,
+        }
+    }
+    /// Returns the number of occupied keys.
+    pub fn len(&self) -> usize {
+        self.extents.occupied_len()
+    }
+    /// Test if there are no occupied keys.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the length of the storage, including free extents.
+    pub fn storage_len(&self) -> usize {
+        self.storage.len()
+    }
+    /// Returns the number of elements in free extents.
+    pub fn free_len(&self) -> usize {
+        self.free_len
+    }
+    /// Insert a segment and return its key.
+    ///
+    /// The first free extent that is large enough is used.
+    /// Otherwise the segment is appended to the storage.
+    pub fn insert(&mut self, segment: &[T]) -> usize {
+        let begin = self.allocate(segment.len());
+        let end = begin + segment.len();
+        if end > self.storage.len() {
+            debug_assert_eq!(begin, self.storage.len());
+            self.storage.extend_from_slice(segment);
+        } else {
+            self.storage[begin..end].copy_from_slice(segment);
+        }
+        self.extents.insert(&[begin, end])
+    }
+    /// Release the segment referred to by `key`.
+    ///
+    /// # Panics
+    /// If `key` is out of range or already released.
+    pub fn release(&mut self, key: usize) {
+        let range = self.range(key).expect("key is not occupied");
+        self.extents.release(key);
+        self.deallocate(range);
+        if self.free_len > self.storage.len() / 2 {
+            self.defragment();
+        }
+    }
+    /// Test if `key` refers to an occupied slot.
+    pub fn contains_key(&self, key: usize) -> bool {
+        self.extents.contains_key(key)
+    }
+    /// Get a reference to a segment.
+    ///
+    /// Returns `None` if `key` is out of range or released.
+    pub fn get(&self, key: usize) -> Option<&[T]> {
+        Some(&self.storage[self.range(key)?])
+    }
+    /// Get a mutable reference to a segment.
+    ///
+    /// Returns `None` if `key` is out of range or released.
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut [T]> {
+        let range = self.range(key)?;
+        Some(&mut self.storage[range])
+    }
+    /// Iterate over key, slice pairs of occupied slots.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (usize, &[T])> {
+        self.extents
+            .iter()
+            .map(|(key, extent)| (key, &self.storage[extent[0]..extent[1]]))
+    }
+    /// Move all segments to the front of the storage.
+    ///
+    /// Keys are unchanged and the free map is emptied. This
+    /// runs automatically when more than half of the storage
+    /// is free.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vs = VarSlicedSlab::new();
+    /// let keys: Vec<_> = (0..4).map(|n| vs.insert(&[n; 3])).collect();
+    /// vs.release(keys[1]);
+    /// assert_eq!(vs.free_len(), 3);
+    /// vs.defragment();
+    /// assert_eq!(vs.free_len(), 0);
+    /// assert_eq!(vs.storage_len(), 9);
+    /// assert_eq!(vs[keys[3]], [3, 3, 3]);
+    /// ```
+    pub fn defragment(&mut self) {
+        let mut order: Vec<(usize, usize)> = self
+            .extents
+            .iter()
+            .map(|(key, extent)| (extent[0], key))
+            .collect();
+        order.sort_unstable();
+        let mut write = 0;
+        for (begin, key) in order {
+            let extent = &mut self.extents[key];
+            let len = extent[1] - begin;
+            self.storage.copy_within(begin..begin + len, write);
+            extent.copy_from_slice(&[write, write + len]);
+            write += len;
+        }
+        self.storage.truncate(write);
+        self.free.clear();
+        self.free_len = 0;
+    }
+    /// Clear the contents.
+    pub fn clear(&mut self) {
+        self.storage.clear();
+        self.extents.clear();
+        self.free.clear();
+        self.free_len = 0;
+    }
+    // Look up the storage range of an occupied key
+    fn range(&self, key: usize) -> Option<Range<usize>> {
+        let extent = self.extents.get(key)?;
+        Some(extent[0]..extent[1])
+    }
+    // Find room for `len` elements, first fit
+    fn allocate(&mut self, len: usize) -> usize {
+        if len == 0 {
+            return 0;
+        }
+        let fit = self
+            .free
+            .iter()
+            .find(|(begin, end)| *end - *begin >= len)
+            .map(|(begin, end)| (*begin, *end));
+        match fit {
+            Some((begin, end)) => {
+                self.free.remove(&begin);
+                if end - begin > len {
+                    self.free.insert(begin + len, end);
+                }
+                self.free_len -= len;
+                begin
+            }
+            None => self.storage.len(),
+        }
+    }
+    // Return a range to the free map, merging with neighbors
+    fn deallocate(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        self.free_len += range.len();
+        let (mut begin, mut end) = (range.start, range.end);
+        if let Some(next_end) = self.free.remove(&end) {
+            end = next_end;
+        }
+        if let Some((&prev_begin, &prev_end)) = self.free.range(..begin).next_back() {
+            if prev_end == begin {
+                self.free.remove(&prev_begin);
+                begin = prev_begin;
+            }
+        }
+        if end == self.storage.len() {
+            self.storage.truncate(begin);
+            self.free_len -= end - begin;
+        } else {
+            self.free.insert(begin, end);
+        }
+    }
+}
+
+impl<T> Default for VarSlicedSlab<T>
+where
+    T: Copy + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Get segment from slab.
+///
+/// # Panics
+/// If `key` is out of range or released.
+impl<T> Index<usize> for VarSlicedSlab<T>
+where
+    T: Copy + Clone,
+{
+    type Output = [T];
+    fn index(&self, key: usize) -> &Self::Output {
+        self.get(key).expect("key is not occupied")
+    }
+}
+
+/// Get mutable segment from slab.
+///
+/// # Panics
+/// If `key` is out of range or released.
+impl<T> IndexMut<usize> for VarSlicedSlab<T>
+where
+    T: Copy + Clone,
+{
+    fn index_mut(&mut self, key: usize) -> &mut Self::Output {
+        self.get_mut(key).expect("key is not occupied")
+    }
+}
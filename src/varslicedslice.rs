@@ -0,0 +1,378 @@
+use std::ops::{Index, IndexMut, Range};
+
+use crate::{offset::Offset, varslicedvec::VarSlicedVec};
+
+/// A borrowed view over a range of variable length segments.
+///
+/// The view holds the storage and the extents of the segments
+/// it covers. Extents are positions in `storage`, so views
+/// produced by `split_at` share the storage of their parent.
+#[derive(Debug)]
+pub struct VarSlicedSlice<'a, T, O = usize>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    pub(crate) storage: &'a [T],
+    pub(crate) extents: &'a [O],
+}
+
+impl<'a, T, O> VarSlicedSlice<'a, T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    /// Initialize a view from storage and offsets.
+    ///
+    /// Segment `i` spans `storage[offsets[i]..offsets[i + 1]]`.
+    /// Unlike `VarSlicedVec::from_parts`, the first offset need
+    /// not be zero and the last need not be the storage length.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let data = [1, 2, 3, 4, 5, 6];
+    /// let offsets = [1usize, 3, 6];
+    /// let vs = VarSlicedSlice::from_parts(&data, &offsets);
+    /// assert_eq!(vs.len(), 2);
+    /// assert_eq!(vs[0], [2, 3]);
+    /// ```
+    /// # Panics
+    /// If `offsets` is empty, decreases, or exceeds
+    /// the length of `storage`.
+    pub fn from_parts(storage: &'a [T], offsets: &'a [O]) -> Self {
+        assert!(!offsets.is_empty());
+        assert!(offsets.windows(2).all(|x| x[0] <= x[1]));
+        assert!(offsets.last().unwrap().to_usize() <= storage.len());
+        Self {
+            storage,
+            extents: offsets,
+        }
+    }
+    /// Returns the number of segments in the view.
+    pub fn len(&self) -> usize {
+        self.extents.len() - 1
+    }
+    /// Test if the view has no segments.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Get a reference to a segment.
+    ///
+    /// Returns `None` if `index` is out of range.
+    pub fn get(&self, index: usize) -> Option<&'a [T]> {
+        if index < self.len() {
+            Some(&self.storage[self.storage_range(index)])
+        } else {
+            None
+        }
+    }
+    /// Get the segment length at `index`.
+    ///
+    /// Returns zero if `index` is out of range.
+    pub fn segment_len(&self, index: usize) -> usize {
+        if index < self.len() {
+            self.storage_range(index).len()
+        } else {
+            0
+        }
+    }
+    /// Split the view into two at a segment boundary.
+    ///
+    /// The first view holds segments `0..mid` and the
+    /// second holds segments `mid..len()`.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1], [2, 3], [4, 5, 6]];
+    /// let (a, b) = vv.as_slice().split_at(1);
+    /// assert_eq!(a.len(), 1);
+    /// assert_eq!(b.len(), 2);
+    /// assert_eq!(b[0], [2, 3]);
+    /// let (c, d) = b.split_at(2);
+    /// assert_eq!(c[1], [4, 5, 6]);
+    /// assert!(d.is_empty());
+    /// ```
+    /// # Panics
+    /// If `mid` is greater than the number of segments.
+    pub fn split_at(&self, mid: usize) -> (Self, Self) {
+        assert!(mid <= self.len());
+        (
+            Self {
+                storage: self.storage,
+                extents: &self.extents[..=mid],
+            },
+            Self {
+                storage: self.storage,
+                extents: &self.extents[mid..],
+            },
+        )
+    }
+    /// Return iterator over slices.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1], [2, 3], [4, 5, 6]];
+    /// let lens = vv.as_slice().iter().map(|x| x.len()).collect::<Vec<_>>();
+    /// assert_eq!(lens, vec![1, 2, 3]);
+    /// ```
+    pub fn iter(&self) -> VarSlicedSliceIter<'a, T, O> {
+        VarSlicedSliceIter {
+            storage: self.storage,
+            extents: self.extents,
+        }
+    }
+    fn storage_range(&self, index: usize) -> Range<usize> {
+        self.extents[index].to_usize()..self.extents[index + 1].to_usize()
+    }
+}
+
+impl<'a, T, O> Clone for VarSlicedSlice<'a, T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, O> Copy for VarSlicedSlice<'a, T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+}
+
+impl<'a, T, O> Index<usize> for VarSlicedSlice<'a, T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    type Output = [T];
+    fn index(&self, index: usize) -> &Self::Output {
+        assert!(index < self.len());
+        &self.storage[self.storage_range(index)]
+    }
+}
+
+impl<'a, T, O> From<&'a VarSlicedVec<T, O>> for VarSlicedSlice<'a, T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    fn from(vv: &'a VarSlicedVec<T, O>) -> Self {
+        vv.as_slice()
+    }
+}
+
+/// Double-ended iterator over the segments of a `VarSlicedSlice`
+#[derive(Debug, Clone)]
+pub struct VarSlicedSliceIter<'a, T, O = usize>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    storage: &'a [T],
+    extents: &'a [O],
+}
+
+impl<'a, T, O> Iterator for VarSlicedSliceIter<'a, T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    type Item = &'a [T];
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.extents {
+            [begin, end, ..] => {
+                let segment = &self.storage[begin.to_usize()..end.to_usize()];
+                self.extents = &self.extents[1..];
+                Some(segment)
+            }
+            _ => None,
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T, O> DoubleEndedIterator for VarSlicedSliceIter<'a, T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self.extents {
+            [.., begin, end] => {
+                let segment = &self.storage[begin.to_usize()..end.to_usize()];
+                self.extents = &self.extents[..self.extents.len() - 1];
+                Some(segment)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'a, T, O> ExactSizeIterator for VarSlicedSliceIter<'a, T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    fn len(&self) -> usize {
+        self.extents.len().saturating_sub(1)
+    }
+}
+
+/// A mutable borrowed view over a range of variable length segments.
+///
+/// `storage` covers only the segments in the view and begins
+/// at position `base` of the parent storage, so two views from
+/// `split_at_mut` never alias and may be sent to different threads.
+#[derive(Debug)]
+pub struct VarSlicedSliceMut<'a, T, O = usize>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    pub(crate) storage: &'a mut [T],
+    pub(crate) extents: &'a [O],
+    pub(crate) base: usize,
+}
+
+impl<'a, T, O> VarSlicedSliceMut<'a, T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    /// Returns the number of segments in the view.
+    pub fn len(&self) -> usize {
+        self.extents.len() - 1
+    }
+    /// Test if the view has no segments.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Get a reference to a segment.
+    ///
+    /// Returns `None` if `index` is out of range.
+    pub fn get(&self, index: usize) -> Option<&[T]> {
+        if index < self.len() {
+            Some(&self.storage[self.storage_range(index)])
+        } else {
+            None
+        }
+    }
+    /// Get a mutable reference to a segment.
+    ///
+    /// Returns `None` if `index` is out of range.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut [T]> {
+        if index < self.len() {
+            let range = self.storage_range(index);
+            Some(&mut self.storage[range])
+        } else {
+            None
+        }
+    }
+    /// Split the view into two at a segment boundary.
+    ///
+    /// # Panics
+    /// If `mid` is greater than the number of segments.
+    pub fn split_at_mut(self, mid: usize) -> (Self, Self) {
+        assert!(mid <= self.len());
+        let at = self.extents[mid].to_usize() - self.base;
+        let (left, right) = self.storage.split_at_mut(at);
+        (
+            Self {
+                storage: left,
+                extents: &self.extents[..=mid],
+                base: self.base,
+            },
+            Self {
+                storage: right,
+                extents: &self.extents[mid..],
+                base: self.base + at,
+            },
+        )
+    }
+    /// Return iterator over mutable slices.
+    pub fn iter_mut(&mut self) -> VarSlicedSliceIterMut<'_, T, O> {
+        VarSlicedSliceIterMut {
+            storage: &mut self.storage[..],
+            extents: self.extents,
+        }
+    }
+    fn storage_range(&self, index: usize) -> Range<usize> {
+        self.extents[index].to_usize() - self.base..self.extents[index + 1].to_usize() - self.base
+    }
+}
+
+impl<'a, T, O> Index<usize> for VarSlicedSliceMut<'a, T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    type Output = [T];
+    fn index(&self, index: usize) -> &Self::Output {
+        assert!(index < self.len());
+        &self.storage[self.storage_range(index)]
+    }
+}
+
+impl<'a, T, O> IndexMut<usize> for VarSlicedSliceMut<'a, T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        assert!(index < self.len());
+        let range = self.storage_range(index);
+        &mut self.storage[range]
+    }
+}
+
+/// Iterator over the mutable segments of a `VarSlicedSliceMut`
+#[derive(Debug)]
+pub struct VarSlicedSliceIterMut<'a, T, O = usize>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    storage: &'a mut [T],
+    extents: &'a [O],
+}
+
+impl<'a, T, O> Iterator for VarSlicedSliceIterMut<'a, T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    type Item = &'a mut [T];
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.extents {
+            [begin, end, ..] => {
+                let len = end.to_usize() - begin.to_usize();
+                let (segment, rest) = std::mem::take(&mut self.storage).split_at_mut(len);
+                self.storage = rest;
+                self.extents = &self.extents[1..];
+                Some(segment)
+            }
+            _ => None,
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.extents.len().saturating_sub(1);
+        (len, Some(len))
+    }
+}
+
+impl<'a, T, O> ExactSizeIterator for VarSlicedSliceIterMut<'a, T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+}
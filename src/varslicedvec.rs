@@ -1,13 +1,36 @@
-use std::ops::{Index, IndexMut, Range};
+use std::{
+    borrow::Borrow,
+    fmt::{self, Debug},
+    ops::{Index, IndexMut, Range},
+};
+
+use crate::{
+    offset::Offset, positionindex::PositionIndex, slicedvec::SlicedVec,
+    varslicedslice::{VarSlicedSlice, VarSlicedSliceMut},
+};
+
+// Number of segments shown before eliding the rest
+const DEBUG_SEGMENTS: usize = 32;
 
 /// A segmented vector with variable length segments.
-#[derive(Debug)]
-pub struct VarSlicedVec<T>
+///
+/// Segment extents are stored as `O`, which defaults to `usize`.
+/// A narrower type such as `u32` halves the size of the extents
+/// when the total storage length is known to fit.
+/// ```
+/// use sliced::*;
+/// let mut vv = VarSlicedVec::<u8, u32>::default();
+/// vv.push(&[1, 2, 3]);
+/// assert_eq!(vv.offsets(), &[0u32, 3]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct VarSlicedVec<T, O = usize>
 where
     T: Copy + Clone,
+    O: Offset,
 {
-    storage: Vec<T>,
-    extents: Vec<usize>,
+    pub(crate) storage: Vec<T>,
+    pub(crate) extents: Vec<O>,
 }
 
 impl<T> VarSlicedVec<T>
@@ -44,6 +67,80 @@ where
             extents: vec![0],
         }
     }
+}
+
+impl<T, O> VarSlicedVec<T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    /// Initialize a `VarSlicedVec` from storage and offsets.
+    ///
+    /// Segment `i` spans `storage[offsets[i]..offsets[i + 1]]`,
+    /// which is the compressed sparse row layout. Neither
+    /// vector is copied.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv: VarSlicedVec<i32> = VarSlicedVec::from_parts((1..=6).collect(), vec![0, 1, 3, 6]);
+    /// assert_eq!(vv.lengths(), vec![1, 2, 3]);
+    /// assert_eq!(vv[2], [4, 5, 6]);
+    /// ```
+    /// # Panics
+    /// If `offsets` is empty, does not start at zero,
+    /// decreases, or does not end at the length of `storage`.
+    pub fn from_parts(storage: Vec<T>, offsets: Vec<O>) -> Self {
+        let vv = Self {
+            storage,
+            extents: offsets,
+        };
+        assert!(vv.check_invariants());
+        vv
+    }
+    /// Initialize a `VarSlicedVec` by grouping runs of `data`.
+    ///
+    /// A new segment starts wherever `pred` returns `false` for
+    /// a pair of neighbouring elements, as in `slice::chunk_by`.
+    /// Only the extents are recorded; `data` is not moved.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv: VarSlicedVec<i32> = VarSlicedVec::from_chunks_by(vec![1, 1, 2, 3, 3, 3], |a, b| a == b);
+    /// assert_eq!(vv.lengths(), vec![2, 1, 3]);
+    /// assert_eq!(vv[2], [3, 3, 3]);
+    /// ```
+    pub fn from_chunks_by<F>(data: Vec<T>, pred: F) -> Self
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        let mut extents = vec![O::from_usize(0)];
+        let mut end = 0;
+        extents.extend(data.chunk_by(pred).map(|chunk| {
+            end += chunk.len();
+            O::from_usize(end)
+        }));
+        Self {
+            storage: data,
+            extents,
+        }
+    }
+    /// Decompose into storage and offsets.
+    ///
+    /// This is the inverse of `from_parts`.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1], [2, 3]];
+    /// let (storage, offsets) = vv.into_parts();
+    /// assert_eq!(storage, vec![1, 2, 3]);
+    /// assert_eq!(offsets, vec![0, 1, 3]);
+    /// ```
+    pub fn into_parts(self) -> (Vec<T>, Vec<O>) {
+        (self.storage, self.extents)
+    }
     /// Append the contents of another `VarSlicedVec`.
     ///
     /// `other` is drained after call.
@@ -64,15 +161,69 @@ where
     /// assert_eq!(a.lengths(), vec![2, 1, 3, 1, 2, 3]);
     /// ```
     pub fn append(&mut self, other: &mut Self) {
-        other
-            .lengths()
-            .into_iter()
-            .for_each(|length| self.extents.push(self.last_extent() + length));
+        let base = self.last_extent();
+        self.extents.extend(
+            other.extents[1..]
+                .iter()
+                .map(|extent| O::from_usize(base + extent.to_usize())),
+        );
         other.extents.truncate(1);
         self.storage.append(&mut other.storage);
         debug_assert!(self.check_invariants());
         debug_assert!(other.check_invariants());
     }
+    /// Append a copy of the contents of another `VarSlicedVec`.
+    ///
+    /// Unlike `append`, `other` is left intact. Storage and
+    /// extents are each reserved once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sliced::*;
+    /// let mut a = varslicedvec![[1, 2], [3]];
+    /// let b = varslicedvec![[4], [5, 6]];
+    /// a.extend_from_other(&b);
+    /// assert_eq!(a.lengths(), vec![2, 1, 1, 2]);
+    /// assert_eq!(b.len(), 2);
+    /// ```
+    pub fn extend_from_other(&mut self, other: &Self) {
+        let base = self.last_extent();
+        check_offset::<O>(base + other.storage.len());
+        self.storage.extend_from_slice(&other.storage);
+        self.extents.extend(
+            other.extents[1..]
+                .iter()
+                .map(|extent| O::from_usize(base + extent.to_usize())),
+        );
+        debug_assert!(self.check_invariants());
+    }
+    /// Append the segments of a `SlicedVec`.
+    ///
+    /// Each fixed length segment becomes one segment here.
+    /// The storage is moved with a single append and
+    /// `other` is drained after call.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = varslicedvec![[1]];
+    /// let mut sv = slicedvec![[2, 3], [4, 5]];
+    /// vv.append_sliced(&mut sv);
+    /// assert_eq!(vv.lengths(), vec![1, 2, 2]);
+    /// assert_eq!(vv[2], [4, 5]);
+    /// assert!(sv.is_empty());
+    /// ```
+    pub fn append_sliced(&mut self, other: &mut SlicedVec<T>) {
+        let base = self.last_extent();
+        let segment_len = other.segment_len();
+        check_offset::<O>(base + other.storage.len());
+        self.extents
+            .extend((1..=other.len()).map(|i| O::from_usize(base + i * segment_len)));
+        self.storage.append(&mut other.storage);
+        debug_assert!(self.check_invariants());
+    }
     /// Add a segments to the end.
     ///
     /// Complexity is amortized the segment size.
@@ -98,7 +249,8 @@ where
     /// ```
     ///
     pub fn push(&mut self, segment: &[T]) {
-        self.extents.push(self.last_extent() + segment.len());
+        let extent = self.last_extent() + segment.len();
+        self.extents.push(O::from_usize(extent));
         self.storage.extend_from_slice(segment);
         debug_assert!(self.check_invariants());
     }
@@ -119,6 +271,40 @@ where
     pub fn push_vec(&mut self, segment: Vec<T>) {
         self.push(segment.as_slice())
     }
+    /// Add a segment by draining an iterator.
+    ///
+    /// Elements are written directly into the storage
+    /// without an intermediate buffer. Returns the length
+    /// of the new segment. If the iterator panics or the new
+    /// extent overflows the offset type, the written elements
+    /// are removed before the panic propagates.
+    /// # Example
+    ///
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = VarSlicedVec::new();
+    /// assert_eq!(vv.push_from_iter((1..=4).map(|x| x * x)), 4);
+    /// assert_eq!(vv.push_from_iter(None), 0);
+    /// assert_eq!(vv[0], [1, 4, 9, 16]);
+    /// assert_eq!(vv.len(), 2);
+    /// ```
+    pub fn push_from_iter<I>(&mut self, iter: I) -> usize
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let begin = self.last_extent();
+        let mut rollback = Rollback {
+            storage: &mut self.storage,
+            len: begin,
+        };
+        rollback.storage.extend(iter);
+        let extent = rollback.storage.len();
+        self.extents.push(O::from_usize(extent));
+        rollback.len = extent;
+        drop(rollback);
+        debug_assert!(self.check_invariants());
+        extent - begin
+    }
     /// Pop and return last segment.
     ///
     /// Returns `None` if empty.
@@ -162,17 +348,18 @@ where
     /// ```
     pub fn split_off(&mut self, at: usize) -> Self {
         debug_assert!(self.check_invariants());
+        let begin = self.storage_begin(at);
         Self {
-            storage: self.storage.split_off(self.storage_begin(at)),
-            extents: [0]
+            storage: self.storage.split_off(begin),
+            extents: [O::from_usize(0)]
                 .into_iter()
                 .chain(
                     self.extents
                         .split_off(at + 1)
                         .into_iter()
-                        .map(|extent| extent - self.storage_begin(at)),
+                        .map(|extent| O::from_usize(extent.to_usize() - begin)),
                 )
-                .collect::<Vec<usize>>(),
+                .collect::<Vec<O>>(),
         }
     }
     /// Insert a segment into the container.
@@ -193,6 +380,42 @@ where
         self.append(&mut back);
         debug_assert!(self.check_invariants());
     }
+    /// Insert several segments starting at `at`.
+    ///
+    /// The new segments are staged in a buffer and the tail
+    /// of the storage and extents is shifted only once.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = varslicedvec![[1], [5, 6]];
+    /// vv.insert_many(1, [[2].as_slice(), &[3, 4], &[]]);
+    /// assert_eq!(vv, varslicedvec![[1], [2], [3, 4], [], [5, 6]]);
+    /// ```
+    /// # Panics
+    /// If `at` is greater than the number of segments.
+    pub fn insert_many<'s, I>(&mut self, at: usize, segments: I)
+    where
+        I: IntoIterator<Item = &'s [T]>,
+        T: 's,
+    {
+        assert!(at <= self.len());
+        let begin = self.storage_begin(at);
+        let mut staged = Vec::new();
+        let mut extents = Vec::new();
+        for segment in segments {
+            staged.extend_from_slice(segment);
+            extents.push(O::from_usize(begin + staged.len()));
+        }
+        let added = staged.len();
+        check_offset::<O>(self.last_extent() + added);
+        self.storage.splice(begin..begin, staged);
+        self.extents[at + 1..]
+            .iter_mut()
+            .for_each(|extent| *extent = O::from_usize(extent.to_usize() + added));
+        self.extents.splice(at + 1..at + 1, extents);
+        debug_assert!(self.check_invariants());
+    }
     /// Remove and return a segment.
     ///
     /// # Example
@@ -202,19 +425,295 @@ where
     /// assert_eq!(vv.remove(1), [2, 3]);
     /// assert_eq!(vv[1], [4, 5, 6]);
     /// ```
+    /// # Panics
+    /// If `index` is out of range.
     pub fn remove(&mut self, index: usize) -> Vec<T> {
+        let mut segment = Vec::with_capacity(self.segment_len(index));
+        self.remove_into(index, &mut segment);
+        segment
+    }
+    /// Remove a segment and copy it into `buf`.
+    ///
+    /// `buf` is cleared first. Returns the length of the
+    /// removed segment. No allocation occurs if `buf` has
+    /// sufficient capacity.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = varslicedvec![[1], [2, 3], [4, 5, 6]];
+    /// let mut buf = Vec::with_capacity(3);
+    /// assert_eq!(vv.remove_into(1, &mut buf), 2);
+    /// assert_eq!(buf, [2, 3]);
+    /// assert_eq!(vv.lengths(), vec![1, 3]);
+    /// ```
+    /// # Panics
+    /// If `index` is out of range.
+    pub fn remove_into(&mut self, index: usize, buf: &mut Vec<T>) -> usize {
         assert!(index < self.len());
-        // Safety: index is in range
-        unsafe {
-            if index == self.len() - 1 {
-                self.pop_unchecked()
-            } else {
-                let mut back = self.split_off(index + 1);
-                let segment = self.pop_unchecked();
-                self.append(&mut back);
-                segment
+        let range = self.storage_range(index);
+        let len = range.len();
+        buf.clear();
+        buf.extend(self.storage.drain(range));
+        self.extents.remove(index + 1);
+        self.extents[index + 1..]
+            .iter_mut()
+            .for_each(|extent| *extent = O::from_usize(extent.to_usize() - len));
+        debug_assert!(self.check_invariants());
+        len
+    }
+    /// Pop the last segment and copy it into `buf`.
+    ///
+    /// `buf` is cleared first. Returns the length of the
+    /// removed segment or `None` if empty.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = varslicedvec![[1], [2, 3]];
+    /// let mut buf = Vec::new();
+    /// while let Some(len) = vv.pop_into(&mut buf) {
+    ///     assert_eq!(buf.len(), len);
+    /// }
+    /// assert_eq!(buf, [1]);
+    /// ```
+    pub fn pop_into(&mut self, buf: &mut Vec<T>) -> Option<usize> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove_into(self.len() - 1, buf))
+        }
+    }
+    /// Pop and discard the last segment.
+    ///
+    /// Returns `false` if empty.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = varslicedvec![[1], [2, 3]];
+    /// assert!(vv.pop_drop());
+    /// assert_eq!(vv.lengths(), vec![1]);
+    /// assert!(vv.pop_drop());
+    /// assert!(!vv.pop_drop());
+    /// ```
+    pub fn pop_drop(&mut self) -> bool {
+        if self.is_empty() {
+            false
+        } else {
+            self.extents.pop();
+            let extent = self.last_extent();
+            self.storage.truncate(extent);
+            debug_assert!(self.check_invariants());
+            true
+        }
+    }
+    /// Split a segment into two at `offset`.
+    ///
+    /// The first `offset` elements of segment `index` remain
+    /// at `index` and the rest become a new segment at
+    /// `index + 1`. Only the extents are edited; the storage
+    /// is not touched.
+    ///
+    /// Complexity is linear in the segments after `index`;
+    /// no data is moved. Extents are stored as one contiguous
+    /// offset array, so inserting a boundary cannot be O(1).
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = varslicedvec![[1, 2, 3, 4], [5]];
+    /// vv.split_segment_at(0, 1);
+    /// assert_eq!(vv.lengths(), vec![1, 3, 1]);
+    /// assert_eq!(vv[1], [2, 3, 4]);
+    /// vv.split_segment_at(2, 1);
+    /// assert_eq!(vv.lengths(), vec![1, 3, 1, 0]);
+    /// ```
+    /// # Panics
+    /// If `index` is out of range or `offset` is
+    /// greater than the length of the segment.
+    pub fn split_segment_at(&mut self, index: usize, offset: usize) {
+        assert!(index < self.len());
+        assert!(offset <= self.segment_len(index));
+        let extent = self.storage_begin(index) + offset;
+        self.extents.insert(index + 1, O::from_usize(extent));
+        debug_assert!(self.check_invariants());
+    }
+    /// Merge segment `index` with its successor.
+    ///
+    /// Only the extents are edited; the storage
+    /// is not touched.
+    ///
+    /// Complexity is linear in the segments after `index`;
+    /// no data is moved. Extents are stored as one contiguous
+    /// offset array, so removing a boundary cannot be O(1).
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = varslicedvec![[1], [2, 3], [4, 5, 6]];
+    /// vv.merge_segments(1);
+    /// assert_eq!(vv.len(), 2);
+    /// assert_eq!(vv[1], [2, 3, 4, 5, 6]);
+    /// vv.merge_segments(0);
+    /// assert_eq!(vv[0], [1, 2, 3, 4, 5, 6]);
+    /// ```
+    /// # Panics
+    /// If `index + 1` is out of range.
+    pub fn merge_segments(&mut self, index: usize) {
+        assert!(index + 1 < self.len());
+        self.extents.remove(index + 1);
+        debug_assert!(self.check_invariants());
+    }
+    /// Merge neighbouring segments while a predicate holds.
+    ///
+    /// Segments are visited from first to last. `pred` receives
+    /// the length of the segment built so far and the length of
+    /// the next one, and returns `true` to merge them. Only the
+    /// extents are edited, in a single pass.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = varslicedvec![[1], [2], [3, 4, 5], [6], [7]];
+    /// vv.coalesce_by(|a, b| a + b <= 3);
+    /// assert_eq!(vv.lengths(), vec![2, 3, 2]);
+    /// ```
+    pub fn coalesce_by<F>(&mut self, mut pred: F)
+    where
+        F: FnMut(usize, usize) -> bool,
+    {
+        let len = self.len();
+        let mut write = 1;
+        for read in 1..len {
+            let begin = self.extents[write - 1].to_usize();
+            let boundary = self.extents[read].to_usize();
+            let end = self.extents[read + 1].to_usize();
+            if !pred(boundary - begin, end - boundary) {
+                self.extents[write] = self.extents[read];
+                write += 1;
             }
         }
+        if len > 0 {
+            self.extents[write] = self.extents[len];
+            self.extents.truncate(write + 1);
+        }
+        debug_assert!(self.check_invariants());
+    }
+    /// Merge each segment shorter than `min_len` with its successor.
+    ///
+    /// Afterwards every segment except possibly the last
+    /// has at least `min_len` elements.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = varslicedvec![[1], [], [2, 3], [4, 5, 6], [7]];
+    /// vv.coalesce_smaller_than(2);
+    /// assert_eq!(vv, varslicedvec![[1, 2, 3], [4, 5, 6], [7]]);
+    /// ```
+    pub fn coalesce_smaller_than(&mut self, min_len: usize) {
+        self.coalesce_by(|len, _| len < min_len)
+    }
+    /// Rotate segments so that segment `mid` becomes the first.
+    ///
+    /// The storage is rotated in place in a single pass.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = varslicedvec![[1], [2, 3], [4, 5, 6]];
+    /// vv.rotate_left(1);
+    /// assert_eq!(vv, varslicedvec![[2, 3], [4, 5, 6], [1]]);
+    /// ```
+    /// # Panics
+    /// If `mid` is greater than the number of segments.
+    pub fn rotate_left(&mut self, mid: usize) {
+        assert!(mid <= self.len());
+        let pivot = self.storage_begin(mid);
+        let tail = self.storage.len() - pivot;
+        self.storage.rotate_left(pivot);
+        let mut extents = Vec::with_capacity(self.extents.len());
+        extents.extend(
+            self.extents[mid..]
+                .iter()
+                .map(|extent| O::from_usize(extent.to_usize() - pivot)),
+        );
+        extents.extend(
+            self.extents[1..=mid]
+                .iter()
+                .map(|extent| O::from_usize(extent.to_usize() + tail)),
+        );
+        self.extents = extents;
+        debug_assert!(self.check_invariants());
+    }
+    /// Rotate segments so that the last `k` become the first.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = varslicedvec![[1], [2, 3], [4, 5, 6]];
+    /// vv.rotate_right(1);
+    /// assert_eq!(vv, varslicedvec![[4, 5, 6], [1], [2, 3]]);
+    /// ```
+    /// # Panics
+    /// If `k` is greater than the number of segments.
+    pub fn rotate_right(&mut self, k: usize) {
+        assert!(k <= self.len());
+        self.rotate_left(self.len() - k)
+    }
+    /// Reverse the order of the segments.
+    ///
+    /// Elements within each segment keep their order. The
+    /// storage is copied once into a new buffer.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = varslicedvec![[1], [2, 3], [4, 5, 6]];
+    /// vv.reverse();
+    /// assert_eq!(vv, varslicedvec![[4, 5, 6], [2, 3], [1]]);
+    /// ```
+    pub fn reverse(&mut self) {
+        let mut storage = Vec::with_capacity(self.storage.len());
+        let mut extents = Vec::with_capacity(self.extents.len());
+        extents.push(O::from_usize(0));
+        for segment in self.as_slice().iter().rev() {
+            storage.extend_from_slice(segment);
+            extents.push(O::from_usize(storage.len()));
+        }
+        self.storage = storage;
+        self.extents = extents;
+        debug_assert!(self.check_invariants());
+    }
+    /// Copy the segments into nested vectors.
+    pub fn to_nested(&self) -> Vec<Vec<T>> {
+        self.iter().map(|segment| segment.to_vec()).collect()
+    }
+    /// Copy the segments at `indices` into a new `VarSlicedVec`.
+    ///
+    /// Segments appear in the order given and may repeat.
+    /// Storage and extents are each allocated exactly once.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1], [2, 3], [4, 5, 6]];
+    /// let picked = vv.select(&[2, 0, 2]);
+    /// assert_eq!(picked, varslicedvec![[4, 5, 6], [1], [4, 5, 6]]);
+    /// ```
+    /// # Panics
+    /// If any index is out of range.
+    pub fn select(&self, indices: &[usize]) -> Self {
+        let total = indices.iter().map(|&index| self[index].len()).sum();
+        let mut storage = Vec::with_capacity(total);
+        let mut extents = Vec::with_capacity(indices.len() + 1);
+        extents.push(O::from_usize(0));
+        for &index in indices {
+            storage.extend_from_slice(&self[index]);
+            extents.push(O::from_usize(storage.len()));
+        }
+        Self { storage, extents }
     }
     /// Get a reference to a segment.
     ///
@@ -233,10 +732,7 @@ where
         debug_assert!(self.check_invariants());
         if index < self.len() {
             // Safety: index range is checked
-            unsafe {
-                let range = self.storage_range_unchecked(index);
-                Some(self.storage.get_unchecked(range))
-            }
+            unsafe { Some(self.get_unchecked(index)) }
         } else {
             None
         }
@@ -248,37 +744,109 @@ where
         debug_assert!(self.check_invariants());
         if index < self.len() {
             // Safety: index range is checked
-            unsafe {
-                let range = self.storage_range_unchecked(index);
-                Some(self.storage.get_unchecked_mut(range))
-            }
+            unsafe { Some(self.get_unchecked_mut(index)) }
         } else {
             None
         }
     }
+    /// Get a reference to a segment without bounds checking.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1, 2, 3], [4, 5], [6]];
+    /// // Safety: vv has three segments
+    /// assert_eq!(unsafe { vv.get_unchecked(1) }, [4, 5]);
+    /// ```
+    /// # Safety
+    /// `index` must be less than `len()`. Calling this
+    /// with an out-of-range index is undefined behavior
+    /// even if the result is not used.
+    pub unsafe fn get_unchecked(&self, index: usize) -> &[T] {
+        debug_assert!(index < self.len());
+        let range = self.storage_range_unchecked(index);
+        self.storage.get_unchecked(range)
+    }
+    /// Get a mutable reference to a segment without bounds checking.
+    ///
+    /// # Safety
+    /// `index` must be less than `len()`. Calling this
+    /// with an out-of-range index is undefined behavior
+    /// even if the result is not used.
+    pub unsafe fn get_unchecked_mut(&mut self, index: usize) -> &mut [T] {
+        debug_assert!(index < self.len());
+        let range = self.storage_range_unchecked(index);
+        self.storage.get_unchecked_mut(range)
+    }
     /// Get a reference to the first segment.
     ///
-    /// Returns `None` if `index` is out of range.
+    /// Returns `None` if empty.
     pub fn first(&self) -> Option<&[T]> {
         self.get(0)
     }
     /// Get a mutable reference to the first segment.
     ///
-    /// Returns `None` if `index` is out of range.
+    /// Returns `None` if empty.
     pub fn first_mut(&mut self) -> Option<&mut [T]> {
         self.get_mut(0)
     }
     /// Get a reference to the last segment.
     ///
-    /// Returns `None` if `index` is out of range.
+    /// Returns `None` if empty.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = varslicedvec![[1], [2, 3]];
+    /// assert_eq!(vv.last(), Some([2, 3].as_slice()));
+    /// vv.clear();
+    /// assert_eq!(vv.first(), None);
+    /// assert_eq!(vv.last(), None);
+    /// assert_eq!(vv.last_mut(), None);
+    /// ```
     pub fn last(&self) -> Option<&[T]> {
-        self.get(self.len() - 1)
+        self.get(self.len().checked_sub(1)?)
     }
     /// Get a mutable reference to the last segment.
     ///
-    /// Returns `None` if `index` is out of range.
+    /// Returns `None` if empty.
     pub fn last_mut(&mut self) -> Option<&mut [T]> {
-        self.get_mut(self.len() - 1)
+        self.get_mut(self.len().checked_sub(1)?)
+    }
+    /// Remove and return the first segment.
+    ///
+    /// Returns `None` if empty. Complexity is linear
+    /// in the storage length.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = varslicedvec![[1], [2, 3]];
+    /// assert_eq!(vv.pop_first(), Some(vec![1]));
+    /// assert_eq!(vv.pop_first(), Some(vec![2, 3]));
+    /// assert_eq!(vv.pop_first(), None);
+    /// ```
+    pub fn pop_first(&mut self) -> Option<Vec<T>> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+    /// Remove and return the last segment.
+    ///
+    /// Returns `None` if empty. This is the same as `pop`.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = varslicedvec![[1], [2, 3]];
+    /// assert_eq!(vv.pop_last(), Some(vec![2, 3]));
+    /// assert_eq!(vv.pop_last(), Some(vec![1]));
+    /// assert_eq!(vv.pop_last(), None);
+    /// ```
+    pub fn pop_last(&mut self) -> Option<Vec<T>> {
+        self.pop()
     }
     /// Get the segment length at `index`.
     ///
@@ -292,7 +860,7 @@ where
     /// ```
     pub fn segment_len(&self, index: usize) -> usize {
         if index < self.len() {
-            self.extents[index + 1] - self.extents[index]
+            self.storage_end(index) - self.storage_begin(index)
         } else {
             0
         }
@@ -306,7 +874,93 @@ where
     /// assert_eq!(vv.lengths(), vec![2, 4]);
     /// ```
     pub fn lengths(&self) -> Vec<usize> {
-        self.extents.windows(2).map(|x| x[1] - x[0]).collect()
+        self.lengths_iter().collect()
+    }
+    /// Iterate over segment lengths.
+    ///
+    /// Unlike `lengths`, this does not allocate.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1, 2], [3, 4, 5, 6]];
+    /// assert_eq!(vv.lengths_iter().max(), Some(4));
+    /// let lens = vv.lengths_iter();
+    /// assert_eq!(lens.len(), 2);
+    /// assert_eq!(lens.rev().collect::<Vec<_>>(), vec![4, 2]);
+    /// ```
+    #[doc(alias = "segment_lens")]
+    pub fn lengths_iter(
+        &self,
+    ) -> impl ExactSizeIterator<Item = usize> + DoubleEndedIterator + '_ {
+        self.extents
+            .windows(2)
+            .map(|x| x[1].to_usize() - x[0].to_usize())
+    }
+    /// Summarize the segment lengths in one pass.
+    ///
+    /// Returns `None` if empty.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1, 2], [], [3, 4, 5, 6]];
+    /// let stats = vv.length_stats().unwrap();
+    /// assert_eq!((stats.min, stats.max, stats.total), (0, 4, 6));
+    /// assert_eq!(stats.mean, 2.0);
+    /// ```
+    pub fn length_stats(&self) -> Option<LengthStats> {
+        if self.is_empty() {
+            return None;
+        }
+        let (min, max) = self
+            .lengths_iter()
+            .fold((usize::MAX, 0), |(min, max), len| (min.min(len), max.max(len)));
+        let total = self.storage.len();
+        Some(LengthStats {
+            min,
+            max,
+            mean: total as f64 / self.len() as f64,
+            total,
+        })
+    }
+    /// Count segment lengths in buckets of `bucket_width`.
+    ///
+    /// Bucket `i` counts lengths in `i * bucket_width..(i + 1) * bucket_width`.
+    /// The result ends at the bucket holding the longest segment.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1, 2], [], [3, 4, 5, 6], [7]];
+    /// assert_eq!(vv.length_histogram(2), vec![2, 1, 1]);
+    /// ```
+    /// # Panics
+    /// If `bucket_width` is zero.
+    pub fn length_histogram(&self, bucket_width: usize) -> Vec<usize> {
+        assert_ne!(bucket_width, 0);
+        let mut counts = Vec::new();
+        for len in self.lengths_iter() {
+            let bucket = len / bucket_width;
+            if bucket >= counts.len() {
+                counts.resize(bucket + 1, 0);
+            }
+            counts[bucket] += 1;
+        }
+        counts
+    }
+    /// Get the segment offsets.
+    ///
+    /// Segment `i` spans `offsets[i]..offsets[i + 1]` in
+    /// the storage. There is always one more offset than
+    /// there are segments.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1, 2], [3, 4, 5, 6]];
+    /// assert_eq!(vv.offsets(), &[0, 2, 6]);
+    /// ```
+    pub fn offsets(&self) -> &[O] {
+        &self.extents
     }
     /// Returns the number of internal segments.
     ///
@@ -336,6 +990,41 @@ where
     pub fn storage_capacity(&self) -> usize {
         self.storage.capacity()
     }
+    /// Returns the length of the underlying storage.
+    ///
+    /// This is the total number of elements in all segments.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1, 2], [], [3, 4, 5, 6]];
+    /// assert_eq!(vv.storage_len(), 6);
+    /// ```
+    #[doc(alias = "total_len")]
+    pub fn storage_len(&self) -> usize {
+        self.storage.len()
+    }
+    /// Get the number of elements that can be pushed
+    /// before the storage reallocates.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = VarSlicedVec::with_capacity(10);
+    /// vv.push(&[1, 2, 3]);
+    /// assert_eq!(vv.storage_len(), 3);
+    /// assert_eq!(vv.spare_storage_capacity(), 7);
+    /// ```
+    pub fn spare_storage_capacity(&self) -> usize {
+        self.storage.capacity() - self.storage.len()
+    }
+    /// Get the capacity of the extents.
+    ///
+    /// There is one more extent than there are segments,
+    /// so pushing reallocates the extents once `len() + 1`
+    /// reaches this value.
+    pub fn extents_capacity(&self) -> usize {
+        self.extents.capacity()
+    }
     /// Shrink the storage to fit data
     pub fn shrink_to_fit(&mut self) {
         self.storage.shrink_to_fit()
@@ -346,11 +1035,11 @@ where
     }
     /// Get start of segment storage.
     fn storage_begin(&self, index: usize) -> usize {
-        self.extents[index]
+        self.extents[index].to_usize()
     }
     /// Get end of segment storage.
     fn storage_end(&self, index: usize) -> usize {
-        self.extents[index + 1]
+        self.extents[index + 1].to_usize()
     }
     /// Get storage range of index.
     unsafe fn storage_range_unchecked(&self, index: usize) -> Range<usize> {
@@ -360,25 +1049,25 @@ where
     /// Get start of segment storage.
     unsafe fn storage_begin_unchecked(&self, index: usize) -> usize {
         debug_assert!(self.check_invariants());
-        *self.extents.get_unchecked(index)
+        self.extents.get_unchecked(index).to_usize()
     }
     /// Get end of segment storage.
     unsafe fn storage_end_unchecked(&self, index: usize) -> usize {
         debug_assert!(self.check_invariants());
-        *self.extents.get_unchecked(index + 1)
+        self.extents.get_unchecked(index + 1).to_usize()
     }
     /// Get last extent
     fn last_extent(&self) -> usize {
         debug_assert!(!self.extents.is_empty());
         let i = self.extents.len() - 1;
         // Safety: extents is never empty
-        unsafe { *self.extents.get_unchecked(i) }
+        unsafe { self.extents.get_unchecked(i).to_usize() }
     }
     /// Debugging sanity check
-    fn check_invariants(&self) -> bool {
+    pub(crate) fn check_invariants(&self) -> bool {
         (!self.extents.is_empty())
-            && self.extents[0] == 0
-            && self.extents.last().unwrap() == &self.storage.len()
+            && self.extents[0].to_usize() == 0
+            && self.extents.last().unwrap().to_usize() == self.storage.len()
             && self.extents_are_monotonic()
     }
     /// Extents must not decrease
@@ -392,6 +1081,123 @@ where
             true
         }
     }
+    /// Find the segment containing a flat storage position.
+    ///
+    /// Returns `None` if `position` is not less than the
+    /// storage length. Complexity is logarithmic in the number
+    /// of segments. See `build_position_index` for repeated
+    /// lookups.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1, 2], [], [3, 4, 5]];
+    /// assert_eq!(vv.segment_containing(1), Some(0));
+    /// assert_eq!(vv.segment_containing(2), Some(2));
+    /// assert_eq!(vv.segment_containing(5), None);
+    /// ```
+    pub fn segment_containing(&self, position: usize) -> Option<usize> {
+        if position < self.storage.len() {
+            Some(
+                self.extents
+                    .partition_point(|extent| extent.to_usize() <= position)
+                    - 1,
+            )
+        } else {
+            None
+        }
+    }
+    /// Build an index for fast flat position lookups.
+    ///
+    /// The index uses roughly one word for every eight
+    /// segments. A lookup binary searches the segments within
+    /// one sample block, which holds about eight segments when
+    /// lengths are even; in the worst case, with skewed lengths,
+    /// one block holds every segment and a lookup costs the same
+    /// as `segment_containing` on the vector.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv: VarSlicedVec<u32> = (0..1000).map(|n| 0..n % 7).collect();
+    /// let index = vv.build_position_index();
+    /// let total = vv.lengths_iter().sum();
+    /// for position in 0..total {
+    ///     assert_eq!(index.segment_containing(position), vv.segment_containing(position));
+    /// }
+    /// ```
+    pub fn build_position_index(&self) -> PositionIndex<'_, T, O> {
+        PositionIndex::new(self)
+    }
+    /// Borrow the contents as a `VarSlicedSlice`.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1], [2, 3], [4, 5, 6]];
+    /// let vs = vv.as_slice();
+    /// assert_eq!(vs.len(), 3);
+    /// assert_eq!(vs[2], [4, 5, 6]);
+    /// ```
+    pub fn as_slice(&self) -> VarSlicedSlice<'_, T, O> {
+        VarSlicedSlice {
+            storage: &self.storage,
+            extents: &self.extents,
+        }
+    }
+    /// Borrow the contents as a `VarSlicedSliceMut`.
+    pub fn as_mut_slice(&mut self) -> VarSlicedSliceMut<'_, T, O> {
+        VarSlicedSliceMut {
+            storage: &mut self.storage,
+            extents: &self.extents,
+            base: 0,
+        }
+    }
+    /// Split into two mutable views at a segment boundary.
+    ///
+    /// The first view holds segments `0..mid` and the second
+    /// holds segments `mid..len()`. Their storage does not
+    /// overlap, so both may be mutated at once.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = varslicedvec![[1], [2, 3], [4, 5, 6]];
+    /// let (mut a, mut b) = vv.split_at_mut(1);
+    /// std::thread::scope(|s| {
+    ///     s.spawn(|| a[0][0] = 10);
+    ///     s.spawn(|| b.iter_mut().for_each(|seg| seg.reverse()));
+    /// });
+    /// assert_eq!(vv, varslicedvec![[10], [3, 2], [6, 5, 4]]);
+    /// ```
+    /// # Panics
+    /// If `mid` is greater than the number of segments.
+    pub fn split_at_mut(
+        &mut self,
+        mid: usize,
+    ) -> (VarSlicedSliceMut<'_, T, O>, VarSlicedSliceMut<'_, T, O>) {
+        self.as_mut_slice().split_at_mut(mid)
+    }
+    /// Borrow a contiguous range of segments as a `VarSlicedSlice`.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1], [2, 3], [4, 5, 6], [7]];
+    /// let vs = vv.subrange(1..3);
+    /// assert_eq!(vs.len(), 2);
+    /// assert_eq!(vs[1], [4, 5, 6]);
+    /// assert_eq!(vv[1..3], [2, 3, 4, 5, 6]);
+    /// ```
+    /// # Panics
+    /// If the range is out of bounds or decreasing.
+    pub fn subrange(&self, range: Range<usize>) -> VarSlicedSlice<'_, T, O> {
+        assert!(range.start <= range.end);
+        VarSlicedSlice {
+            storage: &self.storage,
+            extents: &self.extents[range.start..=range.end],
+        }
+    }
     /// Return iterator over slices
     ///
     /// # Example
@@ -404,14 +1210,36 @@ where
     /// let lens = vv.iter().map(|slice| slice.len()).collect::<Vec<usize>>();
     /// assert_eq!(lens, vec![1, 2, 3, 2, 1]);
     /// ```
-    pub fn iter(&self) -> VarSlicedVecIter<T> {
+    pub fn iter(&self) -> VarSlicedVecIter<'_, T, O> {
         VarSlicedVecIter { data: self, i: 0 }
     }
+    /// Iterate over elements with their segment positions.
+    ///
+    /// Yields `(segment_index, offset_in_segment, &value)`
+    /// for every element of the storage in order. Empty
+    /// segments yield nothing.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1, 2], [], [3]];
+    /// let flat = vv.enumerate_flat().collect::<Vec<_>>();
+    /// assert_eq!(flat, vec![(0, 0, &1), (0, 1, &2), (2, 0, &3)]);
+    /// ```
+    pub fn enumerate_flat(&self) -> impl Iterator<Item = (usize, usize, &T)> {
+        self.iter().enumerate().flat_map(|(index, segment)| {
+            segment
+                .iter()
+                .enumerate()
+                .map(move |(offset, value)| (index, offset, value))
+        })
+    }
 }
 
-impl<T> Index<usize> for VarSlicedVec<T>
+impl<T, O> Index<usize> for VarSlicedVec<T, O>
 where
     T: Copy + Clone,
+    O: Offset,
 {
     type Output = [T];
     fn index(&self, index: usize) -> &Self::Output {
@@ -424,9 +1252,10 @@ where
     }
 }
 
-impl<T> IndexMut<usize> for VarSlicedVec<T>
+impl<T, O> IndexMut<usize> for VarSlicedVec<T, O>
 where
     T: Copy + Clone,
+    O: Offset,
 {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         assert!(index < self.len());
@@ -438,27 +1267,207 @@ where
     }
 }
 
-impl<T> Default for VarSlicedVec<T>
+/// Index the flat storage covering a range of segments.
+impl<T, O> Index<Range<usize>> for VarSlicedVec<T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    type Output = [T];
+    fn index(&self, range: Range<usize>) -> &Self::Output {
+        assert!(range.start <= range.end && range.end <= self.len());
+        &self.storage[self.storage_begin(range.start)..self.storage_begin(range.end)]
+    }
+}
+
+impl<T, O> Default for VarSlicedVec<T, O>
 where
     T: Copy + Clone,
+    O: Offset,
 {
     fn default() -> Self {
-        Self::new()
+        Self {
+            storage: Vec::new(),
+            extents: vec![O::from_usize(0)],
+        }
+    }
+}
+
+/// Format as a list of segments.
+///
+/// Only the first 32 segments are shown. The alternate
+/// form also shows the offsets.
+/// # Example
+/// ```
+/// use sliced::*;
+/// let vv = varslicedvec![[1, 2], [3]];
+/// assert_eq!(format!("{:?}", vv), "[[1, 2], [3]]");
+/// assert!(format!("{:#?}", vv).contains("offsets"));
+/// let vv: VarSlicedVec<_> = (0..100).map(|n| [n]).collect();
+/// assert!(format!("{:?}", vv).ends_with("[31], ...68 more]"));
+/// ```
+impl<T, O> Debug for VarSlicedVec<T, O>
+where
+    T: Copy + Clone + Debug,
+    O: Offset,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.debug_struct("VarSlicedVec")
+                .field("segments", &DebugElided(self.iter(), self.len()))
+                .field(
+                    "offsets",
+                    &DebugElided(self.extents.iter(), self.extents.len()),
+                )
+                .finish()
+        } else {
+            DebugElided(self.iter(), self.len()).fmt(f)
+        }
+    }
+}
+
+/// Summary of segment lengths returned by `length_stats`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LengthStats {
+    /// Length of the shortest segment
+    pub min: usize,
+    /// Length of the longest segment
+    pub max: usize,
+    /// Mean segment length
+    pub mean: f64,
+    /// Total number of elements
+    pub total: usize,
+}
+
+// Debug list showing at most DEBUG_SEGMENTS items
+struct DebugElided<I>(I, usize);
+
+impl<I> Debug for DebugElided<I>
+where
+    I: Iterator + Clone,
+    I::Item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        list.entries(self.0.clone().take(DEBUG_SEGMENTS));
+        if self.1 > DEBUG_SEGMENTS {
+            list.entry(&format_args!("...{} more", self.1 - DEBUG_SEGMENTS));
+        }
+        list.finish()
+    }
+}
+
+/// Convert nested vectors into a `VarSlicedVec`.
+///
+/// Storage and extents are each allocated exactly once.
+/// # Example
+/// ```
+/// use sliced::*;
+/// let vv: VarSlicedVec<i32> = vec![vec![1, 2], vec![], vec![3]].into();
+/// assert_eq!(vv, varslicedvec![[1, 2], [], [3]]);
+/// assert_eq!(vv.to_nested(), vec![vec![1, 2], vec![], vec![3]]);
+/// ```
+impl<T, O> From<Vec<Vec<T>>> for VarSlicedVec<T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+{
+    fn from(nested: Vec<Vec<T>>) -> Self {
+        let mut storage = Vec::with_capacity(nested.iter().map(Vec::len).sum());
+        let mut extents = Vec::with_capacity(nested.len() + 1);
+        extents.push(O::from_usize(0));
+        for segment in nested {
+            storage.extend_from_slice(&segment);
+            extents.push(O::from_usize(storage.len()));
+        }
+        Self { storage, extents }
+    }
+}
+
+/// Extend with segments.
+///
+/// Each item of the iterator becomes one segment. Items
+/// may be vectors, slices or any other iterable.
+/// # Example
+/// ```
+/// use sliced::*;
+/// let mut vv = varslicedvec![[1, 2]];
+/// vv.extend([vec![3], vec![4, 5, 6]]);
+/// vv.extend([[7, 8].as_slice()]);
+/// vv.extend((0..3).map(|n| 0..n));
+/// assert_eq!(vv.lengths(), vec![2, 1, 3, 2, 0, 1, 2]);
+/// ```
+impl<T, O, I> Extend<I> for VarSlicedVec<T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+    I: IntoIterator,
+    I::Item: Borrow<T>,
+{
+    fn extend<J: IntoIterator<Item = I>>(&mut self, iter: J) {
+        iter.into_iter().for_each(|segment| {
+            self.push_from_iter(segment.into_iter().map(|value| *value.borrow()));
+        });
+    }
+}
+
+/// Collect segments into a `VarSlicedVec`.
+///
+/// # Example
+/// ```
+/// use sliced::*;
+/// let records = vec![vec![1, 2, 3], vec![], vec![4]];
+/// let vv = records.into_iter().collect::<VarSlicedVec<_>>();
+/// assert_eq!(vv.lengths(), vec![3, 0, 1]);
+/// ```
+impl<T, O, I> FromIterator<I> for VarSlicedVec<T, O>
+where
+    T: Copy + Clone,
+    O: Offset,
+    I: IntoIterator,
+    I::Item: Borrow<T>,
+{
+    fn from_iter<J: IntoIterator<Item = I>>(iter: J) -> Self {
+        let mut vv = Self::default();
+        vv.extend(iter);
+        vv
+    }
+}
+
+// Panic before anything is modified if `extent`
+// cannot be represented as an offset
+fn check_offset<O: Offset>(extent: usize) {
+    O::from_usize(extent);
+}
+
+// Truncate the storage back to `len` when dropped, so that
+// a panic while appending leaves no elements without extents
+struct Rollback<'a, T> {
+    storage: &'a mut Vec<T>,
+    len: usize,
+}
+
+impl<'a, T> Drop for Rollback<'a, T> {
+    fn drop(&mut self) {
+        self.storage.truncate(self.len);
     }
 }
 
 /// Iterator over slices
-pub struct VarSlicedVecIter<'a, T>
+#[derive(Clone)]
+pub struct VarSlicedVecIter<'a, T, O = usize>
 where
     T: Copy + Clone,
+    O: Offset,
 {
-    data: &'a VarSlicedVec<T>,
+    data: &'a VarSlicedVec<T, O>,
     i: usize,
 }
 
-impl<'a, T> Iterator for VarSlicedVecIter<'a, T>
+impl<'a, T, O> Iterator for VarSlicedVecIter<'a, T, O>
 where
     T: Copy + Clone,
+    O: Offset,
 {
     type Item = &'a [T];
     fn next(&mut self) -> Option<Self::Item> {
@@ -518,9 +1527,17 @@ where T: Copy + Clone
 /// y.push(&[1, 2]);
 /// assert_eq!(y.len(), 1);
 /// ```
+/// Segments may also be collected from an iterator.
+/// ```
+/// use sliced::*;
+/// let z = varslicedvec![from (1..4).map(|n| vec![n; n])];
+/// assert_eq!(z.lengths(), vec![1, 2, 3]);
+/// assert_eq!(z[2], [3, 3, 3]);
+/// ```
 #[macro_export]
 macro_rules! varslicedvec {
     () => { VarSlicedVec::new() };
+    ( from $iter:expr ) => { VarSlicedVec::<_>::from_iter($iter) };
     ( $first:expr$(, $the_rest:expr )*$(,)? ) => {
         {
             let mut temp_vec = VarSlicedVec::new();
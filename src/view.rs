@@ -0,0 +1,165 @@
+use std::{ops::Index, slice::ChunksExact};
+
+use crate::{
+    format::{
+        byte_range, cast_slice, FormatError, Header, Pod, HEADER_LEN, SLICED_MAGIC, VARSLICED_MAGIC,
+    },
+    slicedvec::SlicedVec,
+    varslicedslice::VarSlicedSlice,
+};
+
+/// A read-only view of variable length segments stored as bytes.
+///
+/// The view borrows the offsets and storage directly from
+/// the buffer, so it works over a memory-mapped file without
+/// copying. Use `VarSlicedVec::to_bytes` to produce the buffer.
+pub type VarSlicedView<'a, T> = VarSlicedSlice<'a, T, u64>;
+
+impl<'a, T> VarSlicedSlice<'a, T, u64>
+where
+    T: Copy + Clone + Pod,
+{
+    /// Validate a buffer and view its segments without copying.
+    ///
+    /// The buffer must start with a header and be aligned for
+    /// both `u64` and `T`. Offsets must start at zero, never
+    /// decrease and end at the storage length.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let vv = varslicedvec![[1u32, 2], [], [3, 4, 5]];
+    /// let bytes = vv.to_bytes();
+    /// let view = VarSlicedView::<u32>::from_bytes(&bytes).unwrap();
+    /// assert_eq!(view.len(), 3);
+    /// assert_eq!(view[2], [3, 4, 5]);
+    /// assert_eq!(
+    ///     VarSlicedView::<u16>::from_bytes(&bytes).unwrap_err(),
+    ///     FormatError::ElementSize(4)
+    /// );
+    /// assert_eq!(
+    ///     VarSlicedView::<u32>::from_bytes(&bytes[..30]).unwrap_err(),
+    ///     FormatError::Truncated
+    /// );
+    /// ```
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, FormatError> {
+        let header = Header::read::<T>(bytes, VARSLICED_MAGIC)?;
+        let [count, storage_len] = header.params;
+        let n_offsets = count.checked_add(1).ok_or(FormatError::Truncated)?;
+        let offsets = byte_range::<u64>(HEADER_LEN, n_offsets, bytes.len())?;
+        let data = byte_range::<T>(offsets.end, storage_len, bytes.len())?;
+        if data.end != bytes.len() {
+            return Err(FormatError::Invalid);
+        }
+        let extents = cast_slice::<u64>(&bytes[offsets])?;
+        let storage = cast_slice::<T>(&bytes[data])?;
+        if extents[0] != 0
+            || *extents.last().unwrap() != storage_len
+            || extents.windows(2).any(|x| x[0] > x[1])
+        {
+            return Err(FormatError::Invalid);
+        }
+        Ok(Self { storage, extents })
+    }
+}
+
+/// A read-only view of fixed length segments stored as bytes.
+///
+/// The `SlicedVec` analogue of `VarSlicedView`.
+/// Use `SlicedVec::to_bytes` to produce the buffer.
+/// ```
+/// use sliced::*;
+/// let sv = slicedvec![[1.0, 2.0], [3.0, 4.0]];
+/// let bytes = sv.to_bytes();
+/// let view = SlicedView::<f64>::from_bytes(&bytes).unwrap();
+/// assert_eq!(view.segment_len(), 2);
+/// assert_eq!(view[1], [3.0, 4.0]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SlicedView<'a, T>
+where
+    T: Copy + Clone,
+{
+    pub(crate) storage: &'a [T],
+    pub(crate) segment_len: usize,
+}
+
+impl<'a, T> SlicedView<'a, T>
+where
+    T: Copy + Clone + Pod,
+{
+    /// Validate a buffer and view its segments without copying.
+    ///
+    /// The buffer must start with a header and be aligned for `T`.
+    ///
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let bytes = slicedvec![[1, 2, 3]].to_bytes();
+    /// assert_eq!(
+    ///     SlicedView::<i32>::from_bytes(&bytes[1..]).unwrap_err(),
+    ///     FormatError::Magic
+    /// );
+    /// ```
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, FormatError> {
+        let header = Header::read::<T>(bytes, SLICED_MAGIC)?;
+        let [segment_len, count] = header.params;
+        if segment_len == 0 {
+            return Err(FormatError::Invalid);
+        }
+        let storage_len = segment_len
+            .checked_mul(count)
+            .ok_or(FormatError::Truncated)?;
+        let data = byte_range::<T>(HEADER_LEN, storage_len, bytes.len())?;
+        if data.end != bytes.len() {
+            return Err(FormatError::Invalid);
+        }
+        Ok(Self {
+            storage: cast_slice::<T>(&bytes[data])?,
+            segment_len: segment_len as usize,
+        })
+    }
+}
+
+impl<'a, T> SlicedView<'a, T>
+where
+    T: Copy + Clone,
+{
+    /// Returns the number of segments.
+    pub fn len(&self) -> usize {
+        self.storage.len() / self.segment_len
+    }
+    /// Test if there are no segments.
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+    /// Returns the length of each segment.
+    pub fn segment_len(&self) -> usize {
+        self.segment_len
+    }
+    /// Get a reference to a segment.
+    ///
+    /// Returns `None` if `index` is out of range.
+    pub fn get(&self, index: usize) -> Option<&'a [T]> {
+        self.storage
+            .get(index * self.segment_len..(index + 1) * self.segment_len)
+    }
+    /// Return iterator over slices.
+    pub fn iter(&self) -> ChunksExact<'a, T> {
+        self.storage.chunks_exact(self.segment_len)
+    }
+    /// Copy the segments into a `SlicedVec`.
+    pub fn to_sliced_vec(&self) -> SlicedVec<T> {
+        SlicedVec::from_vec(self.segment_len, self.storage.to_vec())
+    }
+}
+
+impl<'a, T> Index<usize> for SlicedView<'a, T>
+where
+    T: Copy + Clone,
+{
+    type Output = [T];
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.storage[index * self.segment_len..(index + 1) * self.segment_len]
+    }
+}
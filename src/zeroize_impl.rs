@@ -0,0 +1,272 @@
+use std::ops::{Deref, DerefMut};
+
+use zeroize::{DefaultIsZeroes, Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::{
+    offset::Offset, slicedslab::SlicedSlab, slicedvec::SlicedVec, varslicedvec::VarSlicedVec,
+};
+
+// Scrub storage vacated between the current length and `end`
+fn zeroize_vacated<T: DefaultIsZeroes>(storage: &mut Vec<T>, end: usize) {
+    let len = storage.len();
+    if len < end {
+        // Stays within capacity, so nothing is reallocated
+        storage.resize(end, T::default());
+        storage[len..].zeroize();
+        storage.truncate(len);
+    }
+}
+
+/// Scrub the whole storage and clear, as `clear_zeroize`.
+///
+/// Wrap the vector in `Scrubbed` to scrub it when dropped.
+impl<T> Zeroize for SlicedVec<T>
+where
+    T: Copy + Clone + DefaultIsZeroes,
+{
+    fn zeroize(&mut self) {
+        self.clear_zeroize();
+    }
+}
+
+impl<T> SlicedVec<T>
+where
+    T: Copy + Clone + DefaultIsZeroes,
+{
+    /// Remove the last segment and scrub the storage it occupied.
+    ///
+    /// The returned copy is scrubbed when dropped.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut sv = slicedvec![[1u8, 2], [3, 4]];
+    /// assert_eq!(sv.pop_zeroize().as_deref(), Some(&vec![3, 4]));
+    /// assert_eq!(sv.len(), 1);
+    /// ```
+    pub fn pop_zeroize(&mut self) -> Option<Zeroizing<Vec<T>>> {
+        let end = self.storage.len();
+        let segment = Zeroizing::new(self.pop()?);
+        zeroize_vacated(&mut self.storage, end);
+        Some(segment)
+    }
+    /// Remove a segment by swapping in the last one and scrub the vacated storage.
+    ///
+    /// # Panics
+    /// If index is out of range.
+    pub fn swap_remove_zeroize(&mut self, index: usize) -> Zeroizing<Vec<T>> {
+        let end = self.storage.len();
+        let segment = Zeroizing::new(self.swap_remove(index));
+        zeroize_vacated(&mut self.storage, end);
+        segment
+    }
+    /// Remove a segment, preserving order, and scrub the vacated storage.
+    ///
+    /// Later segments are shifted down, so the scrubbed
+    /// region is at the end of the storage.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut sv = slicedvec![[1u8, 2], [3, 4], [5, 6]];
+    /// assert_eq!(*sv.remove_zeroize(0), [1, 2]);
+    /// assert_eq!(sv[0], [3, 4]);
+    /// ```
+    /// # Panics
+    /// If index is out of range.
+    pub fn remove_zeroize(&mut self, index: usize) -> Zeroizing<Vec<T>> {
+        assert!(index < self.len());
+        let end = self.storage.len();
+        let range = index * self.segment_len()..(index + 1) * self.segment_len();
+        let segment = Zeroizing::new(self.storage.drain(range).collect());
+        zeroize_vacated(&mut self.storage, end);
+        segment
+    }
+    /// Shorten the vector and scrub the storage removed.
+    pub fn truncate_zeroize(&mut self, len: usize) {
+        let end = self.storage.len();
+        self.truncate(len);
+        zeroize_vacated(&mut self.storage, end);
+    }
+    /// Scrub the whole storage, including spare capacity, and clear.
+    ///
+    /// The segment length is kept.
+    pub fn clear_zeroize(&mut self) {
+        self.storage.zeroize();
+    }
+}
+
+/// Scrub the whole storage and clear, as `clear_zeroize`.
+impl<T, O> Zeroize for VarSlicedVec<T, O>
+where
+    T: Copy + Clone + DefaultIsZeroes,
+    O: Offset,
+{
+    fn zeroize(&mut self) {
+        self.clear_zeroize();
+    }
+}
+
+impl<T, O> VarSlicedVec<T, O>
+where
+    T: Copy + Clone + DefaultIsZeroes,
+    O: Offset,
+{
+    /// Remove the last segment and scrub the storage it occupied.
+    ///
+    /// The returned copy is scrubbed when dropped.
+    pub fn pop_zeroize(&mut self) -> Option<Zeroizing<Vec<T>>> {
+        let end = self.storage.len();
+        let segment = Zeroizing::new(self.pop()?);
+        zeroize_vacated(&mut self.storage, end);
+        Some(segment)
+    }
+    /// Remove a segment, preserving order, and scrub the vacated storage.
+    ///
+    /// Later segments are shifted down, so the scrubbed
+    /// region is at the end of the storage.
+    /// # Example
+    /// ```
+    /// use sliced::*;
+    /// let mut vv = varslicedvec![[1u8], [2, 3], [4, 5, 6]];
+    /// assert_eq!(*vv.remove_zeroize(1), [2, 3]);
+    /// assert_eq!(vv[1], [4, 5, 6]);
+    /// ```
+    /// # Panics
+    /// If `index` is out of range.
+    pub fn remove_zeroize(&mut self, index: usize) -> Zeroizing<Vec<T>> {
+        let end = self.storage.len();
+        let segment = Zeroizing::new(self.remove(index));
+        zeroize_vacated(&mut self.storage, end);
+        segment
+    }
+    /// Scrub the whole storage, including spare capacity, and clear.
+    ///
+    /// The extents are cleared but not scrubbed, so
+    /// segment lengths may remain in memory.
+    pub fn clear_zeroize(&mut self) {
+        self.storage.zeroize();
+        self.clear();
+    }
+}
+
+/// Scrub every slot and clear, as `clear_zeroize`.
+impl<T> Zeroize for SlicedSlab<T>
+where
+    T: Copy + Clone + DefaultIsZeroes,
+{
+    fn zeroize(&mut self) {
+        self.clear_zeroize();
+    }
+}
+
+impl<T> SlicedSlab<T>
+where
+    T: Copy + Clone + DefaultIsZeroes,
+{
+    /// Scrub a slot and mark it as open.
+    ///
+    /// Storage vacated by any automatic compaction is
+    /// scrubbed as well. Storage abandoned when the slab
+    /// grows is not, so reserve capacity up front. A copy
+    /// saved for an active snapshot is kept until
+    /// `clear_zeroize` scrubs it.
+    /// # Panics
+    /// If the slot is already marked as available.
+    pub fn release_zeroize(&mut self, key: usize) {
+        self.get_mut(key).expect("slot is not occupied").zeroize();
+        let end = self.slots.storage.len();
+        self.release(key);
+        zeroize_vacated(&mut self.slots.storage, end);
+    }
+    /// Scrub every slot, including spare capacity, and clear.
+    ///
+    /// Any active snapshot is discarded and the
+    /// segments it saved are scrubbed too.
+    pub fn clear_zeroize(&mut self) {
+        if let Some(mut undo) = self.undo.take() {
+            undo.saved.clear_zeroize();
+        }
+        self.slots.clear_zeroize();
+        self.clear();
+    }
+}
+
+/// A container that is scrubbed when dropped.
+///
+/// Dereferences to the wrapped `SlicedVec`, `VarSlicedVec` or
+/// `SlicedSlab`, so it is used like the container itself, and
+/// scrubs its storage, including spare capacity, on drop.
+/// Storage abandoned when the container grows is not
+/// scrubbed, so reserve capacity up front.
+/// # Example
+/// ```
+/// use sliced::*;
+/// let mut keys = Scrubbed::new(SlicedSlab::<u8>::with_capacity(32, 16));
+/// let key = keys.insert(&[7; 32]);
+/// keys.release_zeroize(key);
+/// keys.insert(&[9; 32]);
+/// assert_eq!(keys.occupied_len(), 1);
+/// keys.clear_zeroize();
+/// assert!(keys.is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Scrubbed<C>(C)
+where
+    C: Zeroize;
+
+impl<C> Scrubbed<C>
+where
+    C: Zeroize,
+{
+    /// Wrap a container.
+    pub fn new(container: C) -> Self {
+        Self(container)
+    }
+}
+
+impl<C> From<C> for Scrubbed<C>
+where
+    C: Zeroize,
+{
+    fn from(container: C) -> Self {
+        Self(container)
+    }
+}
+
+impl<C> Deref for Scrubbed<C>
+where
+    C: Zeroize,
+{
+    type Target = C;
+    fn deref(&self) -> &C {
+        &self.0
+    }
+}
+
+impl<C> DerefMut for Scrubbed<C>
+where
+    C: Zeroize,
+{
+    fn deref_mut(&mut self) -> &mut C {
+        &mut self.0
+    }
+}
+
+impl<C> Zeroize for Scrubbed<C>
+where
+    C: Zeroize,
+{
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<C> Drop for Scrubbed<C>
+where
+    C: Zeroize,
+{
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<C> ZeroizeOnDrop for Scrubbed<C> where C: Zeroize {}